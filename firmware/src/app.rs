@@ -1,15 +1,74 @@
-use std::{fmt::Display, time::Instant};
+use std::{
+    fmt::Display,
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
 
 use mousefood::prelude::*;
-use ratatui_fretboard::{note::Note, scale::Scale, FretboardState};
+use ratatui_fretboard::{
+    chord::{Chord, ChordType},
+    error::Error,
+    fingering::FingeringSolver,
+    key::Key,
+    note::{Note, STANDARD_TUNING},
+    pattern::{PatternSystem, ScalePattern},
+    position::FretPosition,
+    scale::Scale,
+    Clef, FretboardMarks, FretboardState, TabState,
+};
 use tachyonfx::{fx, Effect, Interpolation, Motion};
 use tui_big_text::PixelSize;
-use tuitar_core::{fps::FpsWidget, songs::*};
+use tuitar_core::{
+    fps::FpsWidget,
+    game::{Difficulty, HighScores, HuntScope, NoteGame, NoteHunt, Outcome},
+    practice::{Advance, ChordChanges, Direction, Progression, ScaleRun, Sequence, Step, Summary},
+    scope::{Oscilloscope, Trigger, MAX_PERIODS},
+    segment::NoteEvent,
+    songs::*,
+    trainer::{FretboardStats, Trainer},
+};
 
 use crate::{utils, Transform, MAX_ADC_VALUE};
 use tuitar_core::state::State;
 
-pub(crate) const MAX_RANDOM_INTERVAL: u64 = 5000;
+/// How long a note can take to be played in reading mode before it is counted as missed and replaced.
+const NOTE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The highest fret that can be shown on the fretboard.
+const MAX_FRET: u8 = 24;
+
+/// The number of frets shown after the first visible fret.
+const FRET_WINDOW: u8 = 6;
+
+/// How long the circle of fifths is shown after changing the key, in milliseconds.
+pub(crate) const KEY_CIRCLE_DURATION: u64 = 2000;
+
+/// How long the note to hunt is shown in big text before the fretboard, in milliseconds.
+pub(crate) const HUNT_NOTE_DURATION: u64 = 1500;
+
+/// How similar the input has to be to a chord of the progression to recognize it.
+const MIN_CHORD_SIMILARITY: f64 = 0.85;
+
+/// The number of trainer answers after which the statistics are saved.
+const TRAINER_SAVE_INTERVAL: u32 = 10;
+
+/// The number of song beats whose positions are chosen at once.
+const SONG_WINDOW_BEATS: usize = 48;
+
+/// The number of beats before the current one that stay in the song window.
+const SONG_WINDOW_BEHIND: usize = 8;
+
+/// The number of beats after the current one that have to be in the song window.
+pub(crate) const SONG_WINDOW_AHEAD: usize = 16;
+
+/// The number of played beats that stay visible before the cursor of the song tablature.
+pub(crate) const SONG_TAB_LOOKBEHIND: usize = 2;
+
+/// How long a song beat can take to be played before it is skipped, when skipping is on.
+const SONG_BEAT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long each chord is played when the chord changes advance on a timer.
+const CHORD_DURATION: Duration = Duration::from_secs(4);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ButtonPressType {
@@ -92,7 +151,9 @@ pub enum Event {
     SwitchTab,
     SwitchInputMode,
     UpdateControlValue(u16),
-    ToggleRootNote,
+    NextKey,
+    SwitchTrigger,
+    ToggleHold,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -118,6 +179,7 @@ pub enum Tab {
     Frequency,
     Waveform,
     Spectrum,
+    Harmonics,
     Fretboard,
 }
 
@@ -127,6 +189,7 @@ impl Display for Tab {
             Tab::Frequency => "Frequency",
             Tab::Waveform => "Waveform",
             Tab::Spectrum => "Spectrum",
+            Tab::Harmonics => "Harmonics",
             Tab::Fretboard => "Fretboard",
         };
         write!(f, "{name}")
@@ -138,8 +201,14 @@ pub enum FretboardMode {
     #[default]
     Live,
     Scale,
+    Run,
+    Arpeggio,
+    Chords,
     Random,
+    Trainer,
+    Hunt,
     Song,
+    Reading,
 }
 
 impl Display for FretboardMode {
@@ -147,8 +216,14 @@ impl Display for FretboardMode {
         let name = match self {
             FretboardMode::Live => "Live",
             FretboardMode::Scale => "Scale",
+            FretboardMode::Run => "Run",
+            FretboardMode::Arpeggio => "Arpeggio",
+            FretboardMode::Chords => "Chords",
             FretboardMode::Random => "Random",
+            FretboardMode::Trainer => "Trainer",
+            FretboardMode::Hunt => "Hunt",
             FretboardMode::Song => "Song",
+            FretboardMode::Reading => "Reading",
         };
         write!(f, "{name}")
     }
@@ -159,8 +234,14 @@ impl FretboardMode {
         let label = match self {
             FretboardMode::Live => "Live".green(),
             FretboardMode::Scale => "Scale".yellow(),
+            FretboardMode::Run => "Run".yellow(),
+            FretboardMode::Arpeggio => "Arpeggio".blue(),
+            FretboardMode::Chords => "Chords".blue(),
             FretboardMode::Random => "Random".cyan(),
+            FretboardMode::Trainer => "Trainer".cyan(),
+            FretboardMode::Hunt => "Hunt".cyan(),
             FretboardMode::Song => "Song".red(),
+            FretboardMode::Reading => "Reading".magenta(),
         };
         Line::from(vec!["[".gray(), label, "]".gray()])
     }
@@ -176,13 +257,73 @@ pub struct Application {
     pub tab: Tab,
     pub fretboard_mode: FretboardMode,
     pub fretboard_state: FretboardState,
+    /// What is marked on the fretboard besides the active and ghost notes.
+    pub fretboard_marks: FretboardMarks,
     pub remove_ghost: bool,
     pub current_scale: Scale,
-    pub current_root_note: Note,
+    /// The pattern of the current scale that is shown, or `None` for the whole neck.
+    pub scale_pattern: Option<usize>,
+    pub current_key: Key,
+    /// When the key was last changed, to briefly show the circle of fifths.
+    pub key_changed: Option<Instant>,
+    /// The order the current scale is played in in run mode.
+    pub scale_run: ScaleRun,
+    /// The notes of the scale run that have to be played in order.
+    pub run: Sequence,
+    /// The result of the last complete scale run.
+    pub run_summary: Option<Summary>,
+    /// The last wrong note played during the scale run.
+    pub run_mistake: Option<Note>,
+    /// The type of the chord practiced in arpeggio mode, built on the tonic of the current key.
+    pub arpeggio_chord: ChordType,
+    /// The chord tones that have to be played in order.
+    pub arpeggio: Sequence,
+    pub arpeggio_direction: Direction,
+    /// How long the last complete arpeggio took.
+    pub arpeggio_time: Option<Duration>,
+    /// The progressions to choose from in chords mode.
+    pub progressions: Vec<Progression>,
+    /// The index of the progression practiced in chords mode.
+    pub progression: usize,
+    /// The chord changes of the current progression.
+    pub chord_changes: ChordChanges,
     pub current_song_index: usize,
     pub song_note_index: usize,
+    /// The positions of a window of beats of the current song, with the first beat of the window.
+    pub song_fingering: Option<(usize, Vec<Vec<FretPosition>>)>,
+    /// Whether the song is shown as a tablature instead of the fretboard.
+    pub show_song_tab: bool,
+    /// The current and missed beats of the song.
+    pub song_tab: TabState,
+    /// Whether a beat that isn't played in time is skipped and marked as missed,
+    /// instead of waiting for it.
+    pub skip_missed_beats: bool,
+    /// When the current song beat was shown.
+    pub song_beat_started: Instant,
     pub random_mode_points: usize,
-    pub last_random: Instant,
+    /// When the note to read was shown.
+    pub note_shown_at: Instant,
+    /// The random note game.
+    pub game: NoteGame,
+    /// The best scores of the random note game since the device started.
+    pub high_scores: HighScores,
+    /// The rank of the last game in the high scores, if it made it there.
+    pub high_score_rank: Option<usize>,
+    /// The fretboard memorization trainer.
+    pub trainer: Trainer,
+    /// The response time of the last position played in the trainer.
+    pub trainer_response: Option<Duration>,
+    /// The number of trainer answers that were not saved yet.
+    trainer_changes: u32,
+    /// Whether the trainer statistics should be saved right away.
+    trainer_save_requested: bool,
+    /// Where the positions of the note are hunted.
+    pub hunt_scope: HuntScope,
+    /// The current note hunt.
+    pub hunt: Option<NoteHunt>,
+    /// When the current note hunt started, to briefly show the note in big text.
+    pub hunt_started: Option<Instant>,
+    pub oscilloscope: Oscilloscope,
     pub intro_effect: Effect,
     pub menu_effect: Effect,
     pub input_mode_effect: Effect,
@@ -192,7 +333,8 @@ pub struct Application {
 impl Application {
     pub fn new(buffer_size: usize) -> Self {
         let transform = Transform::new();
-        let state = State::new(transform, buffer_size, 6, PixelSize::Quadrant, 2, None);
+        let mut state = State::new(transform, buffer_size, 6, PixelSize::Quadrant, 2);
+        state.input_range = 0..=MAX_ADC_VALUE as i16;
 
         Self {
             is_running: true,
@@ -208,13 +350,43 @@ impl Application {
             tab: Tab::default(),
             fretboard_mode: FretboardMode::Live,
             fretboard_state: FretboardState::default(),
-            current_scale: Scale::MajorPentatonic,
+            fretboard_marks: FretboardMarks::default(),
+            current_scale: Scale::MAJOR_PENTATONIC,
+            scale_pattern: None,
             remove_ghost: true,
-            current_root_note: Note::A(4),
+            current_key: Key::major(Note::A(4)),
+            key_changed: None,
+            scale_run: ScaleRun::default(),
+            run: Sequence::default(),
+            run_summary: None,
+            run_mistake: None,
+            arpeggio_chord: ChordType::default(),
+            arpeggio: Sequence::default(),
+            arpeggio_direction: Direction::default(),
+            arpeggio_time: None,
+            progressions: Progression::all(),
+            progression: 0,
+            chord_changes: ChordChanges::default(),
             current_song_index: 0,
             song_note_index: 0,
+            song_fingering: None,
+            show_song_tab: false,
+            song_tab: TabState::new(),
+            skip_missed_beats: false,
+            song_beat_started: Instant::now(),
             random_mode_points: 0,
-            last_random: Instant::now(),
+            note_shown_at: Instant::now(),
+            game: NoteGame::default(),
+            high_scores: HighScores::default(),
+            high_score_rank: None,
+            trainer: Trainer::default(),
+            trainer_response: None,
+            trainer_changes: 0,
+            trainer_save_requested: false,
+            hunt_scope: HuntScope::default(),
+            hunt: None,
+            hunt_started: None,
+            oscilloscope: Oscilloscope::default(),
             intro_effect: fx::coalesce((800, Interpolation::ExpoOut)),
             menu_effect: fx::slide_in(
                 Motion::LeftToRight,
@@ -240,7 +412,8 @@ impl Application {
         self.tab = match self.tab {
             Tab::Frequency => Tab::Waveform,
             Tab::Waveform => Tab::Spectrum,
-            Tab::Spectrum => Tab::Fretboard,
+            Tab::Spectrum => Tab::Harmonics,
+            Tab::Harmonics => Tab::Fretboard,
             Tab::Fretboard => Tab::Frequency,
         };
         self.menu_effect.reset();
@@ -255,9 +428,16 @@ impl Application {
     }
 
     pub fn switch_fretboard_mode(&mut self) {
-        self.fretboard_state.clear_ghost_notes();
+        self.clear_ghost_notes();
+        self.fretboard_marks.set_heatmap(Vec::new());
+        self.fretboard_marks.clear_active_positions();
+        self.fretboard_marks.set_match_pitch_class(false);
+        self.fretboard_marks.set_root(None);
+        self.scale_pattern = None;
+        self.fretboard_marks.set_next_note(None);
         self.remove_ghost = true;
         self.song_note_index = 0;
+        self.song_tab.reset();
         self.random_mode_points = 0;
         match self.fretboard_mode {
             FretboardMode::Live => {
@@ -265,12 +445,38 @@ impl Application {
                 self.set_scale_notes();
             }
             FretboardMode::Scale => {
+                self.fretboard_mode = FretboardMode::Run;
+                self.set_run_notes();
+            }
+            FretboardMode::Run => {
+                self.fretboard_mode = FretboardMode::Arpeggio;
+                self.set_arpeggio_notes();
+            }
+            FretboardMode::Arpeggio => {
+                self.fretboard_mode = FretboardMode::Chords;
+                self.set_progression();
+            }
+            FretboardMode::Chords => {
                 self.fretboard_mode = FretboardMode::Random;
+                self.start_game(self.game.difficulty());
             }
             FretboardMode::Random => {
+                self.fretboard_mode = FretboardMode::Trainer;
+                self.start_trainer();
+            }
+            FretboardMode::Trainer => {
+                self.fretboard_mode = FretboardMode::Hunt;
+                self.trainer_save_requested = true;
+                self.start_hunt();
+            }
+            FretboardMode::Hunt => {
                 self.fretboard_mode = FretboardMode::Song;
+                self.hunt = None;
             }
             FretboardMode::Song => {
+                self.fretboard_mode = FretboardMode::Reading;
+            }
+            FretboardMode::Reading => {
                 self.fretboard_mode = FretboardMode::Live;
             }
         }
@@ -278,63 +484,490 @@ impl Application {
     }
 
     pub fn set_scale_notes(&mut self) {
-        self.fretboard_state.clear_ghost_notes();
+        self.clear_ghost_notes();
         self.remove_ghost = false;
+        self.state.spelling = self.current_scale.spelling(self.current_key.tonic());
+        self.fretboard_marks
+            .set_root(Some(self.current_key.tonic()));
+        if let Some(pattern) = self.current_scale_pattern() {
+            self.fretboard_state
+                .set_ghost_notes(pattern.notes(&STANDARD_TUNING));
+            self.fretboard_marks
+                .set_ghost_positions(pattern.positions().to_vec());
+            self.show_frets(pattern.frets());
+        } else {
+            self.fretboard_state.set_ghost_notes(
+                self.current_scale
+                    .fretboard_notes(self.current_key.tonic(), &self.fretboard_state.frets),
+            );
+        }
+    }
+
+    /// Returns the patterns of the current scale: boxes for pentatonic scales,
+    /// 3-notes-per-string patterns for seven-note scales and CAGED shapes otherwise.
+    pub fn scale_patterns(&self) -> Vec<ScalePattern> {
+        let system = match self.current_scale.intervals().len() {
+            5 => PatternSystem::PentatonicBoxes,
+            7 => PatternSystem::ThreeNotesPerString,
+            _ => PatternSystem::Caged,
+        };
+        system.patterns(
+            &self.current_scale,
+            self.current_key.tonic(),
+            &STANDARD_TUNING,
+        )
+    }
+
+    /// Returns the pattern of the current scale that is shown.
+    pub fn current_scale_pattern(&self) -> Option<ScalePattern> {
+        let index = self.scale_pattern?;
+        self.scale_patterns().into_iter().nth(index)
+    }
+
+    /// Steps through the patterns of the current scale, moving to the next
+    /// scale after the last one.
+    pub fn next_scale_pattern(&mut self) {
+        let count = self.scale_patterns().len();
+        self.scale_pattern = match self.scale_pattern {
+            None if count > 0 => Some(0),
+            Some(index) if index + 1 < count => Some(index + 1),
+            _ => {
+                self.current_scale = self.current_scale.next();
+                None
+            }
+        };
+        self.set_scale_notes();
+    }
+
+    /// Shows the current scale in the visible frets as a run and starts it over.
+    pub fn set_run_notes(&mut self) {
+        let root = self.current_key.tonic();
+        self.run = Sequence::scale_run(
+            &self.current_scale,
+            root,
+            &self.fretboard_state.frets,
+            self.scale_run,
+        );
+        self.run_mistake = None;
+        self.clear_ghost_notes();
+        self.remove_ghost = false;
+        self.state.spelling = self.current_scale.spelling(root);
+        self.fretboard_marks.set_root(Some(root));
+        self.fretboard_state
+            .set_ghost_notes(self.run.notes().to_vec());
+        self.fretboard_marks
+            .set_ghost_positions(self.run.positions().to_vec());
+        self.fretboard_marks.set_next_note(self.run.next_note());
+    }
+
+    /// Plays a note of the scale run, scoring a point for every correct note
+    /// and taking one for every wrong note.
+    pub fn play_run(&mut self, note: Note, time: Duration) {
+        match self.run.play(note, time) {
+            Step::Correct => {
+                self.random_mode_points += 1;
+                self.run_mistake = None;
+            }
+            Step::Wrong => {
+                if self.run.cursor() > 0 {
+                    self.random_mode_points = self.random_mode_points.saturating_sub(1);
+                    self.run_mistake = Some(note);
+                }
+            }
+            Step::Finished(summary) => {
+                self.random_mode_points += 1;
+                self.run_summary = Some(summary);
+                self.set_run_notes();
+            }
+        }
+        self.fretboard_marks.set_next_note(self.run.next_note());
+    }
+
+    /// Returns the chord practiced in arpeggio mode.
+    pub fn current_chord(&self) -> Chord {
+        Chord::new(self.current_key.tonic(), self.arpeggio_chord)
+    }
+
+    /// Shows the tones of the current chord in the visible frets and starts the arpeggio over.
+    pub fn set_arpeggio_notes(&mut self) {
+        let chord = self.current_chord();
+        self.arpeggio =
+            Sequence::arpeggio(&chord, &self.fretboard_state.frets, self.arpeggio_direction);
+        self.clear_ghost_notes();
+        self.remove_ghost = false;
+        self.state.spelling = chord.spelling();
+        self.fretboard_marks.set_root(Some(chord.root()));
+        self.fretboard_state
+            .set_ghost_notes(self.arpeggio.notes().to_vec());
+        self.fretboard_marks
+            .set_next_note(self.arpeggio.next_note());
+    }
+
+    /// Plays a note of the arpeggio, turning around once all chord tones were played.
+    pub fn play_arpeggio(&mut self, note: Note, time: Duration) {
+        if let Step::Finished(summary) = self.arpeggio.play(note, time) {
+            self.arpeggio_time = Some(summary.duration);
+            self.arpeggio_direction = self.arpeggio_direction.reversed();
+            self.set_arpeggio_notes();
+        }
+        self.fretboard_marks
+            .set_next_note(self.arpeggio.next_note());
+    }
+
+    /// Starts the current progression over, built on the current key.
+    pub fn set_progression(&mut self) {
+        let chords = self.progressions[self.progression].chords(&self.current_key);
+        self.chord_changes = ChordChanges::new(chords, self.chord_changes.advance());
+        self.set_chord_voicing();
+    }
+
+    /// Steps through the progressions, switching between advancing on the
+    /// recognized chord and on a timer after the last one.
+    pub fn next_progression(&mut self) {
+        self.progression = (self.progression + 1) % self.progressions.len();
+        if self.progression == 0 {
+            let advance = match self.chord_changes.advance() {
+                Advance::OnChord => Advance::Timer(CHORD_DURATION),
+                Advance::Timer(_) => Advance::OnChord,
+            };
+            self.chord_changes = ChordChanges::new(Vec::new(), advance);
+        }
+        self.set_progression();
+    }
+
+    /// Shows a voicing of the current chord, moving the visible frets if it doesn't fit in them.
+    pub fn set_chord_voicing(&mut self) {
+        self.clear_ghost_notes();
+        self.remove_ghost = false;
+        let Some(chord) = self.chord_changes.current_chord() else {
+            return;
+        };
+        self.state.spelling = chord.spelling();
+        self.fretboard_marks.set_root(Some(chord.root()));
+        let frets = self.fretboard_state.frets.clone();
+        // Every chord can be voiced below the twelfth fret
+        let Some(positions) = chord
+            .voicing(&STANDARD_TUNING, &frets)
+            .or_else(|| chord.voicing(&STANDARD_TUNING, &(0..=12)))
+        else {
+            return;
+        };
+        let low = positions.iter().map(|p| p.fret).min().unwrap_or_default();
+        let high = positions.iter().map(|p| p.fret).max().unwrap_or_default();
+        if !frets.contains(&low) || !frets.contains(&high) {
+            self.show_frets(low..=high);
+        }
         self.fretboard_state.set_ghost_notes(
-            self.current_scale
-                .fretboard_notes(self.current_root_note, &self.fretboard_state.frets),
+            positions
+                .iter()
+                .filter_map(|position| position.note(&STANDARD_TUNING))
+                .collect(),
         );
+        self.fretboard_marks.set_ghost_positions(positions);
+    }
+
+    /// Starts a new random note game with the options of the difficulty.
+    pub fn start_game(&mut self, difficulty: Difficulty) {
+        self.game = NoteGame::new(difficulty, difficulty.options(self.current_key));
+        self.high_score_rank = None;
+        self.clear_ghost_notes();
+        self.remove_ghost = false;
+    }
+
+    /// Checks the time limit of the random note game and picks a new target once the last one is gone.
+    fn update_game(&mut self) {
+        let time = self.state.time();
+        if let Some(outcome) = self.game.update(time) {
+            self.clear_ghost_notes();
+            if let Outcome::GameOver(score) = outcome {
+                self.high_score_rank = self.high_scores.add(score);
+            }
+        }
+        if self.game.target().is_some() {
+            return;
+        }
+        if let Some(position) = self
+            .game
+            .next_target(&self.fretboard_state.frets, time, |len| {
+                fastrand::usize(..len)
+            })
+        {
+            self.clear_ghost_notes();
+            self.fretboard_state
+                .set_ghost_notes(self.game.target_note().into_iter().collect());
+            self.fretboard_marks.set_ghost_positions(vec![position]);
+        }
     }
 
-    pub fn toggle_current_root_note(&mut self) {
-        let index = self.current_root_note.semitone_index() % 12;
-        self.current_root_note = Note::from_semitone_index(index + 1);
+    /// Adds the saved chord names, e.g. `G D Em C`, as the last progression of chords mode.
+    pub fn load_progression(&mut self, names: &str) -> Result<(), Error> {
+        self.progressions.push(Progression::from_chords(names)?);
+        Ok(())
+    }
+
+    /// Continues the trainer from the saved statistics.
+    pub fn load_trainer_stats(&mut self, stats: &str) {
+        self.trainer = Trainer::new(FretboardStats::parse(stats));
+    }
+
+    /// Returns the trainer statistics if they should be saved, i.e. every few
+    /// answers and after leaving the trainer.
+    pub fn take_unsaved_trainer_stats(&mut self) -> Option<String> {
+        let is_due = self.trainer_changes >= TRAINER_SAVE_INTERVAL
+            || (self.trainer_save_requested && self.trainer_changes > 0);
+        self.trainer_save_requested = false;
+        if !is_due {
+            return None;
+        }
+        self.trainer_changes = 0;
+        Some(self.trainer.stats().to_string())
+    }
+
+    /// Starts asking for positions, keeping the statistics.
+    pub fn start_trainer(&mut self) {
+        self.trainer = Trainer::new(self.trainer.stats().clone());
+        self.trainer_response = None;
+        self.clear_ghost_notes();
+        self.remove_ghost = false;
+    }
+
+    /// Checks the time limit of the trainer, picks a new target once the last
+    /// one is gone and updates the heatmap.
+    fn update_trainer(&mut self) {
+        let time = self.state.time();
+        if self.trainer.update(time) {
+            self.trainer_changes += 1;
+            self.trainer_response = None;
+            self.clear_ghost_notes();
+        }
+        if self.trainer.target().is_none() {
+            if let Some(position) =
+                self.trainer
+                    .next_target(&self.fretboard_state.frets, time, |total| {
+                        fastrand::usize(..total)
+                    })
+            {
+                self.clear_ghost_notes();
+                self.fretboard_state
+                    .set_ghost_notes(self.trainer.target_note().into_iter().collect());
+                self.fretboard_marks.set_ghost_positions(vec![position]);
+            }
+        }
+        self.fretboard_marks
+            .set_heatmap(self.trainer.stats().heatmap(&self.fretboard_state.frets));
+    }
+
+    /// Starts hunting a random pitch class in the current scope.
+    pub fn start_hunt(&mut self) {
+        let note = Note::from_semitone_index(4 * 12 + fastrand::u8(..12));
+        let frets = match self.hunt_scope {
+            HuntScope::Window => self.fretboard_state.frets.clone(),
+            HuntScope::Octaves => 0..=MAX_FRET,
+        };
+        let hunt = NoteHunt::new(note, self.hunt_scope, frets, self.state.time());
+        self.clear_ghost_notes();
+        self.fretboard_marks.clear_active_positions();
+        self.remove_ghost = false;
+        self.fretboard_marks.set_match_pitch_class(true);
+        self.fretboard_state.set_ghost_note(note);
+        self.fretboard_marks
+            .set_ghost_positions(hunt.targets().to_vec());
+        self.hunt = Some(hunt);
+        self.hunt_started = Some(Instant::now());
+    }
+
+    /// Returns whether the note to hunt is shown in big text instead of the fretboard.
+    pub fn show_hunt_note(&self) -> bool {
+        self.hunt_started
+            .is_some_and(|started| (started.elapsed().as_millis() as u64) < HUNT_NOTE_DURATION)
+    }
+
+    /// Moves to the next key clockwise on the circle of fifths.
+    pub fn next_key(&mut self) {
+        self.current_key = self.current_key.dominant();
+        self.key_changed = Some(Instant::now());
+    }
+
+    /// Returns whether the circle of fifths is shown instead of the fretboard.
+    pub fn show_key_circle(&self) -> bool {
+        self.key_changed
+            .is_some_and(|changed| (changed.elapsed().as_millis() as u64) < KEY_CIRCLE_DURATION)
     }
 
     pub fn scroll_fretboard(&mut self) {
-        const MAX_FRET: u8 = 24;
-        const WINDOW_SIZE: u8 = 6;
-        let max_start_fret = MAX_FRET - WINDOW_SIZE;
+        let max_start_fret = MAX_FRET - FRET_WINDOW;
         let start_fret = max_start_fret
             - ((self.control_value as u32 * max_start_fret as u32) / MAX_ADC_VALUE as u32) as u8;
-        let end_fret = start_fret + WINDOW_SIZE;
+        let end_fret = start_fret + FRET_WINDOW;
         self.fretboard_state.set_frets(start_fret..=end_fret);
     }
 
+    /// Clears the ghost notes along with the positions they were limited to.
+    pub fn clear_ghost_notes(&mut self) {
+        self.fretboard_state.clear_ghost_notes();
+        self.fretboard_marks.clear_ghost_positions();
+    }
+
+    /// Moves the visible frets to center the given range.
+    pub fn show_frets(&mut self, frets: RangeInclusive<u8>) {
+        let slack = FRET_WINDOW.saturating_sub(frets.end() - frets.start());
+        let start_fret = frets
+            .start()
+            .saturating_sub(slack / 2)
+            .min(MAX_FRET - FRET_WINDOW);
+        self.fretboard_state
+            .set_frets(start_fret..=start_fret + FRET_WINDOW);
+    }
+
     pub fn toggle_current_song(&mut self) {
         self.current_song_index = (self.current_song_index + 1) % SONGS.len();
         self.song_note_index = 0;
-        self.fretboard_state.clear_ghost_notes();
+        self.song_tab.reset();
+        self.song_fingering = None;
+        self.clear_ghost_notes();
+    }
+
+    /// Returns the first beat and the positions of a window of beats of the
+    /// current song around the given beat.
+    ///
+    /// The positions are only chosen for a window of beats at a time to keep
+    /// the memory use low. A new window continues from the hand position of
+    /// the previous beat, and the first one starts at the visible frets.
+    pub fn song_fingering(&mut self, beat: usize) -> (usize, &[Vec<FretPosition>]) {
+        let song = &SONGS[self.current_song_index];
+        let is_solved = matches!(&self.song_fingering, Some((first, positions))
+            if beat >= *first
+                && (beat + SONG_WINDOW_AHEAD).min(song.notes.len()) <= first + positions.len());
+        if !is_solved {
+            let hand = self
+                .song_fingering
+                .as_ref()
+                .and_then(|(first, positions)| positions.get(beat.checked_sub(first + 1)?))
+                .and_then(|positions| {
+                    positions
+                        .iter()
+                        .map(|position| position.fret)
+                        .filter(|fret| *fret > 0)
+                        .min()
+                })
+                .unwrap_or(*self.fretboard_state.frets.start());
+            let first = beat.saturating_sub(SONG_WINDOW_BEHIND);
+            let last = (first + SONG_WINDOW_BEATS).min(song.notes.len());
+            let solver = FingeringSolver::new(0..=MAX_FRET).with_preferred_position(Some(hand));
+            self.song_fingering = Some((
+                first,
+                solver.solve(&song.notes[first..last], &STANDARD_TUNING),
+            ));
+        }
+        self.song_fingering
+            .as_ref()
+            .map_or((beat, &[]), |(first, positions)| {
+                (*first, positions.as_slice())
+            })
+    }
+
+    /// Returns how much of the time to play the current song beat has passed, if missed beats are skipped.
+    pub fn song_beat_progress(&self) -> Option<f64> {
+        self.skip_missed_beats.then(|| {
+            (self.song_beat_started.elapsed().as_secs_f64() / SONG_BEAT_TIMEOUT.as_secs_f64())
+                .min(1.0)
+        })
     }
 
     pub fn tick(&mut self) {
         if self.tab == Tab::Fretboard && self.fretboard_mode == FretboardMode::Random {
+            self.update_game();
+        }
+
+        if self.tab == Tab::Fretboard && self.fretboard_mode == FretboardMode::Trainer {
+            self.update_trainer();
+        }
+
+        if self.tab == Tab::Fretboard && self.fretboard_mode == FretboardMode::Reading {
             if self.fretboard_state.ghost_notes.is_empty() {
-                self.fretboard_state
-                    .set_ghost_note(utils::generate_random_note(&self.fretboard_state.frets));
-                self.last_random = Instant::now();
-            } else if self.last_random.elapsed().as_millis() as u64 > MAX_RANDOM_INTERVAL {
+                // Stay on the staff, the fretboard is not shown while reading
+                let note = utils::generate_random_staff_note(&Clef::Guitar.staff_range());
+                self.fretboard_state.set_ghost_note(note);
+                self.note_shown_at = Instant::now();
+            } else if self.note_shown_at.elapsed() > NOTE_TIMEOUT {
                 self.random_mode_points = self.random_mode_points.saturating_sub(1);
-                self.fretboard_state.clear_ghost_notes();
+                self.clear_ghost_notes();
             }
         }
 
+        if self.tab == Tab::Fretboard
+            && self.fretboard_mode == FretboardMode::Song
+            && self.skip_missed_beats
+            && !self.fretboard_state.ghost_notes.is_empty()
+            && self.song_beat_started.elapsed() > SONG_BEAT_TIMEOUT
+        {
+            // Move on to the next beat, marking the current one as missed
+            self.song_tab.advance(true);
+            self.clear_ghost_notes();
+        }
+
         if self.tab == Tab::Fretboard
             && self.fretboard_mode == FretboardMode::Song
             && self.fretboard_state.ghost_notes.is_empty()
         {
             let song = &SONGS[self.current_song_index];
+            let index = self.song_note_index % song.notes.len();
+            if index == 0 {
+                self.song_tab.reset();
+            }
+            self.song_tab.set_cursor(index);
+            // Only the missed beats that can still be seen are kept
+            self.song_tab
+                .missed
+                .retain(|missed| missed + SONG_TAB_LOOKBEHIND >= index);
+            self.song_beat_started = Instant::now();
+            let (first, positions) = self.song_fingering(index);
+            let positions = positions.get(index - first).cloned().unwrap_or_default();
+            self.clear_ghost_notes();
             self.fretboard_state
-                .set_ghost_notes(song.notes[self.song_note_index % song.notes.len()].to_vec());
+                .set_ghost_notes(song.notes[index].to_vec());
+            self.fretboard_marks.set_ghost_positions(positions);
             self.song_note_index += 1;
         }
 
-        self.fretboard_state.clear_active_notes();
-        if let Some(note) = self
-            .state
-            .get_current_note()
-            .and_then(|(note, _)| note.try_into().ok())
-        {
+        if self.tab == Tab::Fretboard && self.fretboard_mode == FretboardMode::Chords {
+            let recognized = self.state.chroma().and_then(|chroma| {
+                chroma.recognize(self.chord_changes.chords(), MIN_CHORD_SIMILARITY)
+            });
+            if self.chord_changes.update(recognized, self.state.time()) {
+                self.set_chord_voicing();
+            }
+        }
+
+        // Count every pick separately, so repeated notes need to be played again
+        for event in self.state.take_events() {
+            let NoteEvent::NoteOn { note, time, .. } = event else {
+                continue;
+            };
+            if self.tab == Tab::Fretboard && self.fretboard_mode == FretboardMode::Run {
+                self.play_run(note, time);
+            }
+            if self.tab == Tab::Fretboard && self.fretboard_mode == FretboardMode::Arpeggio {
+                self.play_arpeggio(note, time);
+            }
+            if self.tab == Tab::Fretboard
+                && self.fretboard_mode == FretboardMode::Random
+                && self.game.play(note).is_some()
+            {
+                self.clear_ghost_notes();
+            }
+            if self.tab == Tab::Fretboard && self.fretboard_mode == FretboardMode::Trainer {
+                if let Some(response) = self.trainer.play(note, time) {
+                    self.trainer_changes += 1;
+                    self.trainer_response = Some(response);
+                    self.clear_ghost_notes();
+                }
+            }
+            if self.tab == Tab::Fretboard && self.fretboard_mode == FretboardMode::Hunt {
+                if let Some(position) = self.hunt.as_mut().and_then(|hunt| hunt.play(note, time)) {
+                    self.fretboard_marks.set_active_position(position);
+                }
+            }
             if self.remove_ghost {
                 if let Some(pos) = self
                     .fretboard_state
@@ -346,7 +979,15 @@ impl Application {
                     self.random_mode_points += 1;
                 }
             }
+        }
 
+        self.fretboard_state.clear_active_notes();
+        if let Some(note) = self
+            .state
+            .get_current_note()
+            .filter(|detected| !detected.is_uncertain())
+            .and_then(|detected| detected.fretboard_note())
+        {
             if !self.fretboard_state.active_notes.contains(&note) {
                 self.fretboard_state.active_notes.push(note);
             }
@@ -354,9 +995,29 @@ impl Application {
     }
 
     pub fn handle_press(&mut self, button: Button) {
+        if self.tab == Tab::Waveform {
+            if button == Button::Both {
+                self.handle_event(Event::ToggleHold);
+                return;
+            } else if button == Button::Menu(ButtonPressType::Long) {
+                self.handle_event(Event::SwitchTrigger);
+                return;
+            }
+        }
+
         if button == Button::Both && self.tab == Tab::Fretboard {
-            if self.fretboard_mode == FretboardMode::Scale {
-                self.handle_event(Event::ToggleRootNote);
+            if matches!(
+                self.fretboard_mode,
+                FretboardMode::Scale
+                    | FretboardMode::Run
+                    | FretboardMode::Arpeggio
+                    | FretboardMode::Chords
+            ) {
+                self.handle_event(Event::NextKey);
+            } else if self.fretboard_mode == FretboardMode::Random {
+                self.start_game(self.game.difficulty());
+            } else if self.fretboard_mode == FretboardMode::Hunt {
+                self.start_hunt();
             } else if self.fretboard_mode == FretboardMode::Song {
                 self.toggle_current_song();
             }
@@ -385,8 +1046,60 @@ impl Application {
             && self.tab == Tab::Fretboard
             && self.fretboard_mode == FretboardMode::Scale
         {
-            self.current_scale = self.current_scale.next();
-            self.set_scale_notes();
+            self.next_scale_pattern();
+        }
+
+        if button == Button::Menu(ButtonPressType::Long)
+            && self.tab == Tab::Fretboard
+            && self.fretboard_mode == FretboardMode::Run
+        {
+            self.scale_run = self.scale_run.next();
+            self.run_summary = None;
+            self.set_run_notes();
+        }
+
+        if button == Button::Menu(ButtonPressType::Long)
+            && self.tab == Tab::Fretboard
+            && self.fretboard_mode == FretboardMode::Arpeggio
+        {
+            self.arpeggio_chord = self.arpeggio_chord.next();
+            self.set_arpeggio_notes();
+        }
+
+        if button == Button::Menu(ButtonPressType::Long)
+            && self.tab == Tab::Fretboard
+            && self.fretboard_mode == FretboardMode::Chords
+        {
+            self.next_progression();
+        }
+
+        if button == Button::Menu(ButtonPressType::Long)
+            && self.tab == Tab::Fretboard
+            && self.fretboard_mode == FretboardMode::Random
+        {
+            self.start_game(self.game.difficulty().next());
+        }
+
+        if button == Button::Menu(ButtonPressType::Long)
+            && self.tab == Tab::Fretboard
+            && self.fretboard_mode == FretboardMode::Hunt
+        {
+            self.hunt_scope = self.hunt_scope.next();
+            self.start_hunt();
+        }
+
+        if button == Button::Menu(ButtonPressType::Long)
+            && self.tab == Tab::Fretboard
+            && self.fretboard_mode == FretboardMode::Song
+        {
+            // Cycle between the fretboard, the tablature and the tablature skipping missed beats
+            (self.show_song_tab, self.skip_missed_beats) =
+                match (self.show_song_tab, self.skip_missed_beats) {
+                    (false, _) => (true, false),
+                    (true, false) => (true, true),
+                    (true, true) => (false, false),
+                };
+            self.song_beat_started = Instant::now();
         }
     }
 
@@ -403,15 +1116,42 @@ impl Application {
                 log::info!("Control value updated: {value}");
 
                 if self.tab == Tab::Fretboard {
+                    let frets = self.fretboard_state.frets.clone();
                     self.scroll_fretboard();
+                    if self.fretboard_state.frets != frets {
+                        match self.fretboard_mode {
+                            FretboardMode::Run => self.set_run_notes(),
+                            FretboardMode::Arpeggio => self.set_arpeggio_notes(),
+                            FretboardMode::Chords => self.set_chord_voicing(),
+                            FretboardMode::Hunt if self.hunt_scope == HuntScope::Window => {
+                                self.start_hunt()
+                            }
+                            _ => {}
+                        }
+                    }
+                } else if self.tab == Tab::Waveform && self.oscilloscope.trigger != Trigger::Off {
+                    self.oscilloscope.set_periods(
+                        1 + (value as usize * (MAX_PERIODS - 1)) / MAX_ADC_VALUE as usize,
+                    );
                 }
             }
-            Event::ToggleRootNote => {
-                self.toggle_current_root_note();
-                self.set_scale_notes();
+            Event::NextKey => {
+                self.next_key();
+                match self.fretboard_mode {
+                    FretboardMode::Run => self.set_run_notes(),
+                    FretboardMode::Arpeggio => self.set_arpeggio_notes(),
+                    FretboardMode::Chords => self.set_progression(),
+                    _ => self.set_scale_notes(),
+                }
                 #[cfg(feature = "logging")]
-                log::info!("Current root note changed: {}", self.current_root_note);
+                log::info!("Current key changed: {}", self.current_key);
+            }
+            Event::SwitchTrigger => {
+                self.oscilloscope.next_trigger();
+                // The knob controls the timebase while triggered
+                self.oscilloscope.auto_scale = self.oscilloscope.trigger != Trigger::Off;
             }
+            Event::ToggleHold => self.oscilloscope.toggle_hold(),
         }
     }
 }
@@ -27,6 +27,7 @@ use esp_idf_svc::hal::{
     spi::{SpiConfig, SpiDeviceDriver},
     units::*,
 };
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs};
 use mousefood::prelude::*;
 use st7735_lcd::{Orientation, ST7735};
 
@@ -35,6 +36,21 @@ use transform::Transform;
 
 pub(crate) const MAX_ADC_VALUE: u16 = 3129;
 
+/// The NVS namespace of the settings.
+const NVS_NAMESPACE: &str = "tuitar";
+
+/// The NVS key of the trainer statistics.
+const TRAINER_STATS_KEY: &str = "trainer";
+
+/// The NVS key of the chord names added as the last progression of chords mode, e.g. `G D Em C`.
+const PROGRESSION_KEY: &str = "progression";
+
+/// The longest list of chord names that can be loaded.
+const MAX_PROGRESSION_SIZE: usize = 256;
+
+/// The largest trainer statistics that can be loaded, enough for every position up to the 24th fret.
+const MAX_TRAINER_STATS_SIZE: usize = 8192;
+
 type DisplayResult<'a> = anyhow::Result<
     ST7735<
         SpiDeviceDriver<'a, esp_idf_svc::hal::spi::SpiDriver<'a>>,
@@ -147,6 +163,31 @@ fn main() -> anyhow::Result<()> {
 
     let mut app = Application::new(buffer_size);
 
+    // The trainer works without storage, its statistics are just not kept
+    let mut nvs = match EspDefaultNvsPartition::take()
+        .and_then(|partition| EspNvs::new(partition, NVS_NAMESPACE, true))
+    {
+        Ok(nvs) => Some(nvs),
+        Err(_e) => {
+            #[cfg(feature = "logging")]
+            log::warn!("Failed to open the storage, trainer statistics won't be saved: {_e}");
+            None
+        }
+    };
+    if let Some(nvs) = &nvs {
+        let mut stats_buffer = vec![0; MAX_TRAINER_STATS_SIZE];
+        if let Ok(Some(stats)) = nvs.get_raw(TRAINER_STATS_KEY, &mut stats_buffer) {
+            app.load_trainer_stats(&String::from_utf8_lossy(stats));
+        }
+        let mut progression_buffer = [0; MAX_PROGRESSION_SIZE];
+        if let Ok(Some(names)) = nvs.get_str(PROGRESSION_KEY, &mut progression_buffer) {
+            if let Err(_e) = app.load_progression(names) {
+                #[cfg(feature = "logging")]
+                log::warn!("Ignoring the saved progression: {_e}");
+            }
+        }
+    }
+
     while app.is_running {
         let instant = Instant::now();
         let mut sample_len = 0;
@@ -188,6 +229,13 @@ fn main() -> anyhow::Result<()> {
         samples.fill(0);
 
         app.handle_event(Event::Tick);
+
+        if let (Some(nvs), Some(stats)) = (nvs.as_mut(), app.take_unsaved_trainer_stats()) {
+            if let Err(_e) = nvs.set_raw(TRAINER_STATS_KEY, stats.as_bytes()) {
+                #[cfg(feature = "logging")]
+                log::warn!("Failed to save the trainer statistics: {_e}");
+            }
+        }
     }
 
     Ok(())
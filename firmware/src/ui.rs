@@ -1,11 +1,14 @@
 use mousefood::ratatui::layout::Offset;
 use mousefood::ratatui::widgets::Paragraph;
 use mousefood::{prelude::*, ratatui::widgets::LineGauge};
-use ratatui_fretboard::Fretboard;
+use ratatui_fretboard::{
+    tablature::TabBeat, CircleOfFifths, Fretboard, NoteLabel, Staff, Tab as TabStaff, TabState,
+};
 use tachyonfx::{Duration, EffectRenderer};
+use tui_big_text::{BigText, PixelSize};
 
 use crate::{
-    app::{Application, FretboardMode, Tab, MAX_RANDOM_INTERVAL},
+    app::{Application, FretboardMode, Tab, SONG_TAB_LOOKBEHIND, SONG_WINDOW_AHEAD},
     MAX_ADC_VALUE,
 };
 use tuitar_core::{songs::*, ui::*};
@@ -69,6 +72,109 @@ impl Application {
         );
     }
 
+    fn render_level_meter(&mut self, frame: &mut Frame<'_>) {
+        let area = frame.area();
+        draw_level_meter(
+            frame,
+            // Bottom left corner of the screen, next to the input mode
+            Rect::new(area.left() + 4, area.bottom().saturating_sub(1), 4, 1),
+            &self.state,
+        );
+    }
+
+    fn render_song_tab(&mut self, frame: &mut Frame<'_>) {
+        // The current beat is the one shown as ghost notes
+        let beat = self.song_tab.cursor;
+        let (first, positions) = self.song_fingering(beat);
+        // Only the beats around the cursor can fit on the screen
+        let visible = beat.saturating_sub(SONG_TAB_LOOKBEHIND).max(first);
+        let beats: Vec<TabBeat> = positions
+            .iter()
+            .skip(visible - first)
+            .take(beat - visible + SONG_WINDOW_AHEAD)
+            .cloned()
+            .map(TabBeat::new)
+            .collect();
+        let mut tab_state = TabState::new();
+        tab_state.set_cursor(beat - visible);
+        tab_state.missed = self
+            .song_tab
+            .missed
+            .iter()
+            .filter_map(|missed| missed.checked_sub(visible))
+            .collect();
+
+        frame.render_stateful_widget(
+            &TabStaff::default()
+                .with_beats(beats)
+                .with_lookbehind(SONG_TAB_LOOKBEHIND),
+            frame.area().offset(Offset { x: 0, y: 3 }),
+            &mut tab_state,
+        );
+    }
+
+    fn render_reading(&mut self, frame: &mut Frame<'_>) {
+        let area = frame.area();
+        // The font has no clef symbols, '&' is the usual stand-in for the treble clef
+        let staff = Staff::default()
+            .with_clef_symbol('&')
+            .with_beats(vec![self.fretboard_state.ghost_notes.clone()]);
+        frame.render_stateful_widget(
+            &staff,
+            // Everything between the cents gauge and the mode line
+            Rect::new(
+                area.left(),
+                area.top() + 1,
+                area.width,
+                area.height.saturating_sub(3),
+            ),
+            &mut TabState::new(),
+        );
+
+        frame.render_widget(
+            Paragraph::new(self.random_mode_points.to_string().cyan()).alignment(Alignment::Right),
+            // On the mode line, which is two lines above the bottom of the screen
+            Rect::new(area.left(), area.bottom().saturating_sub(2), area.width, 1),
+        );
+    }
+
+    fn render_high_scores(&mut self, frame: &mut Frame<'_>) {
+        let mut lines = vec![Line::from("Game over".red().bold())];
+        for (rank, score) in self.high_scores.scores().iter().enumerate() {
+            let line = Line::from(format!(
+                "{}. {} {} x{}",
+                rank + 1,
+                score.points,
+                score.difficulty,
+                score.best_streak
+            ));
+            if Some(rank) == self.high_score_rank {
+                lines.push(line.yellow());
+            } else {
+                lines.push(line.gray());
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(lines).alignment(Alignment::Center),
+            frame.area().offset(Offset { x: 0, y: 3 }),
+        );
+    }
+
+    fn render_hunt_note(&mut self, frame: &mut Frame<'_>) {
+        let Some(hunt) = &self.hunt else {
+            return;
+        };
+        let big_text = BigText::builder()
+            .pixel_size(PixelSize::Quadrant)
+            .style(Color::Yellow)
+            .lines(vec![Line::from(
+                hunt.note().spelled_name(self.state.spelling),
+            )])
+            .alignment(Alignment::Center)
+            .build();
+        frame.render_widget(big_text, frame.area().offset(Offset { x: 0, y: 4 }));
+    }
+
     fn render_menus(&mut self, frame: &mut Frame<'_>) {
         let frame_area = frame.area();
         draw_cents(frame, frame_area, &self.state);
@@ -87,15 +193,17 @@ impl Application {
             Tab::Waveform => {
                 let value = MAX_ADC_VALUE.saturating_sub(self.control_value);
                 let min_bound = (value / 100 * 100) as f64;
-                draw_waveform(
+                draw_oscilloscope(
                     frame,
                     area,
                     &self.state,
+                    &mut self.oscilloscope,
                     (min_bound, min_bound + 300.),
-                    ("Amp", "Time"),
+                    ("Amp", "ms"),
                 )
             }
             Tab::Spectrum => draw_dbfs_spectrum(frame, area, &self.state, ("dBFS", "Hz")),
+            Tab::Harmonics => draw_harmonics(frame, area, &self.state, 6, ("dBFS", "Hz")),
             Tab::Fretboard => {
                 frame.render_widget(
                     Paragraph::new(self.fretboard_mode.as_line()).alignment(Alignment::Center),
@@ -108,25 +216,134 @@ impl Application {
                     ),
                 );
 
-                frame.render_stateful_widget(
-                    &Fretboard::default(),
-                    frame.area().offset(Offset { x: 0, y: 3 }),
-                    &mut self.fretboard_state,
-                );
+                if self.fretboard_mode == FretboardMode::Song && self.show_song_tab {
+                    self.render_song_tab(frame);
+                } else if self.fretboard_mode == FretboardMode::Scale && self.show_key_circle() {
+                    frame.render_widget(
+                        &CircleOfFifths::default().with_key(self.current_key),
+                        frame.area().offset(Offset { x: 0, y: 3 }),
+                    );
+                } else if self.fretboard_mode == FretboardMode::Random && self.game.is_over() {
+                    self.render_high_scores(frame);
+                } else if self.fretboard_mode == FretboardMode::Hunt && self.show_hunt_note() {
+                    self.render_hunt_note(frame);
+                } else if self.fretboard_mode == FretboardMode::Reading {
+                    self.render_reading(frame);
+                } else {
+                    // The intervals of the scale, shortened to the degree on narrow frets
+                    let note_label = if matches!(
+                        self.fretboard_mode,
+                        FretboardMode::Scale
+                            | FretboardMode::Run
+                            | FretboardMode::Arpeggio
+                            | FretboardMode::Chords
+                    ) {
+                        NoteLabel::Interval
+                    } else {
+                        NoteLabel::Symbol
+                    };
+                    frame.render_stateful_widget(
+                        &Fretboard::default()
+                            .with_spelling(self.state.spelling)
+                            .with_note_label(note_label)
+                            .with_marks(self.fretboard_marks.clone()),
+                        frame.area().offset(Offset { x: 0, y: 3 }),
+                        &mut self.fretboard_state,
+                    );
+                }
 
                 if self.fretboard_mode == FretboardMode::Scale {
-                    let scale_line = Line::from(vec![
+                    let mut scale_line = Line::from(vec![
                         "<".into(),
                         self.current_scale.to_string().yellow(),
                         " (".into(),
-                        self.current_root_note.name().cyan(),
-                        ")>".into(),
+                        self.current_key.name().cyan(),
+                        ")".into(),
                     ]);
+                    if let Some(pattern) = self.current_scale_pattern() {
+                        scale_line.push_span(" ");
+                        scale_line.push_span(pattern.name().to_string().green());
+                    }
+                    scale_line.push_span(">");
                     frame.render_widget(
                         Paragraph::new(scale_line).alignment(Alignment::Center),
                         // Third line from the top
                         frame_area.offset(Offset { x: 0, y: 2 }),
                     );
+                } else if self.fretboard_mode == FretboardMode::Run {
+                    let mut run_line = Line::from(vec![
+                        "<".into(),
+                        self.scale_run.to_string().yellow(),
+                        " ".into(),
+                        self.random_mode_points.to_string().cyan(),
+                    ]);
+                    if let Some(note) = self.run_mistake {
+                        run_line.push_span(" ✖".red());
+                        run_line.push_span(note.spelled_name(self.state.spelling).red());
+                    } else if let Some(summary) = self.run_summary {
+                        run_line.push_span(
+                            format!(
+                                " {:.0}bpm {:.0}%",
+                                summary.tempo(),
+                                summary.accuracy() * 100.0
+                            )
+                            .green(),
+                        );
+                    }
+                    run_line.push_span(">");
+                    frame.render_widget(
+                        Paragraph::new(run_line).alignment(Alignment::Center),
+                        // Third line from the top
+                        frame_area.offset(Offset { x: 0, y: 2 }),
+                    );
+                } else if self.fretboard_mode == FretboardMode::Arpeggio {
+                    let mut arpeggio_line = Line::from(vec![
+                        "<".into(),
+                        self.current_chord().to_string().yellow(),
+                        " ".into(),
+                        self.arpeggio_direction.arrow().cyan(),
+                    ]);
+                    if let Some(time) = self.arpeggio_time {
+                        arpeggio_line.push_span(format!(" {:.1}s", time.as_secs_f64()).green());
+                    }
+                    arpeggio_line.push_span(">");
+                    frame.render_widget(
+                        Paragraph::new(arpeggio_line).alignment(Alignment::Center),
+                        // Third line from the top
+                        frame_area.offset(Offset { x: 0, y: 2 }),
+                    );
+                } else if self.fretboard_mode == FretboardMode::Chords {
+                    let mut chords_line = Line::from("<");
+                    if let Some(chord) = self.chord_changes.current_chord() {
+                        chords_line.push_span(chord.to_string().yellow());
+                    }
+                    if let Some(chord) = self.chord_changes.next_chord() {
+                        chords_line.push_span(" → ".gray());
+                        chords_line.push_span(chord.to_string().gray());
+                    }
+                    if self.chord_changes.is_started() {
+                        chords_line.push_span(
+                            format!(" {:.0}/min", self.chord_changes.changes_per_minute()).green(),
+                        );
+                    } else {
+                        chords_line.push_span(format!(" {}", self.chord_changes.advance()).cyan());
+                    }
+                    chords_line.push_span(">");
+                    frame.render_widget(
+                        Paragraph::new(chords_line).alignment(Alignment::Center),
+                        // Third line from the top
+                        frame_area.offset(Offset { x: 0, y: 2 }),
+                    );
+                    if let Some(progress) = self.chord_changes.progress() {
+                        frame.render_widget(
+                            LineGauge::default()
+                                .filled_style(Color::Green)
+                                .unfilled_style(Color::Red)
+                                .ratio(1.0 - progress),
+                            // Second line from the top
+                            frame_area.offset(Offset { x: 0, y: 1 }),
+                        );
+                    }
                 } else if self.fretboard_mode == FretboardMode::Song {
                     let song_line = Line::from(vec![
                         "<".into(),
@@ -138,25 +355,86 @@ impl Application {
                         // Third line from the top
                         frame_area.offset(Offset { x: 0, y: 2 }),
                     );
+                    if let Some(progress) = self.song_beat_progress() {
+                        frame.render_widget(
+                            LineGauge::default()
+                                .filled_style(Color::Green)
+                                .unfilled_style(Color::Red)
+                                .ratio(1.0 - progress),
+                            // Second line from the top
+                            frame_area.offset(Offset { x: 0, y: 1 }),
+                        );
+                    }
+                } else if self.fretboard_mode == FretboardMode::Trainer {
+                    let mastery = self.trainer.stats().mastery(&self.fretboard_state.frets);
+                    let mut trainer_line = Line::from(vec![
+                        "<".into(),
+                        format!("{:.0}%", mastery * 100.0).yellow(),
+                    ]);
+                    if let Some(response) = self.trainer_response {
+                        trainer_line.push_span(format!(" {:.1}s", response.as_secs_f64()).green());
+                    }
+                    trainer_line.push_span(">");
+                    frame.render_widget(
+                        Paragraph::new(trainer_line).alignment(Alignment::Center),
+                        // Third line from the top
+                        frame_area.offset(Offset { x: 0, y: 2 }),
+                    );
+                    frame.render_widget(
+                        LineGauge::default()
+                            .filled_style(Color::Green)
+                            .unfilled_style(Color::Red)
+                            .ratio(self.trainer.remaining(self.state.time())),
+                        // Second line from the top
+                        frame_area.offset(Offset { x: 0, y: 1 }),
+                    );
+                } else if self.fretboard_mode == FretboardMode::Hunt {
+                    if let Some(hunt) = &self.hunt {
+                        let elapsed = hunt.elapsed(self.state.time()).as_secs_f64();
+                        let hunt_line = Line::from(vec![
+                            "<".into(),
+                            hunt.note().spelled_name(self.state.spelling).yellow(),
+                            " ".into(),
+                            self.hunt_scope.to_string().gray(),
+                            format!(" {}/{}", hunt.found().len(), hunt.targets().len()).cyan(),
+                            if hunt.is_finished() {
+                                format!(" {elapsed:.1}s").green()
+                            } else {
+                                format!(" {elapsed:.0}s").into()
+                            },
+                            ">".into(),
+                        ]);
+                        frame.render_widget(
+                            Paragraph::new(hunt_line).alignment(Alignment::Center),
+                            // Third line from the top
+                            frame_area.offset(Offset { x: 0, y: 2 }),
+                        );
+                    }
                 } else if self.fretboard_mode == FretboardMode::Random {
-                    let random_line = Line::from(vec![
-                        "Points: ".yellow(),
-                        self.random_mode_points.to_string().cyan(),
+                    let mut random_line = Line::from(vec![
+                        "<".into(),
+                        self.game.difficulty().to_string().yellow(),
+                        " ".into(),
+                        self.game.points().to_string().cyan(),
                     ]);
+                    if self.game.streak() > 1 {
+                        random_line.push_span(format!(" x{}", self.game.streak()).green());
+                    }
+                    if let Some(lives) = self.game.lives() {
+                        random_line.push_span(" ");
+                        random_line.push_span("♥".repeat(lives as usize).red());
+                    }
+                    random_line.push_span(">");
                     frame.render_widget(
                         Paragraph::new(random_line).alignment(Alignment::Center),
                         // Third line from the top
                         frame_area.offset(Offset { x: 0, y: 2 }),
                     );
-                    let ratio = 1.0
-                        - (self.last_random.elapsed().as_millis() as f64
-                            / MAX_RANDOM_INTERVAL as f64)
-                            .clamp(0.0, 1.0);
                     frame.render_widget(
                         LineGauge::default()
                             .filled_style(Color::Green)
                             .unfilled_style(Color::Red)
-                            .ratio(ratio),
+                            .ratio(self.game.remaining(self.state.time())),
                         // Second line from the top
                         frame_area.offset(Offset { x: 0, y: 1 }),
                     );
@@ -213,6 +491,7 @@ impl Application {
         self.render_menus(frame);
         self.render_fps(frame);
         self.render_input_mode(frame);
+        self.render_level_meter(frame);
         self.render_effects(frame);
     }
 }
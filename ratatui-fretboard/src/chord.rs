@@ -0,0 +1,473 @@
+//! Chords.
+
+use std::{cmp::Reverse, fmt, ops::RangeInclusive, str::FromStr};
+
+use crate::{
+    error::Error,
+    key::Key,
+    note::{Note, Spelling, Tuning, LETTERS, NATURAL_PITCH_CLASSES, STANDARD_TUNING},
+    position::FretPosition,
+};
+
+/// The octave the root of a parsed chord is put in.
+const ROOT_OCTAVE: u8 = 3;
+
+/// The maximum distance between the lowest and highest fretted note of a voicing.
+const MAX_VOICING_SPAN: u8 = 3;
+
+/// How good a voicing is, compared by the number of strings, then a smaller span and a lower position.
+type VoicingScore = (usize, Reverse<u8>, Reverse<u8>);
+
+/// The type of a chord, defined by its intervals above the root.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ChordType {
+    #[default]
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    Major7,
+    Dominant7,
+    Minor7,
+    HalfDiminished7,
+    Diminished7,
+}
+
+impl ChordType {
+    /// All chord types, triads first.
+    pub const ALL: [ChordType; 9] = [
+        ChordType::Major,
+        ChordType::Minor,
+        ChordType::Diminished,
+        ChordType::Augmented,
+        ChordType::Major7,
+        ChordType::Dominant7,
+        ChordType::Minor7,
+        ChordType::HalfDiminished7,
+        ChordType::Diminished7,
+    ];
+
+    /// Returns the semitones of the chord tones above the root, starting with the root.
+    pub fn intervals(&self) -> &'static [u8] {
+        match self {
+            ChordType::Major => &[0, 4, 7],
+            ChordType::Minor => &[0, 3, 7],
+            ChordType::Diminished => &[0, 3, 6],
+            ChordType::Augmented => &[0, 4, 8],
+            ChordType::Major7 => &[0, 4, 7, 11],
+            ChordType::Dominant7 => &[0, 4, 7, 10],
+            ChordType::Minor7 => &[0, 3, 7, 10],
+            ChordType::HalfDiminished7 => &[0, 3, 6, 10],
+            ChordType::Diminished7 => &[0, 3, 6, 9],
+        }
+    }
+
+    /// Returns the suffix of a chord name with this type, e.g. `m7`.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            ChordType::Major => "",
+            ChordType::Minor => "m",
+            ChordType::Diminished => "°",
+            ChordType::Augmented => "+",
+            ChordType::Major7 => "maj7",
+            ChordType::Dominant7 => "7",
+            ChordType::Minor7 => "m7",
+            ChordType::HalfDiminished7 => "m7b5",
+            ChordType::Diminished7 => "°7",
+        }
+    }
+
+    /// Returns the chord type written with the given suffix, accepting common
+    /// alternatives like `min`, `dim`, `Δ` or `ø`.
+    pub fn from_suffix(suffix: &str) -> Option<ChordType> {
+        let chord_type = match suffix {
+            "" | "M" | "maj" => ChordType::Major,
+            "m" | "min" | "-" => ChordType::Minor,
+            "°" | "o" | "dim" => ChordType::Diminished,
+            "+" | "aug" => ChordType::Augmented,
+            "maj7" | "M7" | "Δ" | "Δ7" => ChordType::Major7,
+            "7" | "dom7" => ChordType::Dominant7,
+            "m7" | "min7" | "-7" => ChordType::Minor7,
+            "m7b5" | "m7♭5" | "ø" | "ø7" => ChordType::HalfDiminished7,
+            "°7" | "o7" | "dim7" => ChordType::Diminished7,
+            _ => return None,
+        };
+        Some(chord_type)
+    }
+
+    /// Returns the triad with the given third and fifth above the root, in semitones.
+    pub fn from_triad(third: u8, fifth: u8) -> Option<ChordType> {
+        match (third, fifth) {
+            (4, 7) => Some(ChordType::Major),
+            (3, 7) => Some(ChordType::Minor),
+            (3, 6) => Some(ChordType::Diminished),
+            (4, 8) => Some(ChordType::Augmented),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the chord has a seventh.
+    pub fn is_seventh(&self) -> bool {
+        self.intervals().len() == 4
+    }
+
+    /// Returns the next chord type, wrapping around to the first one.
+    pub fn next(&self) -> ChordType {
+        let index = Self::ALL.iter().position(|t| t == self).unwrap_or_default();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// A chord, made of a root and a chord type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chord {
+    /// The root of the chord.
+    root: Note,
+    /// The type of the chord.
+    chord_type: ChordType,
+}
+
+impl Chord {
+    /// Creates a new chord.
+    pub fn new(root: Note, chord_type: ChordType) -> Self {
+        Self { root, chord_type }
+    }
+
+    /// Returns the root of the chord.
+    pub fn root(&self) -> Note {
+        self.root
+    }
+
+    /// Returns the type of the chord.
+    pub fn chord_type(&self) -> ChordType {
+        self.chord_type
+    }
+
+    /// Returns `true` if both chords have the same root and type, in any octave.
+    pub fn matches(&self, other: &Chord) -> bool {
+        self.root.pitch_class() == other.root.pitch_class() && self.chord_type == other.chord_type
+    }
+
+    /// Returns the pitch classes of the chord tones.
+    pub fn pitch_classes(&self) -> Vec<u8> {
+        let root_pc = self.root.pitch_class();
+        self.chord_type
+            .intervals()
+            .iter()
+            .map(|&interval| (root_pc + interval) % 12)
+            .collect()
+    }
+
+    /// Returns the chord tones in root position, starting from the root.
+    pub fn notes(&self) -> Vec<Note> {
+        self.chord_type
+            .intervals()
+            .iter()
+            .map(|&interval| self.root + interval)
+            .collect()
+    }
+
+    /// Returns the chord tones on the fretboard with the standard tuning,
+    /// ordered from the lowest to the highest pitch without duplicates.
+    pub fn fretboard_notes(&self, frets: &RangeInclusive<u8>) -> Vec<Note> {
+        let pitch_classes = self.pitch_classes();
+        let mut notes: Vec<Note> = STANDARD_TUNING
+            .iter()
+            .flat_map(|open_note| frets.clone().map(move |fret| *open_note + fret))
+            .filter(|note| pitch_classes.contains(&note.pitch_class()))
+            .collect();
+        notes.sort_by_key(Note::semitone_index);
+        notes.dedup();
+        notes
+    }
+
+    /// Returns a playable voicing of the chord within the frets, with the root in the bass.
+    ///
+    /// Strings can only be muted below the lowest sounding string, the fretted
+    /// notes span at most four frets and open strings can't ring above a barre.
+    /// The fifth of a seventh chord can be left out.
+    ///
+    /// Voicings with more strings and a smaller span are preferred, then the ones
+    /// lower on the neck. Open strings are used if the frets include the nut.
+    ///
+    /// Returns `None` if the chord can't be voiced on at least four strings
+    /// (or all of them, if there are fewer).
+    pub fn voicing(
+        &self,
+        tuning: &Tuning,
+        frets: &RangeInclusive<u8>,
+    ) -> Option<Vec<FretPosition>> {
+        let pitch_classes = self.pitch_classes();
+        let options: Vec<Vec<u8>> = tuning
+            .iter()
+            .map(|open| {
+                frets
+                    .clone()
+                    .filter(|fret| pitch_classes.contains(&(*open + *fret).pitch_class()))
+                    .collect()
+            })
+            .collect();
+        let mut best = None;
+        self.search_voicings(tuning, &options, &mut Vec::new(), &mut best);
+        best.map(|(_, positions)| positions)
+    }
+
+    /// Tries every fret on the remaining strings, keeping the best complete voicing.
+    fn search_voicings(
+        &self,
+        tuning: &Tuning,
+        options: &[Vec<u8>],
+        positions: &mut Vec<FretPosition>,
+        best: &mut Option<(VoicingScore, Vec<FretPosition>)>,
+    ) {
+        let string = tuning.len() - options.len();
+        let Some((frets, rest)) = options.split_first() else {
+            if let Some(score) = self.voicing_score(tuning, positions) {
+                if !matches!(best, Some((best, _)) if *best >= score) {
+                    *best = Some((score, positions.clone()));
+                }
+            }
+            return;
+        };
+        // Mute the string, unless a lower string is already sounding
+        if positions.is_empty() {
+            self.search_voicings(tuning, rest, positions, best);
+        }
+        for &fret in frets {
+            let fretted = positions
+                .iter()
+                .map(|position| position.fret)
+                .chain(Some(fret))
+                .filter(|fret| *fret > 0);
+            let (low, high) = fretted.fold((u8::MAX, 0), |(low, high), fret| {
+                (low.min(fret), high.max(fret))
+            });
+            if high > low && high - low > MAX_VOICING_SPAN {
+                continue;
+            }
+            positions.push(FretPosition::new(string, fret));
+            self.search_voicings(tuning, rest, positions, best);
+            positions.pop();
+        }
+    }
+
+    /// Returns how good the voicing is, or `None` if it doesn't voice the chord.
+    fn voicing_score(&self, tuning: &Tuning, positions: &[FretPosition]) -> Option<VoicingScore> {
+        if positions.len() < tuning.len().min(4) {
+            return None;
+        }
+        let notes: Vec<Note> = positions
+            .iter()
+            .filter_map(|position| position.note(tuning))
+            .collect();
+        if notes.first()?.pitch_class() != self.root.pitch_class() {
+            return None;
+        }
+        let root_pc = self.root.pitch_class();
+        let covered = self.chord_type.intervals().iter().all(|interval| {
+            (*interval == 7 && self.chord_type.is_seventh())
+                || notes
+                    .iter()
+                    .any(|note| note.pitch_class() == (root_pc + interval) % 12)
+        });
+        if !covered {
+            return None;
+        }
+        let fretted = positions
+            .iter()
+            .map(|position| position.fret)
+            .filter(|fret| *fret > 0);
+        let low = fretted.clone().min().unwrap_or_default();
+        let span = fretted.max().unwrap_or_default() - low;
+        // A barre from the lowest string doesn't let the strings above it ring open
+        let is_barre = low > 0 && positions[0].fret == low;
+        if is_barre && positions.iter().any(|position| position.fret == 0) {
+            return None;
+        }
+        // Every string is worth two frets of span
+        Some((
+            positions.len() * 2 - span as usize,
+            Reverse(span),
+            Reverse(low),
+        ))
+    }
+
+    /// Returns the key the chord is spelled in: the major or minor key of the
+    /// root, depending on the third.
+    pub fn key(&self) -> Key {
+        if self.chord_type.intervals().contains(&4) {
+            Key::major(self.root)
+        } else {
+            Key::minor(self.root)
+        }
+    }
+
+    /// Returns the spelling of the chord tones.
+    pub fn spelling(&self) -> Spelling {
+        Spelling::Key(self.key())
+    }
+
+    /// Returns the name of the chord, e.g. `Bbm7`.
+    pub fn name(&self) -> String {
+        format!("{}{}", self.key().tonic_name(), self.chord_type.suffix())
+    }
+}
+
+impl FromStr for Chord {
+    type Err = Error;
+
+    /// Parses a chord from its name: a root letter (in any case), any number of
+    /// accidentals (`#`, `♯`, `b`, `♭`) and a suffix, e.g. `G`, `F#m`, `Bbmaj7` or `Bm7b5`.
+    ///
+    /// See [`ChordType::from_suffix`] for the accepted suffixes.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let first = s.chars().next().ok_or(Error::MissingLetter)?;
+        let letter = LETTERS
+            .iter()
+            .position(|l| *l == first.to_ascii_uppercase())
+            .ok_or_else(|| Error::InvalidLetter(first.to_string()))?;
+        let mut rest = &s[first.len_utf8()..];
+        let mut offset: i8 = 0;
+        while let Some(accidental) = rest.chars().next() {
+            offset += match accidental {
+                '#' | '♯' => 1,
+                'b' | '♭' => -1,
+                _ => break,
+            };
+            rest = &rest[accidental.len_utf8()..];
+        }
+        let chord_type =
+            ChordType::from_suffix(rest).ok_or_else(|| Error::UnknownChord(s.to_string()))?;
+        let pitch_class = (NATURAL_PITCH_CLASSES[letter] as i8 + offset).rem_euclid(12) as u8;
+        Ok(Chord::new(
+            Note::from_semitone_index(ROOT_OCTAVE * 12 + pitch_class),
+            chord_type,
+        ))
+    }
+}
+
+/// Parses a chord progression, e.g. `G D Em C`.
+///
+/// The chords can be separated by whitespace, commas or bars.
+pub fn parse_progression(s: &str) -> Result<Vec<Chord>, Error> {
+    s.split(|c: char| c.is_whitespace() || c == ',' || c == '|')
+        .filter(|chord| !chord.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Note::C(3), ChordType::Major, "C", vec![Note::C(3), Note::E(3), Note::G(3)])]
+    #[case(Note::A(2), ChordType::Minor7, "Am7", vec![Note::A(2), Note::C(3), Note::E(3), Note::G(3)])]
+    #[case(Note::ASharp(2), ChordType::Major7, "Bbmaj7", vec![Note::ASharp(2), Note::D(3), Note::F(3), Note::A(3)])]
+    #[case(Note::FSharp(2), ChordType::Minor, "F#m", vec![Note::FSharp(2), Note::A(2), Note::CSharp(3)])]
+    #[case(Note::B(2), ChordType::HalfDiminished7, "Bm7b5", vec![Note::B(2), Note::D(3), Note::F(3), Note::A(3)])]
+    #[case(Note::G(2), ChordType::Dominant7, "G7", vec![Note::G(2), Note::B(2), Note::D(3), Note::F(3)])]
+    fn test_chord(
+        #[case] root: Note,
+        #[case] chord_type: ChordType,
+        #[case] name: &str,
+        #[case] notes: Vec<Note>,
+    ) {
+        let chord = Chord::new(root, chord_type);
+        assert_eq!(name, chord.to_string());
+        assert_eq!(notes, chord.notes());
+    }
+
+    #[test]
+    fn test_fretboard_notes() {
+        let chord = Chord::new(Note::E(2), ChordType::Minor);
+        assert_eq!(
+            vec![
+                Note::E(2),
+                Note::G(2),
+                Note::B(2),
+                Note::E(3),
+                Note::G(3),
+                Note::B(3),
+                Note::E(4),
+                Note::G(4),
+            ],
+            chord.fretboard_notes(&(0..=3))
+        );
+    }
+
+    #[test]
+    fn test_next() {
+        assert_eq!(ChordType::Minor, ChordType::Major.next());
+        assert_eq!(ChordType::Major, ChordType::Diminished7.next());
+        assert!(ChordType::ALL
+            .iter()
+            .all(|t| t.is_seventh() == t.suffix().contains('7')));
+    }
+
+    #[rstest]
+    #[case("G", Note::G(3), ChordType::Major)]
+    #[case("em", Note::E(3), ChordType::Minor)]
+    #[case("F#m7", Note::FSharp(3), ChordType::Minor7)]
+    #[case("Bbmaj7", Note::ASharp(3), ChordType::Major7)]
+    #[case("Cb", Note::B(3), ChordType::Major)]
+    #[case("B♭m7♭5", Note::ASharp(3), ChordType::HalfDiminished7)]
+    #[case("Ddim7", Note::D(3), ChordType::Diminished7)]
+    #[case("C+", Note::C(3), ChordType::Augmented)]
+    fn test_parse(#[case] input: &str, #[case] root: Note, #[case] chord_type: ChordType) {
+        assert_eq!(Ok(Chord::new(root, chord_type)), input.parse());
+    }
+
+    #[rstest]
+    #[case("", Error::MissingLetter)]
+    #[case("H7", Error::InvalidLetter("H".to_string()))]
+    #[case("Csus4", Error::UnknownChord("Csus4".to_string()))]
+    fn test_parse_error(#[case] input: &str, #[case] error: Error) {
+        assert_eq!(Err(error), input.parse::<Chord>());
+    }
+
+    #[test]
+    fn test_parse_progression() {
+        let chords = parse_progression("G D, Em | C").unwrap();
+        let names: Vec<String> = chords.iter().map(Chord::name).collect();
+        assert_eq!(vec!["G", "D", "Em", "C"], names);
+        assert!(parse_progression("G X").is_err());
+        assert!(chords[0].matches(&Chord::new(Note::G(5), ChordType::Major)));
+        assert!(!chords[0].matches(&Chord::new(Note::G(3), ChordType::Minor)));
+    }
+
+    #[rstest]
+    #[case::open_e("E", 0..=4, vec![0, 2, 2, 1, 0, 0])]
+    #[case::open_a("A", 0..=4, vec![0, 2, 2, 2, 0])]
+    #[case::open_c("C", 0..=4, vec![3, 2, 0, 1, 0])]
+    #[case::open_em("Em", 0..=4, vec![0, 2, 2, 0, 0, 0])]
+    #[case::barre_f("F", 0..=4, vec![1, 3, 3, 2, 1, 1])]
+    #[case::barre_g("G", 5..=12, vec![10, 12, 12, 12, 10])]
+    #[case::dominant_seventh("E7", 0..=4, vec![0, 2, 0, 1, 0, 0])]
+    fn test_voicing(
+        #[case] chord: &str,
+        #[case] frets: RangeInclusive<u8>,
+        #[case] expected: Vec<u8>,
+    ) {
+        let chord: Chord = chord.parse().unwrap();
+        let voicing = chord.voicing(&STANDARD_TUNING, &frets).unwrap();
+        let voiced_frets: Vec<u8> = voicing.iter().map(|position| position.fret).collect();
+        assert_eq!(expected, voiced_frets);
+        // Muted strings are at the bottom
+        assert_eq!(STANDARD_TUNING.len() - expected.len(), voicing[0].string);
+    }
+
+    #[test]
+    fn test_no_voicing() {
+        let chord = Chord::new(Note::C(3), ChordType::Major);
+        assert_eq!(None, chord.voicing(&STANDARD_TUNING, &(0..=1)));
+    }
+}
@@ -0,0 +1,515 @@
+//! Chord diagram widget.
+
+use std::ops::RangeInclusive;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::StatefulWidget,
+};
+
+use crate::{
+    note::{Note, STANDARD_TUNING},
+    position::FretPosition,
+};
+
+/// The default number of frets shown in the diagram.
+const DEFAULT_FRET_COUNT: u8 = 4;
+
+/// The width of the starting fret label on the left side.
+const LABEL_WIDTH: u16 = 3;
+
+/// The default symbols of the fingers, from the index finger to the pinky.
+const FINGER_SYMBOLS: [char; 4] = ['①', '②', '③', '④'];
+
+/// State for the chord diagram widget.
+#[derive(Clone, Debug, Default)]
+pub struct ChordDiagramState {
+    /// The positions of the notes, strings without a position are muted.
+    pub positions: Vec<FretPosition>,
+    /// The fingers of the fretted positions (`1` being the index finger).
+    pub fingers: Vec<(FretPosition, u8)>,
+    /// The fret and the strings covered by a barre.
+    pub barre: Option<(u8, RangeInclusive<usize>)>,
+    /// The first fret to show, or `None` to pick it from the positions.
+    pub start_fret: Option<u8>,
+}
+
+impl ChordDiagramState {
+    /// Creates a new `ChordDiagramState` with the given positions.
+    pub fn new(positions: Vec<FretPosition>) -> Self {
+        Self {
+            positions,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the finger used for a position.
+    pub fn set_finger(&mut self, position: FretPosition, finger: u8) {
+        self.fingers.retain(|(p, _)| *p != position);
+        self.fingers.push((position, finger));
+    }
+
+    /// Sets a barre across the given strings.
+    pub fn set_barre(&mut self, fret: u8, strings: RangeInclusive<usize>) {
+        self.barre = Some((fret, strings));
+    }
+
+    /// Clears the positions, fingers and barre.
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.fingers.clear();
+        self.barre = None;
+    }
+
+    /// Returns the first fret to show for the given number of frets.
+    ///
+    /// Shapes that fit below the given number of frets start at the nut.
+    pub fn first_fret(&self, fret_count: u8) -> u8 {
+        if let Some(start_fret) = self.start_fret {
+            return start_fret.max(1);
+        }
+        let frets = self
+            .positions
+            .iter()
+            .map(|position| position.fret)
+            .chain(self.barre.iter().map(|(fret, _)| *fret))
+            .filter(|fret| *fret > 0);
+        match (frets.clone().min(), frets.max()) {
+            (Some(_), Some(max)) if max <= fret_count => 1,
+            (Some(min), _) => min,
+            _ => 1,
+        }
+    }
+
+    /// Returns the finger of the given position.
+    fn finger(&self, position: FretPosition) -> Option<u8> {
+        self.fingers
+            .iter()
+            .find(|(p, _)| *p == position)
+            .map(|(_, finger)| *finger)
+    }
+}
+
+/// A vertical chord diagram with the strings as columns and the frets as rows.
+///
+/// The lowest string is on the left. Uses a spaced layout with fret lines and
+/// string names when there is enough room, and a compact one otherwise.
+pub struct ChordDiagram {
+    /// The tuning of the strings, from the lowest string to the highest.
+    tuning: Vec<Note>,
+    /// The number of frets to show.
+    fret_count: u8,
+    /// The style for strings and frets.
+    grid_style: Style,
+    /// The style for the nut.
+    nut_style: Style,
+    /// The style for fretted notes and barres.
+    note_style: Style,
+    /// The style for open string markers.
+    open_style: Style,
+    /// The style for muted string markers.
+    muted_style: Style,
+    /// The style for the starting fret label and string names.
+    label_style: Style,
+    /// The symbols of the fingers, from the index finger to the pinky.
+    finger_symbols: [char; 4],
+}
+
+impl Default for ChordDiagram {
+    /// Creates a default `ChordDiagram` with standard guitar tuning.
+    fn default() -> Self {
+        Self {
+            tuning: STANDARD_TUNING.to_vec(),
+            fret_count: DEFAULT_FRET_COUNT,
+            grid_style: Style::default().fg(Color::DarkGray),
+            nut_style: Style::default().fg(Color::White),
+            note_style: Style::default().fg(Color::Yellow),
+            open_style: Style::default().fg(Color::Green),
+            muted_style: Style::default().fg(Color::Red),
+            label_style: Style::default().fg(Color::Magenta),
+            finger_symbols: FINGER_SYMBOLS,
+        }
+    }
+}
+
+impl ChordDiagram {
+    /// Creates a new `ChordDiagram` with default settings.
+    ///
+    /// This is equivalent to `ChordDiagram::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the tuning of the strings, from the lowest string to the highest.
+    pub fn with_tuning(mut self, tuning: Vec<Note>) -> Self {
+        self.tuning = tuning;
+        self
+    }
+
+    /// Sets the number of frets to show.
+    pub fn with_fret_count(mut self, fret_count: u8) -> Self {
+        self.fret_count = fret_count.max(1);
+        self
+    }
+
+    /// Sets the style for strings and frets.
+    pub fn with_grid_style(mut self, style: Style) -> Self {
+        self.grid_style = style;
+        self
+    }
+
+    /// Sets the style for the nut.
+    pub fn with_nut_style(mut self, style: Style) -> Self {
+        self.nut_style = style;
+        self
+    }
+
+    /// Sets the style for fretted notes and barres.
+    pub fn with_note_style(mut self, style: Style) -> Self {
+        self.note_style = style;
+        self
+    }
+
+    /// Sets the style for open string markers.
+    pub fn with_open_style(mut self, style: Style) -> Self {
+        self.open_style = style;
+        self
+    }
+
+    /// Sets the style for muted string markers.
+    pub fn with_muted_style(mut self, style: Style) -> Self {
+        self.muted_style = style;
+        self
+    }
+
+    /// Sets the style for the starting fret label and string names.
+    pub fn with_label_style(mut self, style: Style) -> Self {
+        self.label_style = style;
+        self
+    }
+
+    /// Sets the symbols of the fingers, from the index finger to the pinky.
+    ///
+    /// Plain digits can be used with fonts that lack the circled ones.
+    pub fn with_finger_symbols(mut self, symbols: [char; 4]) -> Self {
+        self.finger_symbols = symbols;
+        self
+    }
+
+    /// Returns the size of the spaced layout.
+    pub fn size(&self) -> (u16, u16) {
+        let strings = self.tuning.len() as u16;
+        (
+            LABEL_WIDTH + (strings * 2).saturating_sub(1),
+            3 + self.fret_count as u16 * 2,
+        )
+    }
+
+    /// Returns the size of the compact layout.
+    pub fn compact_size(&self) -> (u16, u16) {
+        (
+            LABEL_WIDTH + self.tuning.len() as u16,
+            2 + self.fret_count as u16,
+        )
+    }
+}
+
+impl StatefulWidget for &ChordDiagram {
+    type State = ChordDiagramState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let strings = self.tuning.len();
+        if strings == 0 {
+            return;
+        }
+        let (width, height) = self.size();
+        let spaced = area.width >= width && area.height >= height;
+        let step: u16 = if spaced { 2 } else { 1 };
+        let first_fret = state.first_fret(self.fret_count);
+
+        let area = area.intersection(buf.area);
+        let mut put = |x: u16, y: u16, symbol: char, style: Style| {
+            if x < area.right() && y < area.bottom() {
+                buf[(x, y)].set_char(symbol).set_style(style);
+            }
+        };
+        let column = |string: usize| area.x + LABEL_WIDTH + string as u16 * step;
+        let last = strings - 1;
+
+        // Open and muted markers
+        for string in 0..strings {
+            let open = state
+                .positions
+                .iter()
+                .any(|p| p.string == string && p.fret == 0);
+            let fretted = state.positions.iter().any(|p| p.string == string)
+                || matches!(&state.barre, Some((_, range)) if range.contains(&string));
+            if open {
+                put(column(string), area.y, 'o', self.open_style);
+            } else if !fretted {
+                put(column(string), area.y, 'x', self.muted_style);
+            }
+        }
+
+        // The nut, or the top fret when starting higher up
+        let top = area.y + 1;
+        for string in 0..strings {
+            let (symbol, fill, style) = match (first_fret == 1, spaced) {
+                (true, true) => (
+                    match string {
+                        0 => '╒',
+                        s if s == last => '╕',
+                        _ => '╤',
+                    },
+                    '═',
+                    self.nut_style,
+                ),
+                (true, false) => ('═', '═', self.nut_style),
+                (false, true) => (
+                    match string {
+                        0 => '┌',
+                        s if s == last => '┐',
+                        _ => '┬',
+                    },
+                    '─',
+                    self.grid_style,
+                ),
+                (false, false) => ('─', '─', self.grid_style),
+            };
+            put(column(string), top, symbol, style);
+            if spaced && string < last {
+                put(column(string) + 1, top, fill, style);
+            }
+        }
+        if first_fret > 1 {
+            let label = format!("{first_fret:<2}");
+            for (i, c) in label.chars().enumerate() {
+                put(area.x + i as u16, top + 1, c, self.label_style);
+            }
+        }
+
+        // Fret rows, each followed by a fret line in the spaced layout
+        for row in 0..self.fret_count {
+            let fret = first_fret + row;
+            let y = top + 1 + row as u16 * step;
+            let barre = state
+                .barre
+                .as_ref()
+                .filter(|(barre_fret, _)| *barre_fret == fret)
+                .map(|(_, range)| range.clone());
+
+            for string in 0..strings {
+                let x = column(string);
+                let position = FretPosition::new(string, fret);
+                let in_barre = barre.as_ref().is_some_and(|range| range.contains(&string));
+                let note = state.positions.contains(&position)
+                    || barre
+                        .as_ref()
+                        .is_some_and(|r| *r.start() == string || *r.end() == string);
+                if note {
+                    let symbol = match state.finger(position) {
+                        Some(finger @ 1..=4) => self.finger_symbols[finger as usize - 1],
+                        _ => '●',
+                    };
+                    put(x, y, symbol, self.note_style);
+                } else if in_barre {
+                    put(x, y, '━', self.note_style);
+                } else {
+                    put(x, y, '│', self.grid_style);
+                }
+
+                if spaced && string < last {
+                    let joined = barre
+                        .as_ref()
+                        .is_some_and(|r| r.contains(&string) && r.contains(&(string + 1)));
+                    if joined {
+                        put(x + 1, y, '━', self.note_style);
+                    }
+                    let symbol = if row + 1 == self.fret_count {
+                        match string {
+                            0 => '└',
+                            _ => '┴',
+                        }
+                    } else {
+                        match string {
+                            0 => '├',
+                            _ => '┼',
+                        }
+                    };
+                    put(x, y + 1, symbol, self.grid_style);
+                    put(x + 1, y + 1, '─', self.grid_style);
+                } else if spaced {
+                    let symbol = if row + 1 == self.fret_count {
+                        '┘'
+                    } else {
+                        '┤'
+                    };
+                    put(x, y + 1, symbol, self.grid_style);
+                }
+            }
+        }
+
+        // String names below the spaced layout
+        if spaced {
+            let y = top + 1 + self.fret_count as u16 * 2;
+            for (string, note) in self.tuning.iter().enumerate() {
+                for (i, c) in note.name().chars().take(step as usize).enumerate() {
+                    put(column(string) + i as u16, y, c, self.label_style);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{buffer::Buffer, layout::Rect};
+    use rstest::*;
+
+    fn plain() -> ChordDiagram {
+        ChordDiagram::default()
+            .with_grid_style(Style::default())
+            .with_nut_style(Style::default())
+            .with_note_style(Style::default())
+            .with_open_style(Style::default())
+            .with_muted_style(Style::default())
+            .with_label_style(Style::default())
+    }
+
+    /// C major, x32010.
+    fn c_major() -> ChordDiagramState {
+        let mut state = ChordDiagramState::new(vec![
+            FretPosition::new(1, 3),
+            FretPosition::new(2, 2),
+            FretPosition::new(3, 0),
+            FretPosition::new(4, 1),
+            FretPosition::new(5, 0),
+        ]);
+        state.set_finger(FretPosition::new(1, 3), 3);
+        state.set_finger(FretPosition::new(2, 2), 2);
+        state.set_finger(FretPosition::new(4, 1), 1);
+        state
+    }
+
+    /// A barre chord, x35553.
+    fn c_barre() -> ChordDiagramState {
+        let mut state = ChordDiagramState::new(vec![
+            FretPosition::new(1, 3),
+            FretPosition::new(2, 5),
+            FretPosition::new(3, 5),
+            FretPosition::new(4, 5),
+            FretPosition::new(5, 3),
+        ]);
+        state.set_barre(3, 1..=5);
+        state
+    }
+
+    #[rstest]
+    #[case::open_chord(
+        Rect::new(0, 0, 14, 11),
+        c_major(),
+        Buffer::with_lines([
+            "   x     o   o",
+            "   ╒═╤═╤═╤═╤═╕",
+            "   │ │ │ │ ① │",
+            "   ├─┼─┼─┼─┼─┤",
+            "   │ │ ② │ │ │",
+            "   ├─┼─┼─┼─┼─┤",
+            "   │ ③ │ │ │ │",
+            "   ├─┼─┼─┼─┼─┤",
+            "   │ │ │ │ │ │",
+            "   └─┴─┴─┴─┴─┘",
+            "   E A D G B E",
+        ])
+    )]
+    #[case::barre(
+        Rect::new(0, 0, 14, 11),
+        c_barre(),
+        Buffer::with_lines([
+            "   x          ",
+            "   ┌─┬─┬─┬─┬─┐",
+            "3  │ ●━━━━━━━●",
+            "   ├─┼─┼─┼─┼─┤",
+            "   │ │ │ │ │ │",
+            "   ├─┼─┼─┼─┼─┤",
+            "   │ │ ● ● ● │",
+            "   ├─┼─┼─┼─┼─┤",
+            "   │ │ │ │ │ │",
+            "   └─┴─┴─┴─┴─┘",
+            "   E A D G B E",
+        ])
+    )]
+    #[case::compact(
+        Rect::new(0, 0, 9, 6),
+        c_major(),
+        Buffer::with_lines([
+            "   x  o o",
+            "   ══════",
+            "   ││││①│",
+            "   ││②│││",
+            "   │③││││",
+            "   ││││││",
+        ])
+    )]
+    #[case::compact_barre(
+        Rect::new(0, 0, 9, 6),
+        c_barre(),
+        Buffer::with_lines([
+            "   x     ",
+            "   ──────",
+            "3  │●━━━●",
+            "   ││││││",
+            "   ││●●●│",
+            "   ││││││",
+        ])
+    )]
+    fn render_chord_diagram(
+        #[case] area: Rect,
+        #[case] mut state: ChordDiagramState,
+        #[case] expected: Buffer,
+    ) {
+        let mut buf = Buffer::empty(area);
+        plain().render(area, &mut buf, &mut state);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn render_plain_finger_symbols() {
+        let area = Rect::new(0, 0, 9, 6);
+        let mut buf = Buffer::empty(area);
+        plain()
+            .with_finger_symbols(['1', '2', '3', '4'])
+            .render(area, &mut buf, &mut c_major());
+        assert_eq!(
+            buf,
+            Buffer::with_lines([
+                "   x  o o",
+                "   ══════",
+                "   ││││1│",
+                "   ││2│││",
+                "   │3││││",
+                "   ││││││",
+            ])
+        );
+    }
+
+    #[rstest]
+    #[case::open(c_major(), 1)]
+    #[case::barre(c_barre(), 3)]
+    #[case::empty(ChordDiagramState::default(), 1)]
+    fn test_first_fret(#[case] state: ChordDiagramState, #[case] expected: u8) {
+        assert_eq!(expected, state.first_fret(DEFAULT_FRET_COUNT));
+    }
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!((14, 11), ChordDiagram::default().size());
+        assert_eq!(
+            (9, 7),
+            ChordDiagram::default().with_fret_count(5).compact_size()
+        );
+    }
+}
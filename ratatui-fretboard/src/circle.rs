@@ -0,0 +1,192 @@
+//! Circle of fifths widget.
+
+use std::f64::consts::PI;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::Widget,
+};
+
+use crate::{
+    chord::ChordType,
+    key::{Key, Mode},
+};
+
+/// The radius of the ring of minor keys, relative to the ring of major keys.
+const INNER_RING: f64 = 0.55;
+
+/// The circle of fifths, with the major keys on the outer ring and their
+/// relative minor keys on the inner ring.
+///
+/// The current key, its relative key and the keys of its diatonic chords are highlighted.
+pub struct CircleOfFifths {
+    /// The current key.
+    key: Key,
+    /// The style for the current key.
+    key_style: Style,
+    /// The style for the relative key.
+    relative_style: Style,
+    /// The style for the keys of the diatonic chords.
+    chord_style: Style,
+    /// The style for the other keys.
+    default_style: Style,
+}
+
+impl Default for CircleOfFifths {
+    /// Creates a `CircleOfFifths` for C major.
+    fn default() -> Self {
+        Self {
+            key: Key::default(),
+            key_style: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::REVERSED),
+            relative_style: Style::default().fg(Color::Yellow),
+            chord_style: Style::default().fg(Color::Blue),
+            default_style: Style::default().fg(Color::DarkGray),
+        }
+    }
+}
+
+impl CircleOfFifths {
+    /// Creates a new `CircleOfFifths` with default settings.
+    ///
+    /// This is equivalent to `CircleOfFifths::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the current key.
+    pub fn with_key(mut self, key: Key) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Sets the style for the current key.
+    pub fn with_key_style(mut self, style: Style) -> Self {
+        self.key_style = style;
+        self
+    }
+
+    /// Sets the style for the relative key.
+    pub fn with_relative_style(mut self, style: Style) -> Self {
+        self.relative_style = style;
+        self
+    }
+
+    /// Sets the style for the keys of the diatonic chords.
+    pub fn with_chord_style(mut self, style: Style) -> Self {
+        self.chord_style = style;
+        self
+    }
+
+    /// Sets the style for the other keys.
+    pub fn with_default_style(mut self, style: Style) -> Self {
+        self.default_style = style;
+        self
+    }
+
+    /// Returns the style of a key on the circle.
+    fn style(&self, key: Key) -> Style {
+        if key == self.key {
+            return self.key_style;
+        }
+        if key == self.key.relative() {
+            return self.relative_style;
+        }
+        let is_chord = self.key.chords().iter().any(|chord| {
+            Key::new(chord.root, key.mode()) == key
+                && matches!(
+                    (chord.chord_type, key.mode()),
+                    (ChordType::Major, Mode::Major) | (ChordType::Minor, Mode::Minor)
+                )
+        });
+        if is_chord {
+            self.chord_style
+        } else {
+            self.default_style
+        }
+    }
+}
+
+impl Widget for &CircleOfFifths {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        if area.width < 8 || area.height < 5 {
+            return;
+        }
+        // Cells are about twice as high as they are wide
+        let radius_y = f64::from(area.height - 1) / 2.0;
+        let radius_x = (radius_y * 2.0).min(f64::from(area.width - 4) / 2.0);
+        let center_x = f64::from(area.x) + f64::from(area.width) / 2.0;
+        let center_y = f64::from(area.y) + radius_y;
+
+        for (mode, factor) in [(Mode::Major, 1.0), (Mode::Minor, INNER_RING)] {
+            for (i, key) in Key::circle(mode).into_iter().enumerate() {
+                let angle = i as f64 * PI / 6.0;
+                let name = key.name();
+                let width = name.chars().count() as f64;
+                let x = center_x + angle.sin() * radius_x * factor - width / 2.0;
+                let y = center_y - angle.cos() * radius_y * factor;
+                let x = (x.round().max(f64::from(area.x)) as u16)
+                    .min(area.right().saturating_sub(name.chars().count() as u16));
+                let y = (y.round() as u16).clamp(area.y, area.bottom() - 1);
+                buf.set_stringn(x, y, &name, (area.right() - x) as usize, self.style(key));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::Note;
+    use ratatui::{buffer::Buffer, layout::Rect};
+
+    fn plain() -> CircleOfFifths {
+        CircleOfFifths::default()
+            .with_key_style(Style::default())
+            .with_relative_style(Style::default())
+            .with_chord_style(Style::default())
+            .with_default_style(Style::default())
+    }
+
+    #[test]
+    fn render_circle() {
+        let area = Rect::new(0, 0, 24, 11);
+        let mut buf = Buffer::empty(area);
+        plain().render(area, &mut buf);
+        let expected = Buffer::with_lines([
+            "            C           ",
+            "      F          G      ",
+            "  Bb       Am       D   ",
+            "        Dm    Em        ",
+            "      Gm        Bm      ",
+            " Eb   Cm        F#m   A ",
+            "      Fm       C#m      ",
+            "        Bbm  G#m    E   ",
+            "  Ab       D#m          ",
+            "      Db         B      ",
+            "           F#           ",
+        ]);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn render_highlights() {
+        let circle = plain()
+            .with_key(Key::major(Note::G(4)))
+            .with_key_style(Style::default().fg(Color::Yellow))
+            .with_relative_style(Style::default().fg(Color::Green))
+            .with_chord_style(Style::default().fg(Color::Blue));
+        let area = Rect::new(0, 0, 24, 11);
+        let mut buf = Buffer::empty(area);
+        circle.render(area, &mut buf);
+        // G, its relative Em, the chord Bm and the unrelated F#m
+        assert_eq!(Some(Color::Yellow), buf[(17, 1)].style().fg);
+        assert_eq!(Some(Color::Green), buf[(14, 3)].style().fg);
+        assert_eq!(Some(Color::Blue), buf[(16, 4)].style().fg);
+        assert_eq!(Some(Color::Reset), buf[(16, 5)].style().fg);
+    }
+}
@@ -9,6 +9,22 @@ pub enum Error {
     MissingOctave,
     /// Indicates that the note letter is invalid.
     InvalidLetter(String),
+    /// Indicates that the note octave is not an integer.
+    InvalidOctave(String),
+    /// Indicates that the MIDI number is not an integer between 0 and 127.
+    InvalidMidiNumber(String),
+    /// Indicates that the frequency is not a positive number.
+    InvalidFrequency(String),
+    /// Indicates that the note is outside of the supported range (C0 to G10).
+    OutOfRange(String),
+    /// Indicates that a degree of a scale formula is invalid.
+    InvalidDegree(String),
+    /// Indicates that there is no scale with the given name.
+    UnknownScale(String),
+    /// Indicates that the suffix of a chord name is unknown.
+    UnknownChord(String),
+    /// Indicates that a chord progression has no chords.
+    EmptyProgression,
 }
 
 impl std::fmt::Display for Error {
@@ -19,6 +35,14 @@ impl std::fmt::Display for Error {
             Error::InvalidLetter(letter) => {
                 write!(f, "invalid note letter: {letter}")
             }
+            Error::InvalidOctave(octave) => write!(f, "invalid note octave: {octave}"),
+            Error::InvalidMidiNumber(number) => write!(f, "invalid MIDI number: {number}"),
+            Error::InvalidFrequency(frequency) => write!(f, "invalid frequency: {frequency}"),
+            Error::OutOfRange(note) => write!(f, "note is out of range: {note}"),
+            Error::InvalidDegree(degree) => write!(f, "invalid scale degree: {degree}"),
+            Error::UnknownScale(name) => write!(f, "unknown scale: {name}"),
+            Error::UnknownChord(name) => write!(f, "unknown chord: {name}"),
+            Error::EmptyProgression => write!(f, "chord progression is empty"),
         }
     }
 }
@@ -0,0 +1,323 @@
+//! Fingering optimization.
+
+use std::ops::RangeInclusive;
+
+use crate::{
+    note::{Note, Tuning},
+    position::{positions, FretPosition},
+};
+
+/// The default maximum distance between the lowest and highest fretted note of a beat.
+pub const DEFAULT_MAX_SPAN: u8 = 4;
+
+/// The maximum number of shapes considered for a single beat.
+const MAX_SHAPES: usize = 256;
+
+/// The number of cheapest ways to play a single beat that are kept for moving between beats.
+const MAX_STATES: usize = 16;
+
+/// The cost of moving the hand by one fret.
+const MOVE_WEIGHT: f64 = 1.0;
+
+/// The cost of stretching the hand by one fret.
+const SPAN_WEIGHT: f64 = 0.5;
+
+/// The cost of playing one fret away from the preferred position.
+const PREFERENCE_WEIGHT: f64 = 0.5;
+
+/// The cost of playing one fret higher up the neck.
+const HEIGHT_WEIGHT: f64 = 0.1;
+
+/// Picks playable fret positions for a sequence of notes.
+///
+/// Uses dynamic programming over the possible shapes of each beat to
+/// minimize hand movement and stretch for the whole sequence.
+#[derive(Clone, Debug)]
+pub struct FingeringSolver {
+    /// The range of frets that can be used.
+    frets: RangeInclusive<u8>,
+    /// The maximum distance between the fretted notes of a beat.
+    max_span: u8,
+    /// The fret the hand should preferably stay at.
+    preferred_position: Option<u8>,
+}
+
+impl Default for FingeringSolver {
+    fn default() -> Self {
+        Self::new(0..=12)
+    }
+}
+
+impl FingeringSolver {
+    /// Creates a new solver using the given fret range.
+    pub fn new(frets: RangeInclusive<u8>) -> Self {
+        Self {
+            frets,
+            max_span: DEFAULT_MAX_SPAN,
+            preferred_position: None,
+        }
+    }
+
+    pub fn with_max_span(mut self, max_span: u8) -> Self {
+        self.max_span = max_span;
+        self
+    }
+
+    pub fn with_preferred_position(mut self, preferred_position: Option<u8>) -> Self {
+        self.preferred_position = preferred_position;
+        self
+    }
+
+    /// Returns the positions for each note of each beat.
+    ///
+    /// Notes that cannot be played with the tuning and fret range are left out.
+    /// If the notes of a beat cannot be played within the maximum span, the
+    /// span constraint is ignored for that beat.
+    pub fn solve<B: AsRef<[Note]>>(&self, beats: &[B], tuning: &Tuning) -> Vec<Vec<FretPosition>> {
+        let shapes: Vec<Vec<Vec<FretPosition>>> = beats
+            .iter()
+            .map(|beat| self.shapes(beat.as_ref(), tuning))
+            .collect();
+        // The cheapest ways of playing each beat, as a shape and the fret of the index finger
+        let states: Vec<Vec<(usize, u8)>> = shapes
+            .iter()
+            .map(|shapes| {
+                let mut states: Vec<(usize, u8, f64)> = shapes
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, shape)| {
+                        self.hand_positions(shape)
+                            .map(move |hand| (i, hand, self.shape_cost(shape, hand)))
+                    })
+                    .collect();
+                states.sort_by(|a, b| a.2.total_cmp(&b.2));
+                states.truncate(MAX_STATES);
+                states
+                    .into_iter()
+                    .map(|(shape, hand, _)| (shape, hand))
+                    .collect()
+            })
+            .collect();
+
+        // costs[i][j] is the lowest cost of playing the beats up to i, ending in state j
+        let mut costs: Vec<Vec<f64>> = Vec::with_capacity(states.len());
+        let mut previous: Vec<Vec<usize>> = Vec::with_capacity(states.len());
+        // The last beat with notes, empty beats do not move the hand
+        let mut last: Option<usize> = None;
+        for (i, beat_states) in states.iter().enumerate() {
+            let mut beat_costs = Vec::with_capacity(beat_states.len());
+            let mut beat_previous = Vec::with_capacity(beat_states.len());
+            for &(shape, hand) in beat_states {
+                let (from, cost) = last
+                    .and_then(|last| {
+                        states[last]
+                            .iter()
+                            .zip(&costs[last])
+                            .map(|((_, from), cost)| {
+                                cost + from.abs_diff(hand) as f64 * MOVE_WEIGHT
+                            })
+                            .enumerate()
+                            .min_by(|a, b| a.1.total_cmp(&b.1))
+                    })
+                    .unwrap_or((0, 0.0));
+                beat_costs.push(cost + self.shape_cost(&shapes[i][shape], hand));
+                beat_previous.push(from);
+            }
+            if !beat_states.is_empty() {
+                last = Some(i);
+            }
+            costs.push(beat_costs);
+            previous.push(beat_previous);
+        }
+
+        // Walk back from the cheapest final state
+        let mut result = vec![Vec::new(); states.len()];
+        let mut choice = last.and_then(|last| {
+            costs[last]
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(j, _)| j)
+        });
+        for i in (0..states.len()).rev() {
+            if states[i].is_empty() {
+                continue;
+            }
+            let Some(j) = choice else {
+                break;
+            };
+            result[i] = shapes[i][states[i][j].0].clone();
+            choice = Some(previous[i][j]);
+        }
+        result
+    }
+
+    /// Returns the frets the index finger can be at to play the shape.
+    ///
+    /// Shapes with only open strings can be played from anywhere.
+    fn hand_positions(&self, shape: &[FretPosition]) -> RangeInclusive<u8> {
+        let lowest = (*self.frets.start()).max(1);
+        let fretted = shape.iter().map(|p| p.fret).filter(|fret| *fret > 0);
+        match (fretted.clone().min(), fretted.max()) {
+            (Some(min), Some(max)) => max.saturating_sub(self.max_span).clamp(lowest, min)..=min,
+            _ => lowest..=(*self.frets.end()).max(lowest),
+        }
+    }
+
+    /// Returns the possible shapes for the notes of a single beat.
+    fn shapes(&self, notes: &[Note], tuning: &Tuning) -> Vec<Vec<FretPosition>> {
+        let mut options: Vec<Vec<FretPosition>> = notes
+            .iter()
+            .map(|note| positions(*note, tuning, &self.frets))
+            .filter(|options| !options.is_empty())
+            .collect();
+        if options.is_empty() {
+            return Vec::new();
+        }
+        // Notes with fewer options first, so that conflicts are found early
+        options.sort_by_key(|options| options.len());
+
+        let mut shapes = Vec::new();
+        self.collect_shapes(&options, &mut Vec::new(), &mut shapes, true);
+        if shapes.is_empty() {
+            self.collect_shapes(&options, &mut Vec::new(), &mut shapes, false);
+        }
+        shapes
+    }
+
+    /// Collects the shapes that put every note on a different string.
+    fn collect_shapes(
+        &self,
+        options: &[Vec<FretPosition>],
+        current: &mut Vec<FretPosition>,
+        shapes: &mut Vec<Vec<FretPosition>>,
+        limit_span: bool,
+    ) {
+        if shapes.len() >= MAX_SHAPES {
+            return;
+        }
+        let Some((first, rest)) = options.split_first() else {
+            let mut shape = current.clone();
+            shape.sort();
+            shapes.push(shape);
+            return;
+        };
+
+        for position in first {
+            if current.iter().any(|p| p.string == position.string) {
+                continue;
+            }
+            current.push(*position);
+            if !limit_span || span(current) <= self.max_span {
+                self.collect_shapes(rest, current, shapes, limit_span);
+            }
+            current.pop();
+        }
+    }
+
+    /// Returns the cost of playing a shape with the index finger at the given fret.
+    fn shape_cost(&self, shape: &[FretPosition], hand: u8) -> f64 {
+        if shape.iter().all(|position| position.fret == 0) {
+            // The hand is free while only open strings are played
+            return 0.0;
+        }
+        let preference = self
+            .preferred_position
+            .map_or(0.0, |preferred| preferred.abs_diff(hand) as f64);
+        span(shape) as f64 * SPAN_WEIGHT
+            + preference * PREFERENCE_WEIGHT
+            + hand as f64 * HEIGHT_WEIGHT
+    }
+}
+
+/// Returns the distance between the lowest and highest fretted note.
+fn span(shape: &[FretPosition]) -> u8 {
+    let frets = shape.iter().map(|p| p.fret).filter(|fret| *fret > 0);
+    match (frets.clone().min(), frets.max()) {
+        (Some(min), Some(max)) => max - min,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::STANDARD_TUNING;
+    use rstest::rstest;
+    use Note::*;
+
+    fn positions_of(shape: &[(usize, u8)]) -> Vec<FretPosition> {
+        shape
+            .iter()
+            .map(|&(string, fret)| FretPosition::new(string, fret))
+            .collect()
+    }
+
+    #[rstest]
+    #[case::lowest(None, vec![(3, 2)])]
+    #[case::preferred(Some(7), vec![(2, 7)])]
+    #[case::preferred_high(Some(12), vec![(1, 12)])]
+    fn test_preferred_position(#[case] preferred: Option<u8>, #[case] expected: Vec<(usize, u8)>) {
+        let solver = FingeringSolver::new(0..=17).with_preferred_position(preferred);
+        let result = solver.solve(&[[A(3)]], &STANDARD_TUNING);
+        assert_eq!(vec![positions_of(&expected)], result);
+    }
+
+    #[test]
+    fn test_minimizes_movement() {
+        // A scale run that fits into the 5th position
+        let notes = [[A(2)], [B(2)], [C(3)], [D(3)], [E(3)]];
+        let solver = FingeringSolver::new(1..=12).with_preferred_position(Some(5));
+        let result = solver.solve(&notes, &STANDARD_TUNING);
+        assert_eq!(
+            vec![
+                positions_of(&[(0, 5)]),
+                positions_of(&[(0, 7)]),
+                positions_of(&[(0, 8)]),
+                positions_of(&[(1, 5)]),
+                positions_of(&[(1, 7)]),
+            ],
+            result
+        );
+    }
+
+    #[test]
+    fn test_respects_max_span() {
+        // Power chord on the A string
+        let notes = [[C(3), G(3)]];
+        for max_span in [2, 4] {
+            let solver = FingeringSolver::new(1..=12).with_max_span(max_span);
+            let result = solver.solve(&notes, &STANDARD_TUNING);
+            assert!(span(&result[0]) <= max_span, "{result:?}");
+            assert_eq!(2, result[0].len());
+        }
+
+        // Too tight to play at all, the constraint is dropped
+        let solver = FingeringSolver::new(1..=12).with_max_span(0);
+        assert_eq!(2, solver.solve(&notes, &STANDARD_TUNING)[0].len());
+    }
+
+    #[test]
+    fn test_uses_distinct_strings() {
+        let notes = [[E(2), A(2), D(3), G(3), B(3), E(4)]];
+        let result = FingeringSolver::default().solve(&notes, &STANDARD_TUNING);
+        assert_eq!(
+            vec![positions_of(&[
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (5, 0)
+            ])],
+            result
+        );
+    }
+
+    #[test]
+    fn test_skips_unplayable() {
+        let notes = [vec![C(1)], vec![], vec![E(2), C(1)]];
+        let result = FingeringSolver::default().solve(&notes, &STANDARD_TUNING);
+        assert_eq!(vec![vec![], vec![], positions_of(&[(0, 0)])], result);
+    }
+}
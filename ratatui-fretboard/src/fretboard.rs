@@ -5,12 +5,16 @@ use std::ops::RangeInclusive;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::StatefulWidget,
 };
 
-use crate::note::{Note, STANDARD_TUNING};
+use crate::{
+    interval::Interval,
+    note::{Note, Spelling, STANDARD_TUNING},
+    position::FretPosition,
+};
 
 /// Standard guitar fret markers (single dots).
 const FRET_MARKERS: [u8; 9] = [3, 5, 7, 9, 12, 15, 17, 19, 21];
@@ -97,11 +101,169 @@ impl FretboardState {
     }
 }
 
+/// What is marked on the fretboard besides the notes of [`FretboardState`], e.g. the root
+/// that labels are relative to or a heatmap of how well each position is known.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FretboardMarks {
+    /// The positions the ghost notes should be played at.
+    ///
+    /// If empty, ghost notes are shown at every position.
+    ghost_positions: Vec<FretPosition>,
+    /// The root that intervals and scale degrees are labeled relative to.
+    root: Option<Note>,
+    /// The note that should be played next, e.g. in a practice sequence.
+    next_note: Option<Note>,
+    /// How well each position is known (`0.0..=1.0`), drawn as a heatmap.
+    heatmap: Vec<(FretPosition, f64)>,
+    /// The positions that are highlighted as active, regardless of the active notes.
+    active_positions: Vec<FretPosition>,
+    /// Whether the ghost notes match every octave of their pitch class.
+    match_pitch_class: bool,
+}
+
+impl FretboardMarks {
+    /// Limits the ghost notes to the given positions.
+    pub fn set_ghost_positions(&mut self, positions: Vec<FretPosition>) {
+        self.ghost_positions = positions;
+    }
+
+    /// Shows the ghost notes at every position again.
+    pub fn clear_ghost_positions(&mut self) {
+        self.ghost_positions.clear();
+    }
+
+    /// Sets the root that intervals and scale degrees are labeled relative to.
+    ///
+    /// Only the pitch class of the root matters, not its octave.
+    pub fn set_root(&mut self, root: Option<Note>) {
+        self.root = root;
+    }
+
+    /// Sets the note that should be played next.
+    ///
+    /// It is highlighted on the positions of the ghost notes.
+    pub fn set_next_note(&mut self, note: Option<Note>) {
+        self.next_note = note;
+    }
+
+    /// Sets how well each position is known (`0.0..=1.0`).
+    ///
+    /// The positions are drawn with the background of the heatmap colors.
+    pub fn set_heatmap(&mut self, heatmap: Vec<(FretPosition, f64)>) {
+        self.heatmap = heatmap;
+    }
+
+    /// Highlights a single position as active.
+    pub fn set_active_position(&mut self, position: FretPosition) {
+        if !self.active_positions.contains(&position) {
+            self.active_positions.push(position);
+        }
+    }
+
+    /// Highlights multiple positions as active.
+    pub fn set_active_positions(&mut self, positions: Vec<FretPosition>) {
+        for position in positions {
+            self.set_active_position(position);
+        }
+    }
+
+    /// Clears all positions highlighted as active.
+    pub fn clear_active_positions(&mut self) {
+        self.active_positions.clear();
+    }
+
+    /// Sets whether the ghost notes match every octave of their pitch class.
+    pub fn set_match_pitch_class(&mut self, match_pitch_class: bool) {
+        self.match_pitch_class = match_pitch_class;
+    }
+
+    /// Returns whether the note is one of the ghost notes.
+    fn is_ghost(&self, ghost_notes: &[Note], note: Note) -> bool {
+        if self.match_pitch_class {
+            ghost_notes
+                .iter()
+                .any(|ghost| ghost.pitch_class() == note.pitch_class())
+        } else {
+            ghost_notes.contains(&note)
+        }
+    }
+
+    /// Returns the interval from the root up to the pitch class of the note.
+    fn interval_from_root(&self, note: Note) -> Option<Interval> {
+        self.root
+            .map(|root| Interval::new((note.pitch_class() + 12 - root.pitch_class()) % 12))
+    }
+
+    /// Returns the scale degree of the note, counting the pitch classes of the
+    /// ghost notes upwards from the root.
+    fn degree(&self, ghost_notes: &[Note], note: Note) -> Option<usize> {
+        let root = self.root?;
+        let mut pitch_classes: Vec<u8> = ghost_notes
+            .iter()
+            .map(|ghost| (ghost.pitch_class() + 12 - root.pitch_class()) % 12)
+            .collect();
+        pitch_classes.sort_unstable();
+        pitch_classes.dedup();
+        let interval = self.interval_from_root(note)?.semitones();
+        pitch_classes
+            .iter()
+            .position(|pc| *pc == interval)
+            .map(|index| index + 1)
+    }
+}
+
+/// What is drawn on the highlighted positions of the fretboard.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NoteLabel {
+    /// The active and ghost note symbols.
+    #[default]
+    Symbol,
+    /// The note names, e.g. `C#`.
+    NoteName,
+    /// The intervals from the root, e.g. `R`, `b3` or `5`.
+    Interval,
+    /// The scale degrees, counting the ghost notes upwards from the root.
+    Degree,
+}
+
+impl NoteLabel {
+    /// Returns the label of the note, and a single character version of it for narrow frets
+    /// with whether it lost an accidental.
+    ///
+    /// Returns `None` if the note can't be labeled, e.g. there is no root.
+    fn label(
+        &self,
+        note: Note,
+        spelling: Spelling,
+        marks: &FretboardMarks,
+        ghost_notes: &[Note],
+    ) -> Option<(String, char, bool)> {
+        let label = match self {
+            NoteLabel::Symbol => return None,
+            NoteLabel::NoteName => note.spelled_name(spelling),
+            NoteLabel::Interval => marks.interval_from_root(note)?.degree_name().to_string(),
+            NoteLabel::Degree => marks.degree(ghost_notes, note)?.to_string(),
+        };
+        // Drop the accidentals, keep the root marker and the number of the degree
+        let (short, dropped) = match self {
+            NoteLabel::NoteName => (label.chars().next()?, &label[1..]),
+            _ => {
+                let short = label.chars().last()?;
+                (short, &label[..label.len() - short.len_utf8()])
+            }
+        };
+        let altered = dropped.contains(['b', '#', 'x', '♭', '♯', '𝄫', '𝄪']);
+        Some((label, short, altered))
+    }
+}
+
 /// Represents a fretboard widget for displaying musical notes
 /// and their positions on a guitar fretboard.
 pub struct Fretboard {
     /// The names of the strings on the fretboard.
     tuning: Vec<Note>,
+    /// The spelling of the string names.
+    spelling: Spelling,
     /// The style for fret numbers.
     fret_number_style: Style,
     /// The style for note names.
@@ -116,10 +278,22 @@ pub struct Fretboard {
     ghost_note_style: Style,
     /// The symbol used to represent ghost notes on the fretboard.
     ghost_note_symbol: char,
+    /// The style for ghost notes that are the root.
+    root_style: Style,
+    /// The style for the ghost note that should be played next.
+    next_note_style: Style,
+    /// The style added to shortened labels that lost their accidental.
+    altered_style: Style,
+    /// What is drawn on the highlighted positions.
+    note_label: NoteLabel,
     /// The symbol used to represent fret markers.
     fret_marker_symbol: char,
     /// The style for fret markers.
     fret_marker_style: Style,
+    /// The background colors of the heatmap, from the least to the best known positions.
+    heatmap_colors: Vec<Color>,
+    /// What is marked besides the active and ghost notes.
+    marks: FretboardMarks,
 }
 
 impl Default for Fretboard {
@@ -127,6 +301,7 @@ impl Default for Fretboard {
     fn default() -> Self {
         Self {
             tuning: STANDARD_TUNING.to_vec(),
+            spelling: Spelling::default(),
             fret_number_style: Style::default().fg(Color::Magenta),
             note_name_style: Style::default().fg(Color::Green),
             active_note_style: Style::default().fg(Color::Yellow),
@@ -134,8 +309,20 @@ impl Default for Fretboard {
             active_string_style: Style::default().fg(Color::Yellow),
             ghost_note_style: Style::default().fg(Color::Blue),
             ghost_note_symbol: '✖',
+            root_style: Style::default().fg(Color::Red),
+            next_note_style: Style::default().fg(Color::Green),
+            altered_style: Style::default().add_modifier(Modifier::UNDERLINED),
+            note_label: NoteLabel::default(),
             fret_marker_symbol: '•',
             fret_marker_style: Style::default().fg(Color::DarkGray),
+            heatmap_colors: vec![
+                Color::Red,
+                Color::LightRed,
+                Color::Yellow,
+                Color::LightGreen,
+                Color::Green,
+            ],
+            marks: FretboardMarks::default(),
         }
     }
 }
@@ -154,6 +341,12 @@ impl Fretboard {
         self
     }
 
+    /// Sets the spelling of the string names, e.g. `Eb` instead of `D#`.
+    pub fn with_spelling(mut self, spelling: Spelling) -> Self {
+        self.spelling = spelling;
+        self
+    }
+
     /// Sets the style for fret numbers.
     pub fn with_fret_number_style(mut self, style: Style) -> Self {
         self.fret_number_style = style;
@@ -196,6 +389,34 @@ impl Fretboard {
         self
     }
 
+    /// Sets the style for ghost notes that are the root.
+    pub fn with_root_style(mut self, style: Style) -> Self {
+        self.root_style = style;
+        self
+    }
+
+    /// Sets the style for the ghost note that should be played next.
+    pub fn with_next_note_style(mut self, style: Style) -> Self {
+        self.next_note_style = style;
+        self
+    }
+
+    /// Sets the style added to shortened labels that lost their accidental.
+    ///
+    /// This tells e.g. `b3` and `3` apart when both are shortened to `3` on narrow frets.
+    pub fn with_altered_style(mut self, style: Style) -> Self {
+        self.altered_style = style;
+        self
+    }
+
+    /// Sets what is drawn on the highlighted positions.
+    ///
+    /// Labels that don't fit in a fret are shortened to a single character.
+    pub fn with_note_label(mut self, label: NoteLabel) -> Self {
+        self.note_label = label;
+        self
+    }
+
     /// Sets the symbol used to represent fret markers.
     pub fn with_fret_marker_symbol(mut self, symbol: char) -> Self {
         self.fret_marker_symbol = symbol;
@@ -207,6 +428,26 @@ impl Fretboard {
         self.fret_marker_style = style;
         self
     }
+
+    /// Sets the background colors of the heatmap, from the least to the best known positions.
+    pub fn with_heatmap_colors(mut self, colors: Vec<Color>) -> Self {
+        self.heatmap_colors = colors;
+        self
+    }
+
+    /// Sets what is marked besides the active and ghost notes, e.g. the root or a heatmap.
+    pub fn with_marks(mut self, marks: FretboardMarks) -> Self {
+        self.marks = marks;
+        self
+    }
+
+    /// Returns the heatmap color of the position, if it is on the heatmap.
+    fn heatmap_color(&self, position: FretPosition) -> Option<Color> {
+        let (_, value) = self.marks.heatmap.iter().find(|(p, _)| *p == position)?;
+        let steps = self.heatmap_colors.len().checked_sub(1)?;
+        let index = (value.clamp(0.0, 1.0) * steps as f64).round() as usize;
+        self.heatmap_colors.get(index).copied()
+    }
 }
 
 impl StatefulWidget for &Fretboard {
@@ -231,7 +472,7 @@ impl StatefulWidget for &Fretboard {
             let base_note = *string_note;
 
             // Draw string name
-            let name = base_note.to_string();
+            let name = base_note.spelled(self.spelling);
             let mut spans: Vec<Span> = vec![
                 Span::from(format!("{name:<3}")).style(self.note_name_style),
                 "║".into(),
@@ -248,25 +489,48 @@ impl StatefulWidget for &Fretboard {
                 let note = base_note + *fret_num;
 
                 let fret_width = if j == 0 { 1 } else { fret_width };
-                let highlight_active = state.active_notes.contains(&note);
-                let highlight_ghost = state.ghost_notes.contains(&note);
+                let position = FretPosition::new(self.tuning.len() - 1 - i, *fret_num);
+                let marks = &self.marks;
+                let highlight_active = state.active_notes.contains(&note)
+                    || marks.active_positions.contains(&position);
+                let highlight_ghost = marks.is_ghost(&state.ghost_notes, note)
+                    && (marks.ghost_positions.is_empty()
+                        || marks.ghost_positions.contains(&position));
 
                 let has_marker = FRET_MARKERS.contains(fret_num);
                 let has_double = DOUBLE_MARKERS.contains(fret_num);
 
-                let symbol: Vec<Span> = if (highlight_active || highlight_ghost) && fret_num != &0 {
-                    let left_pad = (fret_width - 1) / 2;
-                    let right_pad = fret_width - 1 - left_pad;
+                let mut symbol: Vec<Span> = if (highlight_active || highlight_ghost)
+                    && fret_num != &0
+                {
+                    let is_root = marks.interval_from_root(note) == Some(Interval::UNISON);
+                    let (symbol, style) = if highlight_active {
+                        (self.active_note_symbol, self.active_note_style)
+                    } else if marks.next_note == Some(note) {
+                        (self.ghost_note_symbol, self.next_note_style)
+                    } else if is_root {
+                        (self.ghost_note_symbol, self.root_style)
+                    } else {
+                        (self.ghost_note_symbol, self.ghost_note_style)
+                    };
+                    let label =
+                        self.note_label
+                            .label(note, self.spelling, marks, &state.ghost_notes);
+                    let (label, style) = match label {
+                        Some((label, _, _)) if label.chars().count() <= fret_width => {
+                            (label, style)
+                        }
+                        Some((_, short, true)) => {
+                            (short.to_string(), style.patch(self.altered_style))
+                        }
+                        Some((_, short, false)) => (short.to_string(), style),
+                        None => (symbol.to_string(), style),
+                    };
+                    let left_pad = (fret_width - label.chars().count()) / 2;
+                    let right_pad = fret_width - label.chars().count() - left_pad;
                     vec![
                         Span::styled("─".repeat(left_pad), string_style),
-                        if highlight_active {
-                            Span::styled(
-                                self.active_note_symbol.to_string(),
-                                self.active_note_style,
-                            )
-                        } else {
-                            Span::styled(self.ghost_note_symbol.to_string(), self.ghost_note_style)
-                        },
+                        Span::styled(label, style),
                         Span::styled("─".repeat(right_pad), string_style),
                     ]
                 } else if has_marker
@@ -283,6 +547,12 @@ impl StatefulWidget for &Fretboard {
                 } else {
                     vec![Span::styled("─".repeat(fret_width), string_style)]
                 };
+                if let Some(color) = self.heatmap_color(position) {
+                    symbol = symbol
+                        .into_iter()
+                        .map(|span| span.patch_style(Style::default().bg(color)))
+                        .collect();
+                }
 
                 if j == 0 {
                     spans.extend(symbol);
@@ -559,4 +829,272 @@ mod tests {
         fretboard.render(area, &mut buf, &mut state);
         assert_eq!(buf, expected);
     }
+
+    fn plain() -> Fretboard {
+        Fretboard::default()
+            .with_active_note_style(Style::default())
+            .with_active_string_style(Style::default())
+            .with_ghost_note_style(Style::default())
+            .with_root_style(Style::default())
+            .with_next_note_style(Style::default())
+            .with_altered_style(Style::default())
+            .with_fret_number_style(Style::default())
+            .with_note_name_style(Style::default())
+            .with_fret_marker_style(Style::default())
+    }
+
+    fn rooted(root: Note) -> FretboardMarks {
+        let mut marks = FretboardMarks::default();
+        marks.set_root(Some(root));
+        marks
+    }
+
+    fn ghost_positions(positions: Vec<FretPosition>) -> FretboardMarks {
+        let mut marks = FretboardMarks::default();
+        marks.set_ghost_positions(positions);
+        marks
+    }
+
+    #[rstest]
+    #[case::ghost_positions(
+        Rect::new(0, 0, 36, 7),
+        Fretboard::default()
+            .with_ghost_note_style(Style::default())
+            .with_fret_number_style(Style::default())
+            .with_note_name_style(Style::default())
+            .with_fret_marker_style(Style::default())
+            .with_marks(ghost_positions(vec![FretPosition::new(3, 5)])),
+        FretboardState {
+            active_notes: Vec::new(),
+            ghost_notes: vec![Note::C(4)],
+            frets: 0..=6,
+        },
+        Buffer::with_lines([
+            "E4 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "B3 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "G3 ║─┼─────┼─────┼─────┼─────┼──✖──║",
+            "D3 ║─┼─────┼─────┼──•──┼─────┼──•──║",
+            "A2 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "E2 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "     1     2     3     4     5     6",
+        ])
+    )]
+    #[case::flat_spelling(
+        Rect::new(0, 0, 20, 3),
+        Fretboard::default()
+            .with_tuning(vec![Note::DSharp(2), Note::GSharp(2)])
+            .with_spelling(Spelling::Flats)
+            .with_fret_number_style(Style::default())
+            .with_note_name_style(Style::default())
+            .with_fret_marker_style(Style::default()),
+        FretboardState {
+            active_notes: Vec::new(),
+            ghost_notes: Vec::new(),
+            frets: 0..=3,
+        },
+        Buffer::with_lines([
+            "Ab2║─┼─────┼─────║  ",
+            "Eb2║─┼─────┼─────║  ",
+            "     1     2     3  ",
+        ])
+    )]
+    #[case::interval_labels(
+        Rect::new(0, 0, 36, 7),
+        plain()
+            .with_note_label(NoteLabel::Interval)
+            .with_marks(rooted(Note::A(2))),
+        FretboardState {
+            active_notes: Vec::new(),
+            ghost_notes: vec![Note::C(3), Note::E(3), Note::A(3), Note::B(3)],
+            frets: 0..=6,
+        },
+        Buffer::with_lines([
+            "E4 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "B3 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "G3 ║─┼─────┼──R──┼─────┼──2──┼─────║",
+            "D3 ║─┼─────┼──5──┼──•──┼─────┼──•──║",
+            "A2 ║─┼─────┼─────┼─b3──┼─────┼─────║",
+            "E2 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "     1     2     3     4     5     6",
+        ])
+    )]
+    #[case::degree_labels(
+        Rect::new(0, 0, 36, 7),
+        plain()
+            .with_note_label(NoteLabel::Degree)
+            .with_marks(rooted(Note::A(2))),
+        FretboardState {
+            active_notes: Vec::new(),
+            ghost_notes: vec![Note::C(3), Note::E(3), Note::A(3), Note::B(3)],
+            frets: 0..=6,
+        },
+        Buffer::with_lines([
+            "E4 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "B3 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "G3 ║─┼─────┼──1──┼─────┼──2──┼─────║",
+            "D3 ║─┼─────┼──4──┼──•──┼─────┼──•──║",
+            "A2 ║─┼─────┼─────┼──3──┼─────┼─────║",
+            "E2 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "     1     2     3     4     5     6",
+        ])
+    )]
+    #[case::narrow_note_names(
+        Rect::new(0, 0, 7, 7),
+        plain().with_note_label(NoteLabel::NoteName),
+        FretboardState {
+            active_notes: vec![Note::F(2)],
+            ghost_notes: vec![Note::ASharp(2)],
+            frets: 0..=3,
+        },
+        Buffer::with_lines([
+            "E4 ║─┼─",
+            "B3 ║─┼─",
+            "G3 ║─┼─",
+            "D3 ║─┼─",
+            "A2 ║─┼A",
+            "E2 ║─┼F",
+            "     1 ",
+        ])
+    )]
+    #[case::missing_root(
+        Rect::new(0, 0, 36, 7),
+        plain().with_note_label(NoteLabel::Interval),
+        FretboardState {
+            active_notes: Vec::new(),
+            ghost_notes: vec![Note::C(3)],
+            frets: 0..=6,
+        },
+        Buffer::with_lines([
+            "E4 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "B3 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "G3 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "D3 ║─┼─────┼─────┼──•──┼─────┼──•──║",
+            "A2 ║─┼─────┼─────┼──✖──┼─────┼─────║",
+            "E2 ║─┼─────┼─────┼─────┼─────┼─────║",
+            "     1     2     3     4     5     6",
+        ])
+    )]
+    fn render_fretboard_marks(
+        #[case] area: Rect,
+        #[case] fretboard: Fretboard,
+        #[case] mut state: FretboardState,
+        #[case] expected: Buffer,
+    ) {
+        let mut buf = Buffer::empty(area);
+        fretboard.render(area, &mut buf, &mut state);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn render_root_style() {
+        let fretboard = plain()
+            .with_ghost_note_style(Style::default().fg(Color::Blue))
+            .with_root_style(Style::default().fg(Color::Red))
+            .with_marks(rooted(Note::A(0)));
+        let mut state = FretboardState::new(0..=6);
+        state.set_ghost_notes(vec![Note::C(3), Note::A(3)]);
+
+        let area = Rect::new(0, 0, 36, 7);
+        let mut buf = Buffer::empty(area);
+        fretboard.render(area, &mut buf, &mut state);
+
+        // A3 on the G string and C3 on the A string
+        assert_eq!(Some(Color::Red), buf[(14, 2)].style().fg);
+        assert_eq!(Some(Color::Blue), buf[(20, 4)].style().fg);
+    }
+
+    #[test]
+    fn render_altered_style() {
+        let fretboard = plain()
+            .with_note_label(NoteLabel::Interval)
+            .with_altered_style(Style::default().add_modifier(Modifier::UNDERLINED))
+            .with_marks(rooted(Note::A(2)));
+        let mut state = FretboardState::new(0..=3);
+        state.set_ghost_notes(vec![Note::C(4), Note::GSharp(3)]);
+
+        let area = Rect::new(0, 0, 7, 7);
+        let mut buf = Buffer::empty(area);
+        fretboard.render(area, &mut buf, &mut state);
+
+        // The b3 on the B string is shortened like a major third, but underlined
+        assert_eq!("3", buf[(6, 1)].symbol());
+        assert!(buf[(6, 1)].modifier.contains(Modifier::UNDERLINED));
+        assert_eq!("7", buf[(6, 2)].symbol());
+        assert!(!buf[(6, 2)].modifier.contains(Modifier::UNDERLINED));
+    }
+
+    #[test]
+    fn render_next_note_style() {
+        let mut marks = rooted(Note::A(0));
+        marks.set_next_note(Some(Note::A(3)));
+        let fretboard = plain()
+            .with_ghost_note_style(Style::default().fg(Color::Blue))
+            .with_root_style(Style::default().fg(Color::Red))
+            .with_next_note_style(Style::default().fg(Color::Green))
+            .with_marks(marks);
+        let mut state = FretboardState::new(0..=6);
+        state.set_ghost_notes(vec![Note::C(3), Note::A(3)]);
+
+        let area = Rect::new(0, 0, 36, 7);
+        let mut buf = Buffer::empty(area);
+        fretboard.render(area, &mut buf, &mut state);
+
+        // The next note wins over the root style
+        assert_eq!(Some(Color::Green), buf[(14, 2)].style().fg);
+        assert_eq!(Some(Color::Blue), buf[(20, 4)].style().fg);
+    }
+
+    #[test]
+    fn render_heatmap() {
+        let mut marks = FretboardMarks::default();
+        marks.set_heatmap(vec![
+            (FretPosition::new(0, 0), 0.0),
+            (FretPosition::new(0, 5), 0.6),
+            (FretPosition::new(5, 1), 1.0),
+        ]);
+        let fretboard = plain()
+            .with_heatmap_colors(vec![Color::Red, Color::Yellow, Color::Green])
+            .with_marks(marks);
+        let mut state = FretboardState::new(0..=6);
+        state.set_active_note(Note::A(2));
+
+        let area = Rect::new(0, 0, 36, 7);
+        let mut buf = Buffer::empty(area);
+        fretboard.render(area, &mut buf, &mut state);
+
+        assert_eq!(Color::Red, buf[(4, 5)].bg);
+        // The whole fret is colored, also below a note
+        assert_eq!(Color::Yellow, buf[(30, 5)].bg);
+        assert_eq!(Color::Yellow, buf[(32, 5)].bg);
+        assert_eq!(Color::Green, buf[(6, 0)].bg);
+        assert_eq!(Color::Reset, buf[(12, 0)].bg);
+    }
+
+    #[test]
+    fn render_active_positions_and_pitch_classes() {
+        let mut marks = FretboardMarks::default();
+        marks.set_match_pitch_class(true);
+        marks.set_active_position(FretPosition::new(0, 5));
+        let fretboard = plain().with_marks(marks);
+        let mut state = FretboardState::new(0..=6);
+        state.set_ghost_note(Note::A(4));
+
+        let area = Rect::new(0, 0, 36, 7);
+        let mut buf = Buffer::empty(area);
+        fretboard.render(area, &mut buf, &mut state);
+
+        // Every A is a ghost note, the one on the low E string is active
+        assert_eq!(
+            Buffer::with_lines([
+                "E4 ║─┼─────┼─────┼─────┼─────┼──✖──║",
+                "B3 ║─┼─────┼─────┼─────┼─────┼─────║",
+                "G3 ║─┼─────┼──✖──┼─────┼─────┼─────║",
+                "D3 ║─┼─────┼─────┼──•──┼─────┼──•──║",
+                "A2 ║─┼─────┼─────┼─────┼─────┼─────║",
+                "E2 ║─┼─────┼─────┼─────┼─────┼──⬤──║",
+                "     1     2     3     4     5     6",
+            ]),
+            buf
+        );
+    }
 }
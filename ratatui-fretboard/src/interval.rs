@@ -0,0 +1,256 @@
+//! Musical intervals.
+
+use std::fmt;
+
+/// The quality of an interval.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quality {
+    Perfect,
+    Major,
+    Minor,
+    Augmented,
+}
+
+impl Quality {
+    /// Returns the abbreviation of the quality, as used in interval names like `m3`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Quality::Perfect => "P",
+            Quality::Major => "M",
+            Quality::Minor => "m",
+            Quality::Augmented => "A",
+        }
+    }
+}
+
+impl fmt::Display for Quality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Quality::Perfect => "perfect",
+            Quality::Major => "major",
+            Quality::Minor => "minor",
+            Quality::Augmented => "augmented",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The distance between two notes, counted in semitones.
+///
+/// Since notes are stored without their spelling, an interval is named after
+/// its most common form, e.g. 6 semitones is an augmented fourth.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Interval {
+    /// The number of semitones.
+    semitones: u8,
+}
+
+impl Interval {
+    pub const UNISON: Interval = Interval::new(0);
+    pub const MINOR_SECOND: Interval = Interval::new(1);
+    pub const MAJOR_SECOND: Interval = Interval::new(2);
+    pub const MINOR_THIRD: Interval = Interval::new(3);
+    pub const MAJOR_THIRD: Interval = Interval::new(4);
+    pub const PERFECT_FOURTH: Interval = Interval::new(5);
+    pub const TRITONE: Interval = Interval::new(6);
+    pub const PERFECT_FIFTH: Interval = Interval::new(7);
+    pub const MINOR_SIXTH: Interval = Interval::new(8);
+    pub const MAJOR_SIXTH: Interval = Interval::new(9);
+    pub const MINOR_SEVENTH: Interval = Interval::new(10);
+    pub const MAJOR_SEVENTH: Interval = Interval::new(11);
+    pub const OCTAVE: Interval = Interval::new(12);
+    pub const MINOR_NINTH: Interval = Interval::new(13);
+    pub const MAJOR_NINTH: Interval = Interval::new(14);
+    pub const MINOR_TENTH: Interval = Interval::new(15);
+    pub const MAJOR_TENTH: Interval = Interval::new(16);
+    pub const PERFECT_ELEVENTH: Interval = Interval::new(17);
+    pub const AUGMENTED_ELEVENTH: Interval = Interval::new(18);
+    pub const PERFECT_TWELFTH: Interval = Interval::new(19);
+    pub const MINOR_THIRTEENTH: Interval = Interval::new(20);
+    pub const MAJOR_THIRTEENTH: Interval = Interval::new(21);
+    pub const MINOR_FOURTEENTH: Interval = Interval::new(22);
+    pub const MAJOR_FOURTEENTH: Interval = Interval::new(23);
+    pub const DOUBLE_OCTAVE: Interval = Interval::new(24);
+
+    /// Creates an interval spanning the given number of semitones.
+    pub const fn new(semitones: u8) -> Self {
+        Self { semitones }
+    }
+
+    /// Returns the number of semitones.
+    pub fn semitones(&self) -> u8 {
+        self.semitones
+    }
+
+    /// Returns the number of whole octaves the interval spans.
+    pub fn octaves(&self) -> u8 {
+        self.semitones / 12
+    }
+
+    /// Returns `true` if the interval is larger than an octave.
+    pub fn is_compound(&self) -> bool {
+        self.semitones > 12
+    }
+
+    /// Returns the interval reduced to a single octave, e.g. a ninth becomes a second.
+    ///
+    /// Octaves stay octaves and are not reduced to a unison.
+    pub fn simple(&self) -> Interval {
+        match self.semitones % 12 {
+            0 if self.semitones > 0 => Interval::OCTAVE,
+            semitones => Interval::new(semitones),
+        }
+    }
+
+    /// Returns the interval that adds up to an octave with this one, e.g. a fifth becomes a fourth.
+    pub fn inversion(&self) -> Interval {
+        Interval::new((12 - self.semitones % 12) % 12)
+    }
+
+    /// Returns the quality of the interval.
+    pub fn quality(&self) -> Quality {
+        match self.semitones % 12 {
+            0 | 5 | 7 => Quality::Perfect,
+            2 | 4 | 9 | 11 => Quality::Major,
+            1 | 3 | 8 | 10 => Quality::Minor,
+            _ => Quality::Augmented,
+        }
+    }
+
+    /// Returns the diatonic number of the interval, `1` being a unison and `8` an octave.
+    pub fn number(&self) -> u8 {
+        let number = match self.semitones % 12 {
+            0 => 1,
+            1 | 2 => 2,
+            3 | 4 => 3,
+            5 | 6 => 4,
+            7 => 5,
+            8 | 9 => 6,
+            _ => 7,
+        };
+        number + 7 * self.octaves()
+    }
+
+    /// Returns the short name of the interval, e.g. `m3` or `P8`.
+    pub fn short_name(&self) -> String {
+        format!("{}{}", self.quality().symbol(), self.number())
+    }
+
+    /// Returns the name of the interval relative to a root, e.g. `R`, `b3` or `5`.
+    ///
+    /// Compound intervals are named like their simple form.
+    pub fn degree_name(&self) -> &'static str {
+        match self.semitones % 12 {
+            0 => "R",
+            1 => "b2",
+            2 => "2",
+            3 => "b3",
+            4 => "3",
+            5 => "4",
+            6 => "b5",
+            7 => "5",
+            8 => "b6",
+            9 => "6",
+            10 => "b7",
+            _ => "7",
+        }
+    }
+
+    /// Adds two intervals, returning `None` on overflow.
+    pub fn checked_add(&self, other: Interval) -> Option<Interval> {
+        self.semitones
+            .checked_add(other.semitones)
+            .map(Interval::new)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = match self.number() {
+            1 => "unison".to_string(),
+            8 => "octave".to_string(),
+            15 => "double octave".to_string(),
+            n => {
+                let name = match n {
+                    2 => "second",
+                    3 => "third",
+                    4 => "fourth",
+                    5 => "fifth",
+                    6 => "sixth",
+                    7 => "seventh",
+                    9 => "ninth",
+                    10 => "tenth",
+                    11 => "eleventh",
+                    12 => "twelfth",
+                    13 => "thirteenth",
+                    14 => "fourteenth",
+                    _ => {
+                        let suffix = match (n % 10, n % 100) {
+                            (_, 11..=13) => "th",
+                            (1, _) => "st",
+                            (2, _) => "nd",
+                            (3, _) => "rd",
+                            _ => "th",
+                        };
+                        return write!(f, "{} {n}{suffix}", self.quality());
+                    }
+                };
+                name.to_string()
+            }
+        };
+        write!(f, "{} {number}", self.quality())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Interval::UNISON, "P1", "perfect unison")]
+    #[case(Interval::MINOR_THIRD, "m3", "minor third")]
+    #[case(Interval::TRITONE, "A4", "augmented fourth")]
+    #[case(Interval::PERFECT_FIFTH, "P5", "perfect fifth")]
+    #[case(Interval::MAJOR_SEVENTH, "M7", "major seventh")]
+    #[case(Interval::OCTAVE, "P8", "perfect octave")]
+    #[case(Interval::MAJOR_NINTH, "M9", "major ninth")]
+    #[case(Interval::AUGMENTED_ELEVENTH, "A11", "augmented eleventh")]
+    #[case(Interval::DOUBLE_OCTAVE, "P15", "perfect double octave")]
+    #[case(Interval::new(28), "M17", "major 17th")]
+    #[case(Interval::new(32), "m20", "minor 20th")]
+    #[case(Interval::new(36), "P22", "perfect 22nd")]
+    fn test_names(#[case] interval: Interval, #[case] short_name: &str, #[case] name: &str) {
+        assert_eq!(short_name, interval.short_name());
+        assert_eq!(name, interval.to_string());
+    }
+
+    #[rstest]
+    #[case(Interval::UNISON, "R")]
+    #[case(Interval::MINOR_THIRD, "b3")]
+    #[case(Interval::TRITONE, "b5")]
+    #[case(Interval::MINOR_SEVENTH, "b7")]
+    #[case(Interval::OCTAVE, "R")]
+    #[case(Interval::MAJOR_NINTH, "2")]
+    fn test_degree_name(#[case] interval: Interval, #[case] expected: &str) {
+        assert_eq!(expected, interval.degree_name());
+    }
+
+    #[rstest]
+    #[case(Interval::MAJOR_NINTH, Interval::MAJOR_SECOND, Interval::MINOR_SEVENTH)]
+    #[case(
+        Interval::PERFECT_FIFTH,
+        Interval::PERFECT_FIFTH,
+        Interval::PERFECT_FOURTH
+    )]
+    #[case(Interval::DOUBLE_OCTAVE, Interval::OCTAVE, Interval::UNISON)]
+    #[case(Interval::UNISON, Interval::UNISON, Interval::UNISON)]
+    fn test_simple_and_inversion(
+        #[case] interval: Interval,
+        #[case] simple: Interval,
+        #[case] inversion: Interval,
+    ) {
+        assert_eq!(simple, interval.simple());
+        assert_eq!(inversion, interval.inversion());
+    }
+}
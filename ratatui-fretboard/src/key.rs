@@ -0,0 +1,389 @@
+//! Musical keys.
+
+use std::{fmt, ops::RangeInclusive};
+
+use crate::{
+    chord::{Chord, ChordType},
+    interval::Interval,
+    note::{flat_name, spell_with_letter, Note, LETTERS},
+    scale::Scale,
+};
+
+/// The roman numerals of the scale degrees.
+const NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+/// The octave the tonic of a key is kept in.
+const TONIC_OCTAVE: u8 = 4;
+
+/// The mode of a key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Mode {
+    #[default]
+    Major,
+    Minor,
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Mode::Major => "major",
+            Mode::Minor => "minor",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl Mode {
+    /// Returns the scale of the mode.
+    pub fn scale(&self) -> Scale {
+        match self {
+            Mode::Major => Scale::MAJOR,
+            Mode::Minor => Scale::NATURAL_MINOR,
+        }
+    }
+}
+
+/// A chord built on a degree of a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiatonicChord {
+    /// The scale degree, `1` being the tonic.
+    pub degree: u8,
+    /// The root of the chord.
+    pub root: Note,
+    /// The type of the chord, a triad.
+    pub chord_type: ChordType,
+    /// The roman numeral of the chord, e.g. `ii` or `vii°`.
+    pub numeral: String,
+    /// The name of the chord spelled in the key, e.g. `Bb` or `F#m`.
+    pub name: String,
+}
+
+impl DiatonicChord {
+    /// Returns the chord.
+    pub fn chord(&self) -> Chord {
+        Chord::new(self.root, self.chord_type)
+    }
+}
+
+/// A key, made of a tonic and a mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    /// The tonic, always in the same octave.
+    tonic: Note,
+    /// The mode of the key.
+    mode: Mode,
+}
+
+impl Default for Key {
+    /// Creates the key of C major.
+    fn default() -> Self {
+        Self::major(Note::C(TONIC_OCTAVE))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.tonic_name(), self.mode)
+    }
+}
+
+impl Key {
+    /// Creates a new key, the octave of the tonic is ignored.
+    pub fn new(tonic: Note, mode: Mode) -> Self {
+        Self {
+            tonic: Note::from_semitone_index(tonic.pitch_class() + TONIC_OCTAVE * 12),
+            mode,
+        }
+    }
+
+    /// Creates a major key.
+    pub fn major(tonic: Note) -> Self {
+        Self::new(tonic, Mode::Major)
+    }
+
+    /// Creates a minor key.
+    pub fn minor(tonic: Note) -> Self {
+        Self::new(tonic, Mode::Minor)
+    }
+
+    /// Returns the major (or minor) keys in the order of the circle of fifths, starting from C (or A).
+    pub fn circle(mode: Mode) -> Vec<Key> {
+        let start = match mode {
+            Mode::Major => Key::major(Note::C(TONIC_OCTAVE)),
+            Mode::Minor => Key::minor(Note::A(TONIC_OCTAVE)),
+        };
+        std::iter::successors(Some(start), |key| Some(key.dominant()))
+            .take(12)
+            .collect()
+    }
+
+    /// Returns the tonic of the key.
+    pub fn tonic(&self) -> Note {
+        self.tonic
+    }
+
+    /// Returns the mode of the key.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the scale of the key.
+    pub fn scale(&self) -> Scale {
+        self.mode.scale()
+    }
+
+    /// Returns the number of sharps (positive) or flats (negative) in the key signature.
+    ///
+    /// Keys with six accidentals are written with sharps.
+    pub fn signature(&self) -> i8 {
+        let major = match self.mode {
+            Mode::Major => self.tonic.pitch_class(),
+            Mode::Minor => (self.tonic.pitch_class() + 3) % 12,
+        };
+        let fifths = (major * 7 % 12) as i8;
+        if fifths > 6 {
+            fifths - 12
+        } else {
+            fifths
+        }
+    }
+
+    /// Returns the position of the key on the circle of fifths, `0` being C major or A minor.
+    pub fn circle_index(&self) -> usize {
+        self.signature().rem_euclid(12) as usize
+    }
+
+    /// Returns the notes of the key, starting from the tonic.
+    pub fn notes(&self) -> Vec<Note> {
+        self.scale().notes(self.tonic)
+    }
+
+    /// Returns all notes of the key found on the given fret range.
+    pub fn fretboard_notes(&self, frets: &RangeInclusive<u8>) -> Vec<Note> {
+        self.scale().fretboard_notes(self.tonic, frets)
+    }
+
+    /// Returns the names of the notes of the key, spelled with the key signature.
+    pub fn note_names(&self) -> Vec<String> {
+        let letter = self.tonic_letter();
+        self.notes()
+            .iter()
+            .enumerate()
+            .map(|(degree, note)| spell_with_letter(note.pitch_class(), (letter + degree) % 7))
+            .collect()
+    }
+
+    /// Returns the name of the note spelled in this key.
+    ///
+    /// Natural notes outside of the key keep their name. Other notes are spelled as the scale
+    /// degree they form with the tonic (see [`Interval::degree_name`]), so the blue note `b5`
+    /// is `Eb` in A and `Db` in G.
+    pub fn spell(&self, note: Note) -> String {
+        let pitch_class = note.pitch_class();
+        if let Some(degree) = self
+            .notes()
+            .iter()
+            .position(|n| n.pitch_class() == pitch_class)
+        {
+            return self.note_names().swap_remove(degree);
+        }
+        if note.is_natural() {
+            return note.name().to_string();
+        }
+        let interval = Interval::new((pitch_class + 12 - self.tonic.pitch_class()) % 12);
+        let degree = interval
+            .degree_name()
+            .trim_start_matches('b')
+            .parse::<usize>()
+            .unwrap_or(1);
+        spell_with_letter(pitch_class, (self.tonic_letter() + degree - 1) % 7)
+    }
+
+    /// Returns the name of the tonic spelled in this key.
+    pub fn tonic_name(&self) -> String {
+        spell_with_letter(self.tonic.pitch_class(), self.tonic_letter())
+    }
+
+    /// Returns the short name of the key, e.g. `Bb` or `F#m`.
+    pub fn name(&self) -> String {
+        match self.mode {
+            Mode::Major => self.tonic_name(),
+            Mode::Minor => format!("{}m", self.tonic_name()),
+        }
+    }
+
+    /// Returns the relative key, sharing the same key signature.
+    pub fn relative(&self) -> Key {
+        match self.mode {
+            Mode::Major => Key::minor(self.tonic + 9),
+            Mode::Minor => Key::major(self.tonic + 3),
+        }
+    }
+
+    /// Returns the parallel key, sharing the same tonic.
+    pub fn parallel(&self) -> Key {
+        match self.mode {
+            Mode::Major => Key::minor(self.tonic),
+            Mode::Minor => Key::major(self.tonic),
+        }
+    }
+
+    /// Returns the key a fifth above, the next one clockwise on the circle of fifths.
+    pub fn dominant(&self) -> Key {
+        Key::new(self.tonic + 7, self.mode)
+    }
+
+    /// Returns the key a fifth below, the next one counterclockwise on the circle of fifths.
+    pub fn subdominant(&self) -> Key {
+        Key::new(self.tonic + 5, self.mode)
+    }
+
+    /// Returns the triad built on the degree of the key, `1` being the tonic.
+    pub fn chord(&self, degree: u8) -> Option<Chord> {
+        self.chords()
+            .into_iter()
+            .find(|chord| chord.degree == degree)
+            .map(|chord| chord.chord())
+    }
+
+    /// Returns the triads built on each degree of the key.
+    pub fn chords(&self) -> Vec<DiatonicChord> {
+        let notes = self.notes();
+        let names = self.note_names();
+        (0..notes.len())
+            .filter_map(|degree| {
+                let root = notes[degree];
+                let interval = |n: usize| {
+                    (notes[(degree + n) % notes.len()].pitch_class() + 12 - root.pitch_class()) % 12
+                };
+                let chord_type = ChordType::from_triad(interval(2), interval(4))?;
+                let numeral = match chord_type {
+                    ChordType::Minor | ChordType::Diminished => NUMERALS[degree].to_lowercase(),
+                    _ => NUMERALS[degree].to_string(),
+                };
+                let suffix = match chord_type {
+                    ChordType::Diminished | ChordType::Augmented => chord_type.suffix(),
+                    _ => "",
+                };
+                Some(DiatonicChord {
+                    degree: degree as u8 + 1,
+                    root,
+                    chord_type,
+                    numeral: format!("{numeral}{suffix}"),
+                    name: format!("{}{}", names[degree], chord_type.suffix()),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the index of the letter of the tonic in [`LETTERS`].
+    fn tonic_letter(&self) -> usize {
+        let pitch_class = self.tonic.pitch_class();
+        let name = if self.signature() < 0 {
+            flat_name(pitch_class)
+        } else {
+            self.tonic.name().to_string()
+        };
+        LETTERS
+            .iter()
+            .position(|letter| name.starts_with(*letter))
+            .expect("note names start with a letter")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use Note::*;
+
+    #[rstest]
+    #[case(Key::major(C(4)), 0, "C major")]
+    #[case(Key::major(A(2)), 3, "A major")]
+    #[case(Key::major(ASharp(4)), -2, "Bb major")]
+    #[case(Key::major(FSharp(4)), 6, "F# major")]
+    #[case(Key::major(CSharp(4)), -5, "Db major")]
+    #[case(Key::minor(A(4)), 0, "A minor")]
+    #[case(Key::minor(D(4)), -1, "D minor")]
+    #[case(Key::minor(DSharp(4)), 6, "D# minor")]
+    fn test_signature(#[case] key: Key, #[case] signature: i8, #[case] name: &str) {
+        assert_eq!(signature, key.signature());
+        assert_eq!(name, key.to_string());
+    }
+
+    #[rstest]
+    #[case(Key::major(C(4)), vec!["C", "D", "E", "F", "G", "A", "B"])]
+    #[case(Key::major(F(4)), vec!["F", "G", "A", "Bb", "C", "D", "E"])]
+    #[case(Key::major(FSharp(4)), vec!["F#", "G#", "A#", "B", "C#", "D#", "E#"])]
+    #[case(Key::minor(DSharp(4)), vec!["D#", "E#", "F#", "G#", "A#", "B", "C#"])]
+    #[case(Key::minor(G(4)), vec!["G", "A", "Bb", "C", "D", "Eb", "F"])]
+    fn test_note_names(#[case] key: Key, #[case] expected: Vec<&str>) {
+        assert_eq!(expected, key.note_names());
+    }
+
+    #[test]
+    fn test_spell() {
+        let key = Key::major(F(4));
+        assert_eq!("Bb", key.spell(ASharp(2)));
+        assert_eq!("Db", key.spell(CSharp(3)));
+        assert_eq!("F#", Key::major(G(4)).spell(FSharp(3)));
+        assert_eq!("E#", Key::major(FSharp(4)).spell(F(3)));
+        assert_eq!("Eb", Key::minor(A(4)).spell(DSharp(3)));
+        assert_eq!("Db", Key::minor(G(4)).spell(CSharp(3)));
+        assert_eq!("C#", Key::minor(A(4)).spell(CSharp(3)));
+    }
+
+    #[rstest]
+    #[case(Key::major(C(4)), vec!["I C", "ii Dm", "iii Em", "IV F", "V G", "vi Am", "vii° B°"])]
+    #[case(Key::minor(E(4)), vec!["i Em", "ii° F#°", "III G", "iv Am", "v Bm", "VI C", "VII D"])]
+    #[case(Key::major(ASharp(4)), vec!["I Bb", "ii Cm", "iii Dm", "IV Eb", "V F", "vi Gm", "vii° A°"])]
+    fn test_chords(#[case] key: Key, #[case] expected: Vec<&str>) {
+        let chords: Vec<String> = key
+            .chords()
+            .iter()
+            .map(|chord| format!("{} {}", chord.numeral, chord.name))
+            .collect();
+        assert_eq!(expected, chords);
+    }
+
+    #[test]
+    fn test_chord() {
+        let key = Key::major(G(4));
+        let progression: Vec<String> = [1, 5, 6, 4]
+            .iter()
+            .filter_map(|degree| key.chord(*degree))
+            .map(|chord| chord.name())
+            .collect();
+        assert_eq!(vec!["G", "D", "Em", "C"], progression);
+        assert_eq!(None, key.chord(8));
+    }
+
+    #[test]
+    fn test_related_keys() {
+        let key = Key::major(C(3));
+        assert_eq!(Key::major(C(4)), key);
+        assert_eq!(Key::minor(A(4)), key.relative());
+        assert_eq!(Key::minor(C(4)), key.parallel());
+        assert_eq!(Key::major(G(4)), key.dominant());
+        assert_eq!(Key::major(F(4)), key.subdominant());
+        assert_eq!(key, key.relative().relative());
+    }
+
+    #[test]
+    fn test_circle() {
+        let names: Vec<String> = Key::circle(Mode::Major).iter().map(Key::name).collect();
+        assert_eq!(
+            vec!["C", "G", "D", "A", "E", "B", "F#", "Db", "Ab", "Eb", "Bb", "F"],
+            names
+        );
+        let names: Vec<String> = Key::circle(Mode::Minor).iter().map(Key::name).collect();
+        assert_eq!(
+            vec!["Am", "Em", "Bm", "F#m", "C#m", "G#m", "D#m", "Bbm", "Fm", "Cm", "Gm", "Dm"],
+            names
+        );
+        assert!(Key::circle(Mode::Major)
+            .iter()
+            .enumerate()
+            .all(|(i, key)| key.circle_index() == i));
+    }
+}
@@ -0,0 +1,359 @@
+//! Piano keyboard widget.
+
+use std::ops::RangeInclusive;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::StatefulWidget,
+};
+
+use crate::{note::Note, FretboardState};
+
+/// The width of a white key, including its left border.
+const WHITE_KEY_WIDTH: u16 = 3;
+
+/// State for the keyboard widget.
+#[derive(Clone, Debug)]
+pub struct KeyboardState {
+    /// The currently active notes on the keyboard.
+    pub active_notes: Vec<Note>,
+    /// The notes that are being used for tracking.
+    pub ghost_notes: Vec<Note>,
+    /// The range of keys to display on the keyboard.
+    pub keys: RangeInclusive<Note>,
+}
+
+impl Default for KeyboardState {
+    /// Creates a default `KeyboardState` with two octaves from C3.
+    fn default() -> Self {
+        Self::new(Note::C(3)..=Note::B(4))
+    }
+}
+
+impl From<&FretboardState> for KeyboardState {
+    /// Creates a `KeyboardState` with the notes of the fretboard.
+    fn from(state: &FretboardState) -> Self {
+        Self {
+            active_notes: state.active_notes.clone(),
+            ghost_notes: state.ghost_notes.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+impl KeyboardState {
+    /// Creates a new `KeyboardState` with no active notes.
+    pub fn new(keys: RangeInclusive<Note>) -> Self {
+        Self {
+            active_notes: Vec::new(),
+            ghost_notes: Vec::new(),
+            keys,
+        }
+    }
+
+    /// Sets the active note on the keyboard.
+    pub fn set_active_note(&mut self, note: Note) {
+        if !self.active_notes.contains(&note) {
+            self.active_notes.push(note);
+        }
+    }
+
+    /// Sets multiple active notes on the keyboard.
+    pub fn set_active_notes(&mut self, notes: Vec<Note>) {
+        for note in notes {
+            self.set_active_note(note);
+        }
+    }
+
+    /// Sets a ghost note on the keyboard, which is a note
+    /// that is not currently active but is being tracked.
+    pub fn set_ghost_note(&mut self, note: Note) {
+        if !self.ghost_notes.contains(&note) {
+            self.ghost_notes.push(note);
+        }
+    }
+
+    /// Sets multiple ghost notes on the keyboard.
+    pub fn set_ghost_notes(&mut self, notes: Vec<Note>) {
+        for note in notes {
+            self.set_ghost_note(note);
+        }
+    }
+
+    /// Clears all active notes on the keyboard.
+    pub fn clear_active_notes(&mut self) {
+        self.active_notes.clear();
+    }
+
+    /// Clears all ghost notes on the keyboard.
+    pub fn clear_ghost_notes(&mut self) {
+        self.ghost_notes.clear();
+    }
+
+    /// Sets the range of keys to display on the keyboard.
+    pub fn set_keys(&mut self, keys: RangeInclusive<Note>) {
+        self.keys = keys;
+    }
+
+    /// Returns the white keys to display, with the black key after each of them.
+    ///
+    /// The range is widened to start and end on a white key.
+    fn white_keys(&self) -> Vec<(Note, Option<Note>)> {
+        let start = self.keys.start().semitone_index();
+        let end = self.keys.end().semitone_index();
+        let start = if Note::from_semitone_index(start).is_natural() {
+            start
+        } else {
+            start.saturating_sub(1)
+        };
+        let end = if Note::from_semitone_index(end).is_natural() {
+            end
+        } else {
+            end.saturating_add(1).min(127)
+        };
+
+        (start..=end)
+            .map(Note::from_semitone_index)
+            .filter(Note::is_natural)
+            .map(|note| {
+                let next = note + 1;
+                let black = (!next.is_natural() && next.semitone_index() < end).then_some(next);
+                (note, black)
+            })
+            .collect()
+    }
+}
+
+/// Represents a piano keyboard widget for displaying musical notes.
+///
+/// The bottom line shows the names of the C keys when there is room for it.
+pub struct Keyboard {
+    /// The style for white keys.
+    white_key_style: Style,
+    /// The style for black keys.
+    black_key_style: Style,
+    /// The style for the active note.
+    active_note_style: Style,
+    /// The symbol used to represent the active note on the keyboard.
+    active_note_symbol: char,
+    /// The style for ghost notes on the keyboard.
+    ghost_note_style: Style,
+    /// The symbol used to represent ghost notes on the keyboard.
+    ghost_note_symbol: char,
+    /// The style for note names.
+    note_name_style: Style,
+}
+
+impl Default for Keyboard {
+    /// Creates a default `Keyboard`.
+    fn default() -> Self {
+        Self {
+            white_key_style: Style::default().fg(Color::Gray),
+            black_key_style: Style::default().fg(Color::DarkGray),
+            active_note_style: Style::default().fg(Color::Yellow),
+            active_note_symbol: '⬤',
+            ghost_note_style: Style::default().fg(Color::Blue),
+            ghost_note_symbol: '✖',
+            note_name_style: Style::default().fg(Color::Green),
+        }
+    }
+}
+
+impl Keyboard {
+    /// Creates a new `Keyboard` with default settings.
+    ///
+    /// This is equivalent to `Keyboard::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the style for white keys.
+    pub fn with_white_key_style(mut self, style: Style) -> Self {
+        self.white_key_style = style;
+        self
+    }
+
+    /// Sets the style for black keys.
+    pub fn with_black_key_style(mut self, style: Style) -> Self {
+        self.black_key_style = style;
+        self
+    }
+
+    /// Sets the style for the active note.
+    pub fn with_active_note_style(mut self, style: Style) -> Self {
+        self.active_note_style = style;
+        self
+    }
+
+    /// Sets the symbol used to represent the active note on the keyboard.
+    pub fn with_active_note_symbol(mut self, symbol: char) -> Self {
+        self.active_note_symbol = symbol;
+        self
+    }
+
+    /// Sets the style for ghost notes on the keyboard.
+    pub fn with_ghost_note_style(mut self, style: Style) -> Self {
+        self.ghost_note_style = style;
+        self
+    }
+
+    /// Sets the symbol used to represent ghost notes on the keyboard.
+    pub fn with_ghost_note_symbol(mut self, symbol: char) -> Self {
+        self.ghost_note_symbol = symbol;
+        self
+    }
+
+    /// Sets the style for note names.
+    pub fn with_note_name_style(mut self, style: Style) -> Self {
+        self.note_name_style = style;
+        self
+    }
+
+    /// Returns the marker of the note, if it is highlighted.
+    fn marker(&self, note: Note, state: &KeyboardState) -> Option<(char, Style)> {
+        if state.active_notes.contains(&note) {
+            Some((self.active_note_symbol, self.active_note_style))
+        } else if state.ghost_notes.contains(&note) {
+            Some((self.ghost_note_symbol, self.ghost_note_style))
+        } else {
+            None
+        }
+    }
+}
+
+impl StatefulWidget for &Keyboard {
+    type State = KeyboardState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = area.intersection(buf.area);
+        if area.height < 2 {
+            return;
+        }
+        let show_names = area.height >= 4;
+        let white_row = area.bottom() - 1 - show_names as u16;
+
+        let keys = state.white_keys();
+        let fitting = ((area.width.saturating_sub(1)) / WHITE_KEY_WIDTH) as usize;
+        let keys = &keys[..keys.len().min(fitting)];
+
+        for (i, (note, _)) in keys.iter().enumerate() {
+            let x = area.x + i as u16 * WHITE_KEY_WIDTH;
+            let has_black_before = i > 0 && keys[i - 1].1.is_some();
+
+            // The white key, narrowed by the black keys on its top part
+            for y in area.y..=white_row {
+                buf[(x, y)].set_char('│').set_style(self.white_key_style);
+                for dx in 1..WHITE_KEY_WIDTH {
+                    buf[(x + dx, y)]
+                        .set_char(' ')
+                        .set_style(self.white_key_style);
+                }
+            }
+            if let Some((symbol, style)) = self.marker(*note, state) {
+                buf[(x + 1, white_row)].set_char(symbol).set_style(style);
+            }
+
+            // The black key on the border to the previous key
+            if has_black_before {
+                let black = keys[i - 1].1.expect("checked above");
+                for y in area.y..white_row {
+                    buf[(x, y)].set_char('█').set_style(self.black_key_style);
+                }
+                if let Some((symbol, style)) = self.marker(black, state) {
+                    buf[(x, white_row - 1)].set_char(symbol).set_style(style);
+                }
+            }
+
+            if show_names && matches!(note, Note::C(_)) {
+                buf.set_stringn(
+                    x,
+                    area.bottom() - 1,
+                    note.to_string(),
+                    (area.right() - x) as usize,
+                    self.note_name_style,
+                );
+            }
+        }
+
+        // Closing border of the last key
+        if !keys.is_empty() {
+            let x = area.x + keys.len() as u16 * WHITE_KEY_WIDTH;
+            for y in area.y..=white_row {
+                buf[(x, y)].set_char('│').set_style(self.white_key_style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{buffer::Buffer, layout::Rect};
+    use rstest::*;
+
+    fn plain() -> Keyboard {
+        Keyboard::default()
+            .with_white_key_style(Style::default())
+            .with_black_key_style(Style::default())
+            .with_active_note_style(Style::default())
+            .with_ghost_note_style(Style::default())
+            .with_note_name_style(Style::default())
+    }
+
+    #[rstest]
+    #[case::octave(
+        Rect::new(0, 0, 22, 4),
+        KeyboardState {
+            active_notes: vec![Note::E(4), Note::CSharp(4)],
+            ghost_notes: vec![Note::G(4), Note::E(4)],
+            keys: Note::C(4)..=Note::B(4),
+        },
+        Buffer::with_lines([
+            "│  █  █  │  █  █  █  │",
+            "│  ⬤  █  │  █  █  █  │",
+            "│  │  │⬤ │  │✖ │  │  │",
+            "C4                    ",
+        ])
+    )]
+    #[case::widened_range(
+        Rect::new(0, 0, 10, 2),
+        KeyboardState {
+            active_notes: Vec::new(),
+            ghost_notes: vec![Note::FSharp(2)],
+            keys: Note::FSharp(2)..=Note::GSharp(2),
+        },
+        Buffer::with_lines([
+            "│  ✖  █  │",
+            "│  │  │  │",
+        ])
+    )]
+    #[case::clipped(
+        Rect::new(0, 0, 8, 2),
+        KeyboardState::new(Note::C(3)..=Note::B(3)),
+        Buffer::with_lines([
+            "│  █  │ ",
+            "│  │  │ ",
+        ])
+    )]
+    fn render_keyboard(
+        #[case] area: Rect,
+        #[case] mut state: KeyboardState,
+        #[case] expected: Buffer,
+    ) {
+        let mut buf = Buffer::empty(area);
+        plain().render(area, &mut buf, &mut state);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_from_fretboard_state() {
+        let mut fretboard_state = FretboardState::default();
+        fretboard_state.set_active_note(Note::A(3));
+        fretboard_state.set_ghost_note(Note::B(3));
+        let state = KeyboardState::from(&fretboard_state);
+        assert_eq!(vec![Note::A(3)], state.active_notes);
+        assert_eq!(vec![Note::B(3)], state.ghost_notes);
+    }
+}
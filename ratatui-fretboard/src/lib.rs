@@ -1,8 +1,25 @@
 #![doc = include_str!("../README.md")]
 
+pub mod chord;
+mod chord_diagram;
+mod circle;
 pub mod error;
+pub mod fingering;
 mod fretboard;
+pub mod interval;
+pub mod key;
+mod keyboard;
 pub mod note;
+pub mod pattern;
+pub mod position;
 pub mod scale;
+mod staff;
+mod tab;
+pub mod tablature;
 
-pub use fretboard::{Fretboard, FretboardState};
+pub use chord_diagram::{ChordDiagram, ChordDiagramState};
+pub use circle::CircleOfFifths;
+pub use fretboard::{Fretboard, FretboardMarks, FretboardState, NoteLabel};
+pub use keyboard::{Keyboard, KeyboardState};
+pub use staff::{diatonic_step, Clef, Staff};
+pub use tab::{Tab, TabState};
@@ -1,8 +1,27 @@
 //! Error handling.
 
-use std::{fmt, ops::Add};
+use std::{
+    fmt,
+    ops::{Add, Sub},
+    str::FromStr,
+};
 
-use crate::error::Error;
+use crate::{error::Error, interval::Interval, key::Key};
+
+/// The frequency of A4 in standard concert pitch, in Hz.
+pub const A4_FREQUENCY: f64 = 440.0;
+
+/// The highest semitone index a note can have (G10).
+const MAX_SEMITONE_INDEX: u8 = 127;
+
+/// The letters of the natural notes, starting from C.
+pub(crate) const LETTERS: [char; 7] = ['C', 'D', 'E', 'F', 'G', 'A', 'B'];
+
+/// The pitch classes of the natural notes, starting from C.
+pub(crate) const NATURAL_PITCH_CLASSES: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// The open notes of the strings, from the lowest string to the highest.
+pub type Tuning = [Note];
 
 /// Represents the standard tuning of a 6-string guitar.
 pub const STANDARD_TUNING: [Note; 6] = [
@@ -14,6 +33,21 @@ pub const STANDARD_TUNING: [Note; 6] = [
     Note::E(4),
 ];
 
+/// How notes are written down.
+///
+/// [`Note`] only stores sharps, the spelling decides whether a black key is
+/// shown as a sharp or a flat, e.g. `A#` or `Bb`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Spelling {
+    /// Black keys are written with sharps.
+    #[default]
+    Sharps,
+    /// Black keys are written with flats.
+    Flats,
+    /// Notes are written with the key signature of the key.
+    Key(Key),
+}
+
 /// Represents a musical note with its pitch and octave.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Note {
@@ -74,6 +108,47 @@ impl Note {
         }
     }
 
+    /// Returns `true` if the note has no accidental (a white key on a piano).
+    pub fn is_natural(&self) -> bool {
+        !self.name().ends_with('#')
+    }
+
+    /// Returns the pitch class (0-11) of the note, ignoring the octave.
+    pub fn pitch_class(&self) -> u8 {
+        self.semitone_index() % 12
+    }
+
+    /// Returns the name of the note written with the given spelling, e.g. `Bb` or `E#`.
+    pub fn spelled_name(&self, spelling: Spelling) -> String {
+        match spelling {
+            Spelling::Sharps => self.name().to_string(),
+            Spelling::Flats => flat_name(self.pitch_class()),
+            Spelling::Key(key) => key.spell(*self),
+        }
+    }
+
+    /// Returns the name and octave of the note written with the given spelling, e.g. `Bb3`.
+    ///
+    /// The octave follows the letter, so C4 may be written as `B#3`.
+    pub fn spelled(&self, spelling: Spelling) -> String {
+        let name = self.spelled_name(spelling);
+        let letter = LETTERS
+            .iter()
+            .position(|letter| name.starts_with(*letter))
+            .expect("note names start with a letter");
+        let natural = NATURAL_PITCH_CLASSES[letter] as i16;
+        let pitch_class = self.pitch_class() as i16;
+        let octave = (self.semitone_index() / 12) as i16
+            + if natural - pitch_class > 6 {
+                -1
+            } else if pitch_class - natural > 6 {
+                1
+            } else {
+                0
+            };
+        format!("{name}{octave}")
+    }
+
     /// Returns the octave of the note.
     pub fn semitone_index(&self) -> u8 {
         let (offset, octave) = match self {
@@ -112,14 +187,120 @@ impl Note {
             _ => unreachable!(),
         }
     }
+
+    /// Returns the MIDI number of the note, C4 being 60.
+    ///
+    /// Returns `None` above G9, the highest note MIDI can represent.
+    pub fn midi_number(&self) -> Option<u8> {
+        Some(self.semitone_index() + 12).filter(|midi| *midi <= 127)
+    }
+
+    /// Returns the note of the MIDI number, if it is not below C0.
+    pub fn from_midi_number(midi: u8) -> Option<Note> {
+        midi.checked_sub(12).map(Note::from_semitone_index)
+    }
+
+    /// Returns the frequency of the note in Hz, tuned to the given frequency of A4.
+    pub fn frequency(&self, a4: f64) -> f64 {
+        // A4 is 57 semitones above C0
+        a4 * 2f64.powf((self.semitone_index() as f64 - 57.0) / 12.0)
+    }
+
+    /// Returns the note closest to the frequency, tuned to the given frequency of A4.
+    ///
+    /// Returns `None` if the frequency is not positive or the note is out of range.
+    pub fn from_frequency(frequency: f64, a4: f64) -> Option<Note> {
+        let midi = (69.0 + 12.0 * (frequency / a4).log2()).round();
+        (midi.is_finite() && (12.0..=(MAX_SEMITONE_INDEX as f64 + 12.0)).contains(&midi))
+            .then(|| Note::from_semitone_index(midi as u8 - 12))
+    }
+
+    /// Moves the note by the given number of semitones, up or down.
+    ///
+    /// Returns `None` if the result is out of range.
+    pub fn checked_transpose(&self, semitones: i16) -> Option<Note> {
+        let index = (self.semitone_index() as i16).checked_add(semitones)?;
+        u8::try_from(index)
+            .ok()
+            .filter(|index| *index <= MAX_SEMITONE_INDEX)
+            .map(Note::from_semitone_index)
+    }
+
+    /// Moves the note by the given number of semitones, stopping at C0 and G10.
+    pub fn saturating_transpose(&self, semitones: i16) -> Note {
+        let index = (self.semitone_index() as i16)
+            .saturating_add(semitones)
+            .clamp(0, MAX_SEMITONE_INDEX as i16);
+        Note::from_semitone_index(index as u8)
+    }
+
+    /// Moves the note up by the interval, returning `None` if the result is out of range.
+    pub fn checked_add(&self, interval: Interval) -> Option<Note> {
+        self.checked_transpose(interval.semitones() as i16)
+    }
+
+    /// Moves the note down by the interval, returning `None` if the result is out of range.
+    pub fn checked_sub(&self, interval: Interval) -> Option<Note> {
+        self.checked_transpose(-(interval.semitones() as i16))
+    }
+
+    /// Moves the note up by the interval, stopping at G10.
+    pub fn saturating_add(&self, interval: Interval) -> Note {
+        self.saturating_transpose(interval.semitones() as i16)
+    }
+
+    /// Moves the note down by the interval, stopping at C0.
+    pub fn saturating_sub(&self, interval: Interval) -> Note {
+        self.saturating_transpose(-(interval.semitones() as i16))
+    }
 }
 
 impl Add<u8> for Note {
     type Output = Self;
 
     /// Adds a number of semitones to the note.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result is above G10, see [`Note::checked_transpose`].
     fn add(self, other: u8) -> Self::Output {
-        Note::from_semitone_index(self.semitone_index() + other)
+        self.checked_transpose(other as i16)
+            .expect("note is out of range")
+    }
+}
+
+impl Add<Interval> for Note {
+    type Output = Self;
+
+    /// Moves the note up by the interval.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result is above G10, see [`Note::checked_add`].
+    fn add(self, interval: Interval) -> Self::Output {
+        self.checked_add(interval).expect("note is out of range")
+    }
+}
+
+impl Sub<Interval> for Note {
+    type Output = Self;
+
+    /// Moves the note down by the interval.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result is below C0, see [`Note::checked_sub`].
+    fn sub(self, interval: Interval) -> Self::Output {
+        self.checked_sub(interval).expect("note is out of range")
+    }
+}
+
+impl Sub for Note {
+    type Output = Interval;
+
+    /// Returns the interval between the two notes, regardless of which one is higher.
+    fn sub(self, other: Note) -> Self::Output {
+        Interval::new(self.semitone_index().abs_diff(other.semitone_index()))
     }
 }
 
@@ -146,36 +327,272 @@ impl fmt::Display for Note {
 impl TryFrom<String> for Note {
     type Error = Error;
     /// Converts a string to a `Note`.
+    ///
+    /// See the [`FromStr`] implementation for the accepted formats.
     fn try_from(s: String) -> Result<Self, Error> {
-        let mut chars = s.chars();
-        let note = match chars.next() {
-            Some('C') => Ok(Note::C(0)),
-            Some('D') => Ok(Note::D(0)),
-            Some('E') => Ok(Note::E(0)),
-            Some('F') => Ok(Note::F(0)),
-            Some('G') => Ok(Note::G(0)),
-            Some('A') => Ok(Note::A(0)),
-            Some('B') => Ok(Note::B(0)),
-            _ => return Err(Error::MissingLetter),
-        };
+        s.parse()
+    }
+}
+
+impl FromStr for Note {
+    type Err = Error;
+
+    /// Parses a note from its name, MIDI number or frequency.
+    ///
+    /// - Names are written in scientific pitch notation: a letter (in any case), any number
+    ///   of accidentals (`#`, `♯`, `x`, `𝄪`, `b`, `♭`, `𝄫`) and an octave, e.g. `C#4`,
+    ///   `Bb3` or `e♭3`.
+    /// - MIDI numbers are plain integers from `12` (C0) to `127`, e.g. `69` for A4.
+    /// - Frequencies end with `Hz` and are rounded to the nearest note, e.g. `440Hz`.
+    ///
+    /// Only notes from C0 to G10 can be represented. Anything below or above,
+    /// such as `Bb-1`, `Cb0` or the MIDI numbers `0` to `11`, returns
+    /// [`Error::OutOfRange`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let first = s.chars().next().ok_or(Error::MissingLetter)?;
+        if first.is_ascii_digit() || first == '.' {
+            let lowercase = s.to_ascii_lowercase();
+            return match lowercase.strip_suffix("hz") {
+                Some(frequency) => parse_frequency(s, frequency.trim_end()),
+                None => parse_midi_number(s),
+            };
+        }
+
+        let letter = LETTERS
+            .iter()
+            .position(|l| *l == first.to_ascii_uppercase())
+            .ok_or_else(|| Error::InvalidLetter(first.to_string()))?;
+        let rest = &s[first.len_utf8()..];
 
-        let octave = chars
-            .next()
-            .and_then(|c| c.to_digit(10))
-            .map(|d| d as u8)
-            .unwrap_or(0);
-
-        if chars.next() == Some('#') {
-            match note.clone()? {
-                Note::C(_) => Ok(Note::CSharp(octave)),
-                Note::D(_) => Ok(Note::DSharp(octave)),
-                Note::F(_) => Ok(Note::FSharp(octave)),
-                Note::G(_) => Ok(Note::GSharp(octave)),
-                Note::A(_) => Ok(Note::ASharp(octave)),
-                _ => note,
-            }
-        } else {
-            note
+        let mut offset = 0;
+        let mut octave = rest;
+        for (i, accidental) in rest.char_indices() {
+            offset += match accidental {
+                '#' | '♯' => 1,
+                'x' | '𝄪' => 2,
+                'b' | '♭' => -1,
+                '𝄫' => -2,
+                _ => {
+                    octave = &rest[i..];
+                    break;
+                }
+            };
+            octave = &rest[i + accidental.len_utf8()..];
         }
+
+        if octave.is_empty() {
+            return Err(Error::MissingOctave);
+        }
+        let octave: i32 = octave
+            .parse()
+            .map_err(|_| Error::InvalidOctave(octave.to_string()))?;
+        let midi = octave
+            .checked_add(1)
+            .and_then(|octave| octave.checked_mul(12))
+            .and_then(|midi| midi.checked_add(NATURAL_PITCH_CLASSES[letter] as i32 + offset))
+            .ok_or_else(|| Error::OutOfRange(s.to_string()))?;
+        from_midi(midi, s)
+    }
+}
+
+/// Parses a MIDI number (0-127).
+fn parse_midi_number(input: &str) -> Result<Note, Error> {
+    let midi: u8 = input
+        .parse()
+        .ok()
+        .filter(|midi| *midi <= 127)
+        .ok_or_else(|| Error::InvalidMidiNumber(input.to_string()))?;
+    from_midi(midi as i32, input)
+}
+
+/// Parses a frequency in Hz and rounds it to the nearest note.
+fn parse_frequency(input: &str, frequency: &str) -> Result<Note, Error> {
+    let frequency: f64 = frequency
+        .parse()
+        .ok()
+        .filter(|frequency: &f64| frequency.is_finite() && *frequency > 0.0)
+        .ok_or_else(|| Error::InvalidFrequency(input.to_string()))?;
+    Note::from_frequency(frequency, A4_FREQUENCY)
+        .ok_or_else(|| Error::OutOfRange(input.to_string()))
+}
+
+/// Returns the note of the MIDI number, if it can be represented.
+fn from_midi(midi: i32, input: &str) -> Result<Note, Error> {
+    u8::try_from(midi)
+        .ok()
+        .filter(|midi| *midi <= MAX_SEMITONE_INDEX + 12)
+        .and_then(Note::from_midi_number)
+        .ok_or_else(|| Error::OutOfRange(input.to_string()))
+}
+
+/// Returns the name of the pitch class using flats for the black keys.
+pub(crate) fn flat_name(pitch_class: u8) -> String {
+    match NATURAL_PITCH_CLASSES
+        .iter()
+        .position(|pc| *pc == pitch_class)
+    {
+        Some(letter) => LETTERS[letter].to_string(),
+        None => {
+            let letter = NATURAL_PITCH_CLASSES
+                .iter()
+                .position(|pc| *pc == pitch_class + 1)
+                .expect("every black key is below a white key");
+            format!("{}b", LETTERS[letter])
+        }
+    }
+}
+
+/// Returns the name of the pitch class written with the given letter.
+pub(crate) fn spell_with_letter(pitch_class: u8, letter: usize) -> String {
+    let accidental = match (pitch_class + 12 - NATURAL_PITCH_CLASSES[letter]) % 12 {
+        0 => "",
+        1 => "#",
+        2 => "##",
+        10 => "bb",
+        11 => "b",
+        _ => "?",
+    };
+    format!("{}{accidental}", LETTERS[letter])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use Note::*;
+
+    #[rstest]
+    #[case(ASharp(3), Spelling::Sharps, "A#3")]
+    #[case(ASharp(3), Spelling::Flats, "Bb3")]
+    #[case(DSharp(2), Spelling::Flats, "Eb2")]
+    #[case(E(2), Spelling::Flats, "E2")]
+    #[case(ASharp(3), Spelling::Key(Key::major(F(4))), "Bb3")]
+    #[case(F(3), Spelling::Key(Key::major(FSharp(4))), "E#3")]
+    #[case(CSharp(4), Spelling::Key(Key::major(CSharp(4))), "Db4")]
+    #[case(C(4), Spelling::Key(Key::minor(GSharp(4))), "C4")]
+    fn test_spelled(#[case] note: Note, #[case] spelling: Spelling, #[case] expected: &str) {
+        assert_eq!(expected, note.spelled(spelling));
+    }
+
+    #[rstest]
+    #[case(C(4), Some(60), 261.63)]
+    #[case(A(4), Some(69), 440.0)]
+    #[case(E(2), Some(40), 82.41)]
+    #[case(C(0), Some(12), 16.35)]
+    #[case(G(9), Some(127), 12543.85)]
+    #[case(GSharp(9), None, 13289.75)]
+    #[case(G(10), None, 25087.71)]
+    fn test_midi_number_and_frequency(
+        #[case] note: Note,
+        #[case] midi: Option<u8>,
+        #[case] frequency: f64,
+    ) {
+        assert_eq!(midi, note.midi_number());
+        if let Some(midi) = midi {
+            assert_eq!(Some(note), Note::from_midi_number(midi));
+        }
+        assert!((note.frequency(A4_FREQUENCY) - frequency).abs() < 0.01);
+        assert_eq!(Some(note), Note::from_frequency(frequency, A4_FREQUENCY));
+    }
+
+    #[test]
+    fn test_frequency_with_tuning() {
+        assert_eq!(432.0, A(4).frequency(432.0));
+        assert_eq!(Some(A(4)), Note::from_frequency(432.0, 432.0));
+        assert_eq!(Some(GSharp(4)), Note::from_frequency(415.0, A4_FREQUENCY));
+        assert_eq!(None, Note::from_frequency(0.0, A4_FREQUENCY));
+        assert_eq!(None, Note::from_frequency(f64::NAN, A4_FREQUENCY));
+        assert_eq!(None, Note::from_midi_number(11));
+    }
+
+    #[rstest]
+    #[case(C(4), E(4), Interval::MAJOR_THIRD)]
+    #[case(E(4), C(4), Interval::MAJOR_THIRD)]
+    #[case(E(2), E(4), Interval::DOUBLE_OCTAVE)]
+    #[case(A(3), A(3), Interval::UNISON)]
+    #[case(C(3), FSharp(3), Interval::TRITONE)]
+    fn test_interval_between(#[case] a: Note, #[case] b: Note, #[case] expected: Interval) {
+        assert_eq!(expected, b - a);
+    }
+
+    #[test]
+    fn test_transpose() {
+        assert_eq!(G(4), C(4) + Interval::PERFECT_FIFTH);
+        assert_eq!(F(3), C(4) - Interval::PERFECT_FIFTH);
+        assert_eq!(Some(E(2)), E(4).checked_sub(Interval::DOUBLE_OCTAVE));
+        assert_eq!(None, C(0).checked_sub(Interval::MINOR_SECOND));
+        assert_eq!(None, G(10).checked_add(Interval::MINOR_SECOND));
+        assert_eq!(C(0), D(0).saturating_sub(Interval::OCTAVE));
+        assert_eq!(G(10), C(10).saturating_add(Interval::OCTAVE));
+        assert_eq!(Some(B(3)), C(4).checked_transpose(-1));
+        assert_eq!(G(10), G(10).saturating_transpose(i16::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "note is out of range")]
+    fn test_add_overflow() {
+        let _ = G(10) + 1;
+    }
+
+    #[rstest]
+    #[case::natural("A4", A(4))]
+    #[case::sharp("C#4", CSharp(4))]
+    #[case::flat("Bb3", ASharp(3))]
+    #[case::lowercase("e4", E(4))]
+    #[case::lowercase_flat("bb3", ASharp(3))]
+    #[case::unicode_sharp("F♯2", FSharp(2))]
+    #[case::unicode_flat("e♭3", DSharp(3))]
+    #[case::double_sharp("F##4", G(4))]
+    #[case::double_sharp_x("Fx4", G(4))]
+    #[case::unicode_double_sharp("C𝄪4", D(4))]
+    #[case::double_flat("Ebb4", D(4))]
+    #[case::unicode_double_flat("B𝄫3", A(3))]
+    #[case::mixed_accidentals("C#b4", C(4))]
+    #[case::flat_crossing_octave("Cb4", B(3))]
+    #[case::sharp_crossing_octave("B#3", C(4))]
+    #[case::lowest("C0", C(0))]
+    #[case::two_digit_octave("E10", E(10))]
+    #[case::highest("G10", G(10))]
+    #[case::whitespace(" G3 ", G(3))]
+    #[case::midi_number("69", A(4))]
+    #[case::midi_number_lowest("12", C(0))]
+    #[case::midi_number_highest("127", G(9))]
+    #[case::frequency("440Hz", A(4))]
+    #[case::frequency_lowercase("440hz", A(4))]
+    #[case::frequency_with_space("82.41 Hz", E(2))]
+    #[case::frequency_rounded("450Hz", A(4))]
+    #[case::frequency_rounded_up("455Hz", ASharp(4))]
+    fn test_parse(#[case] input: &str, #[case] expected: Note) {
+        assert_eq!(Ok(expected), input.parse::<Note>());
+        assert_eq!(Ok(expected), Note::try_from(input.to_string()));
+    }
+
+    #[rstest]
+    #[case::empty("", Error::MissingLetter)]
+    #[case::blank("   ", Error::MissingLetter)]
+    #[case::invalid_letter("H4", Error::InvalidLetter("H".to_string()))]
+    #[case::symbol("?4", Error::InvalidLetter("?".to_string()))]
+    #[case::missing_octave("C#", Error::MissingOctave)]
+    #[case::invalid_octave("C#four", Error::InvalidOctave("four".to_string()))]
+    #[case::invalid_accidental("C$4", Error::InvalidOctave("$4".to_string()))]
+    #[case::fractional_octave("C4.5", Error::InvalidOctave("4.5".to_string()))]
+    #[case::below_c0("Bb-1", Error::OutOfRange("Bb-1".to_string()))]
+    #[case::flat_below_c0("Cb0", Error::OutOfRange("Cb0".to_string()))]
+    #[case::above_range("A10", Error::OutOfRange("A10".to_string()))]
+    #[case::huge_octave("C2147483647", Error::OutOfRange("C2147483647".to_string()))]
+    #[case::octave_near_overflow("B178956970", Error::OutOfRange("B178956970".to_string()))]
+    #[case::octave_overflow("C99999999999", Error::InvalidOctave("99999999999".to_string()))]
+    #[case::midi_number_below_c0("11", Error::OutOfRange("11".to_string()))]
+    #[case::midi_number_too_high("128", Error::InvalidMidiNumber("128".to_string()))]
+    #[case::midi_number_with_symbol("6-9", Error::InvalidMidiNumber("6-9".to_string()))]
+    #[case::midi_number_fractional("60.5", Error::InvalidMidiNumber("60.5".to_string()))]
+    #[case::zero_frequency("0Hz", Error::InvalidFrequency("0Hz".to_string()))]
+    #[case::invalid_frequency("4.4.0Hz", Error::InvalidFrequency("4.4.0Hz".to_string()))]
+    #[case::missing_frequency_value("Hz", Error::InvalidLetter("H".to_string()))]
+    #[case::low_frequency("1Hz", Error::OutOfRange("1Hz".to_string()))]
+    #[case::high_frequency("40000Hz", Error::OutOfRange("40000Hz".to_string()))]
+    fn test_parse_error(#[case] input: &str, #[case] expected: Error) {
+        assert_eq!(Err(expected), input.parse::<Note>());
     }
 }
@@ -0,0 +1,392 @@
+//! Scale patterns.
+
+use std::{fmt, ops::RangeInclusive};
+
+use crate::{note::Note, position::FretPosition, scale::Scale};
+
+/// A system for splitting a scale into playable positions along the neck.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PatternSystem {
+    /// The five boxes of a pentatonic scale, with two notes on each string.
+    #[default]
+    PentatonicBoxes,
+    /// One pattern per mode of a seven-note scale, with three notes on each string.
+    ThreeNotesPerString,
+    /// The five shapes of the CAGED system, following the open chord shapes.
+    Caged,
+}
+
+impl PatternSystem {
+    /// Returns the patterns of the scale in this system.
+    ///
+    /// See [`pentatonic_boxes`], [`three_notes_per_string`] and [`caged_shapes`].
+    pub fn patterns(&self, scale: &Scale, root: Note, tuning: &[Note]) -> Vec<ScalePattern> {
+        match self {
+            PatternSystem::PentatonicBoxes => pentatonic_boxes(scale, root, tuning),
+            PatternSystem::ThreeNotesPerString => three_notes_per_string(scale, root, tuning),
+            PatternSystem::Caged => caged_shapes(scale, root, tuning),
+        }
+    }
+
+    /// Returns the next pattern system.
+    pub fn next(&self) -> PatternSystem {
+        match self {
+            PatternSystem::PentatonicBoxes => PatternSystem::ThreeNotesPerString,
+            PatternSystem::ThreeNotesPerString => PatternSystem::Caged,
+            PatternSystem::Caged => PatternSystem::PentatonicBoxes,
+        }
+    }
+}
+
+impl fmt::Display for PatternSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PatternSystem::PentatonicBoxes => "Pentatonic boxes",
+            PatternSystem::ThreeNotesPerString => "3 notes per string",
+            PatternSystem::Caged => "CAGED",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A chord shape of the CAGED system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CagedShape {
+    C,
+    A,
+    G,
+    E,
+    D,
+}
+
+impl CagedShape {
+    /// All shapes in CAGED order.
+    pub const ALL: [CagedShape; 5] = [
+        CagedShape::C,
+        CagedShape::A,
+        CagedShape::G,
+        CagedShape::E,
+        CagedShape::D,
+    ];
+
+    /// Returns the string the root of the shape is played on and the frets
+    /// of the shape relative to that root, as in standard tuning.
+    fn anchor(&self) -> (usize, RangeInclusive<i16>) {
+        match self {
+            CagedShape::C => (1, -3..=1),
+            CagedShape::A => (1, -1..=3),
+            CagedShape::G => (0, -3..=1),
+            CagedShape::E => (0, -1..=3),
+            CagedShape::D => (2, -1..=3),
+        }
+    }
+}
+
+impl fmt::Display for CagedShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CagedShape::C => "C",
+            CagedShape::A => "A",
+            CagedShape::G => "G",
+            CagedShape::E => "E",
+            CagedShape::D => "D",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A group of scale tones that can be played in one position on the neck.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScalePattern {
+    /// The name of the pattern, e.g. `Box 1`.
+    name: String,
+    /// The positions of the scale tones, ordered from the lowest string to the highest.
+    positions: Vec<FretPosition>,
+}
+
+impl ScalePattern {
+    /// Creates a pattern from its positions.
+    pub fn new(name: impl Into<String>, positions: Vec<FretPosition>) -> Self {
+        Self {
+            name: name.into(),
+            positions,
+        }
+    }
+
+    /// Returns the name of the pattern.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the positions of the scale tones.
+    pub fn positions(&self) -> &[FretPosition] {
+        &self.positions
+    }
+
+    /// Returns the range of frets the pattern spans.
+    pub fn frets(&self) -> RangeInclusive<u8> {
+        let frets = self.positions.iter().map(|position| position.fret);
+        let start = frets.clone().min().unwrap_or_default();
+        let end = frets.max().unwrap_or_default();
+        start..=end
+    }
+
+    /// Returns the notes of the pattern with the given tuning.
+    pub fn notes(&self, tuning: &[Note]) -> Vec<Note> {
+        self.positions
+            .iter()
+            .filter_map(|position| position.note(tuning))
+            .collect()
+    }
+}
+
+impl fmt::Display for ScalePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Returns the five boxes of a pentatonic scale, named `Box 1` to `Box 5`.
+///
+/// Box `n` starts on the `n`-th degree of the scale on the lowest string and
+/// continues with two notes on each string, at the lowest fret it fits.
+/// Scales without five notes have no boxes.
+pub fn pentatonic_boxes(scale: &Scale, root: Note, tuning: &[Note]) -> Vec<ScalePattern> {
+    if scale.intervals().len() != 5 {
+        return Vec::new();
+    }
+    notes_per_string(scale, root, tuning, 2, |degree| {
+        format!("Box {}", degree + 1)
+    })
+}
+
+/// Returns the seven 3-notes-per-string patterns of a seven-note scale,
+/// named after the mode they start on, e.g. `Dorian 3NPS`.
+///
+/// Each pattern starts on a degree of the scale on the lowest string and
+/// continues with three notes on each string, at the lowest fret it fits.
+/// Scales without seven notes have no patterns.
+pub fn three_notes_per_string(scale: &Scale, root: Note, tuning: &[Note]) -> Vec<ScalePattern> {
+    if scale.intervals().len() != 7 {
+        return Vec::new();
+    }
+    notes_per_string(scale, root, tuning, 3, |degree| {
+        let mode = scale.mode(degree + 1).unwrap_or_else(|| scale.clone());
+        format!("{mode} 3NPS")
+    })
+}
+
+/// Returns the CAGED shapes of the scale, named `C shape` to `D shape` and
+/// ordered up the neck.
+///
+/// Each shape holds the scale tones in a five fret window around the root, on
+/// the string the root of the open chord shape is played on. Shapes whose root
+/// string is missing from the tuning are left out.
+pub fn caged_shapes(scale: &Scale, root: Note, tuning: &[Note]) -> Vec<ScalePattern> {
+    let pitch_classes = &scale.pitch_classes(root);
+    let mut shapes: Vec<ScalePattern> = CagedShape::ALL
+        .iter()
+        .filter_map(|shape| {
+            let (string, window) = shape.anchor();
+            let open = tuning.get(string)?;
+            // Allow one fret of the window below the nut, for the open shapes
+            let min_fret = (-window.start() - 1).max(0);
+            let mut fret = i16::from((root.pitch_class() + 12 - open.pitch_class()) % 12);
+            if fret < min_fret {
+                fret += 12;
+            }
+            let start = u8::try_from((fret + window.start()).max(0)).ok()?;
+            let end = u8::try_from(fret + window.end()).ok()?;
+            let positions = tuning
+                .iter()
+                .enumerate()
+                .flat_map(|(string, open)| {
+                    (start..=end).filter_map(move |fret| {
+                        let pitch_class = (open.pitch_class() + fret % 12) % 12;
+                        Some(FretPosition::new(string, fret))
+                            .filter(|_| pitch_classes.contains(&pitch_class))
+                    })
+                })
+                .collect();
+            Some(ScalePattern::new(format!("{shape} shape"), positions))
+        })
+        .collect();
+    shapes.sort_by_key(|shape| *shape.frets().start());
+    shapes
+}
+
+/// Returns a pattern for each degree of the scale, playing `count` consecutive
+/// scale tones on each string.
+fn notes_per_string(
+    scale: &Scale,
+    root: Note,
+    tuning: &[Note],
+    count: usize,
+    name: impl Fn(usize) -> String,
+) -> Vec<ScalePattern> {
+    let Some(lowest) = tuning.first() else {
+        return Vec::new();
+    };
+    let intervals = scale.intervals();
+    (0..intervals.len())
+        .filter_map(|degree| {
+            let first_fret =
+                (root.pitch_class() + intervals[degree] + 12 - lowest.pitch_class()) % 12;
+            // Move up an octave if a string would need a note below its open string
+            [first_fret, first_fret + 12].into_iter().find_map(|fret| {
+                let root = i16::from(lowest.semitone_index()) + i16::from(fret)
+                    - i16::from(intervals[degree]);
+                let positions = tuning
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(string, open)| {
+                        (0..count).map(move |i| (string, open, degree + string * count + i))
+                    })
+                    .map(|(string, open, step)| {
+                        let octave = (step / intervals.len()) as i16;
+                        let semitones =
+                            root + octave * 12 + i16::from(intervals[step % intervals.len()]);
+                        let fret =
+                            u8::try_from(semitones - i16::from(open.semitone_index())).ok()?;
+                        Some(FretPosition::new(string, fret))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(ScalePattern::new(name(degree), positions))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::STANDARD_TUNING;
+    use rstest::rstest;
+
+    fn frets(patterns: &[ScalePattern]) -> Vec<(&str, RangeInclusive<u8>)> {
+        patterns
+            .iter()
+            .map(|pattern| (pattern.name(), pattern.frets()))
+            .collect()
+    }
+
+    #[test]
+    fn test_pentatonic_boxes() {
+        let boxes = pentatonic_boxes(&Scale::MINOR_PENTATONIC, Note::A(2), &STANDARD_TUNING);
+        assert_eq!(
+            vec![
+                ("Box 1", 5..=8),
+                ("Box 2", 7..=10),
+                ("Box 3", 9..=13),
+                ("Box 4", 0..=3),
+                ("Box 5", 2..=5),
+            ],
+            frets(&boxes)
+        );
+        let first: Vec<(usize, u8)> = boxes[0]
+            .positions()
+            .iter()
+            .map(|position| (position.string, position.fret))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, 5),
+                (0, 8),
+                (1, 5),
+                (1, 7),
+                (2, 5),
+                (2, 7),
+                (3, 5),
+                (3, 7),
+                (4, 5),
+                (4, 8),
+                (5, 5),
+                (5, 8),
+            ],
+            first
+        );
+        assert_eq!(
+            vec![
+                Note::A(2),
+                Note::C(3),
+                Note::D(3),
+                Note::E(3),
+                Note::G(3),
+                Note::A(3),
+                Note::C(4),
+                Note::D(4),
+                Note::E(4),
+                Note::G(4),
+                Note::A(4),
+                Note::C(5),
+            ],
+            boxes[0].notes(&STANDARD_TUNING)
+        );
+    }
+
+    #[test]
+    fn test_three_notes_per_string() {
+        let patterns = three_notes_per_string(&Scale::MAJOR, Note::C(3), &STANDARD_TUNING);
+        assert_eq!(
+            vec![
+                ("Major 3NPS", 8..=13),
+                ("Dorian 3NPS", 10..=15),
+                ("Phrygian 3NPS", 0..=5),
+                ("Lydian 3NPS", 1..=7),
+                ("Mixolydian 3NPS", 3..=8),
+                ("Natural Minor 3NPS", 5..=10),
+                ("Locrian 3NPS", 7..=12),
+            ],
+            frets(&patterns)
+        );
+        assert!(patterns
+            .iter()
+            .all(|pattern| pattern.positions().len() == 18));
+    }
+
+    #[test]
+    fn test_caged_shapes() {
+        let shapes = caged_shapes(&Scale::MAJOR, Note::G(2), &STANDARD_TUNING);
+        assert_eq!(
+            vec![
+                ("G shape", 0..=4),
+                ("E shape", 2..=5),
+                ("D shape", 4..=8),
+                ("C shape", 7..=11),
+                ("A shape", 9..=13),
+            ],
+            frets(&shapes)
+        );
+        // Every shape holds the root
+        for shape in &shapes {
+            assert!(shape
+                .notes(&STANDARD_TUNING)
+                .iter()
+                .any(|note| note.pitch_class() == Note::G(0).pitch_class()));
+        }
+    }
+
+    #[rstest]
+    #[case::boxes_of_major(PatternSystem::PentatonicBoxes, Scale::MAJOR)]
+    #[case::three_notes_of_blues(PatternSystem::ThreeNotesPerString, Scale::BLUES)]
+    fn test_unsupported_scale(#[case] system: PatternSystem, #[case] scale: Scale) {
+        assert!(system
+            .patterns(&scale, Note::A(2), &STANDARD_TUNING)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_drop_d_tuning() {
+        let mut tuning = STANDARD_TUNING.to_vec();
+        tuning[0] = Note::D(2);
+        let boxes = pentatonic_boxes(&Scale::MINOR_PENTATONIC, Note::D(2), &tuning);
+        assert_eq!(5, boxes.len());
+        // G2 is below the open A string, so the first box moves up an octave
+        assert_eq!(10..=15, boxes[0].frets());
+        assert_eq!(Note::D(3), boxes[0].notes(&tuning)[0]);
+        assert_eq!(
+            4,
+            caged_shapes(&Scale::MAJOR, Note::D(2), &tuning[..2]).len()
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Fret positions.
+
+use std::ops::RangeInclusive;
+
+use crate::{fingering::FingeringSolver, note::Note};
+
+/// A place on the fretboard where a note can be played.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FretPosition {
+    /// The index of the string in the tuning, `0` being the lowest string.
+    pub string: usize,
+    /// The fret number, `0` being the open string.
+    pub fret: u8,
+}
+
+impl FretPosition {
+    /// Creates a new position.
+    pub fn new(string: usize, fret: u8) -> Self {
+        Self { string, fret }
+    }
+
+    /// Returns the note played at this position with the given tuning.
+    pub fn note(&self, tuning: &[Note]) -> Option<Note> {
+        tuning.get(self.string).map(|open| *open + self.fret)
+    }
+}
+
+/// Returns all positions of the note with the given tuning and fret range.
+///
+/// The positions are ordered from the lowest string to the highest.
+pub fn positions(note: Note, tuning: &[Note], frets: &RangeInclusive<u8>) -> Vec<FretPosition> {
+    tuning
+        .iter()
+        .enumerate()
+        .filter_map(|(string, open)| {
+            let fret = note.semitone_index().checked_sub(open.semitone_index())?;
+            frets
+                .contains(&fret)
+                .then_some(FretPosition::new(string, fret))
+        })
+        .collect()
+}
+
+/// Picks a position for each note of each beat.
+///
+/// Notes of the same beat are put on different strings, see
+/// [`FingeringSolver`] for how the positions are chosen. Notes that cannot be
+/// played with the tuning and fret range are left out.
+pub fn choose_positions<B: AsRef<[Note]>>(
+    beats: &[B],
+    tuning: &[Note],
+    frets: &RangeInclusive<u8>,
+) -> Vec<Vec<FretPosition>> {
+    FingeringSolver::new(frets.clone()).solve(beats, tuning)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::STANDARD_TUNING;
+    use rstest::rstest;
+    use Note::*;
+
+    #[rstest]
+    #[case(E(2), 0..=12, vec![(0, 0)])]
+    #[case(A(2), 0..=5, vec![(0, 5), (1, 0)])]
+    #[case(E(4), 0..=12, vec![(3, 9), (4, 5), (5, 0)])]
+    #[case(DSharp(2), 0..=12, vec![])]
+    fn test_positions(
+        #[case] note: Note,
+        #[case] frets: RangeInclusive<u8>,
+        #[case] expected: Vec<(usize, u8)>,
+    ) {
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(string, fret)| FretPosition::new(string, fret))
+            .collect();
+        assert_eq!(expected, positions(note, &STANDARD_TUNING, &frets));
+    }
+
+    #[test]
+    fn test_position_note() {
+        assert_eq!(Some(A(4)), FretPosition::new(5, 5).note(&STANDARD_TUNING));
+        assert_eq!(None, FretPosition::new(6, 0).note(&STANDARD_TUNING));
+    }
+
+    #[test]
+    fn test_choose_positions() {
+        let beats = [vec![G(3), D(3)], vec![ASharp(3), F(3)]];
+        let chosen = choose_positions(&beats, &STANDARD_TUNING, &(0..=12));
+        assert_eq!(
+            vec![
+                vec![FretPosition::new(2, 0), FretPosition::new(3, 0)],
+                vec![FretPosition::new(2, 3), FretPosition::new(3, 3)],
+            ],
+            chosen
+        );
+    }
+
+    #[test]
+    fn test_choose_positions_skips_unplayable() {
+        let chosen = choose_positions(&[[C(1)]], &STANDARD_TUNING, &(0..=12));
+        assert_eq!(vec![Vec::<FretPosition>::new()], chosen);
+    }
+}
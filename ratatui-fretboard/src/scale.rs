@@ -1,42 +1,255 @@
 //! Common guitar scales.
 
-use std::{fmt, ops::RangeInclusive};
-
-use crate::note::{Note, STANDARD_TUNING};
-
-/// Represents common guitar scales and their semitone intervals.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Scale {
-    MajorPentatonic,
-    MinorPentatonic,
-    Major,
-    NaturalMinor,
-    Blues,
-    Mixolydian,
-    Dorian,
-    Lydian,
+use std::{borrow::Cow, fmt, ops::RangeInclusive, str::FromStr};
+
+use crate::{
+    error::Error,
+    key::Key,
+    note::{Note, Spelling, STANDARD_TUNING},
+};
+
+/// The semitones of the degrees of the major scale, used for reading scale formulas.
+const MAJOR_DEGREES: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// The built-in scales, in the order [`Scale::next`] steps through them.
+static SCALES: &[Scale] = &[
+    Scale::MAJOR_PENTATONIC,
+    Scale::MINOR_PENTATONIC,
+    Scale::BLUES,
+    Scale::MAJOR_BLUES,
+    Scale::MAJOR,
+    Scale::DORIAN,
+    Scale::PHRYGIAN,
+    Scale::LYDIAN,
+    Scale::MIXOLYDIAN,
+    Scale::NATURAL_MINOR,
+    Scale::LOCRIAN,
+    Scale::HARMONIC_MINOR,
+    Scale::LOCRIAN_NATURAL_6,
+    Scale::IONIAN_SHARP_5,
+    Scale::DORIAN_SHARP_4,
+    Scale::PHRYGIAN_DOMINANT,
+    Scale::LYDIAN_SHARP_2,
+    Scale::ULTRALOCRIAN,
+    Scale::MELODIC_MINOR,
+    Scale::DORIAN_FLAT_2,
+    Scale::LYDIAN_AUGMENTED,
+    Scale::LYDIAN_DOMINANT,
+    Scale::MIXOLYDIAN_FLAT_6,
+    Scale::LOCRIAN_NATURAL_2,
+    Scale::ALTERED,
+    Scale::WHOLE_TONE,
+    Scale::DIMINISHED,
+    Scale::DOMINANT_DIMINISHED,
+    Scale::HUNGARIAN_MINOR,
+    Scale::DOUBLE_HARMONIC,
+    Scale::NEAPOLITAN_MINOR,
+    Scale::NEAPOLITAN_MAJOR,
+    Scale::HIRAJOSHI,
+    Scale::IN_SEN,
+    Scale::CHROMATIC,
+];
+
+/// A scale, defined by its name and the semitones of its notes above the root.
+///
+/// The built-in scales are available as constants (e.g. [`Scale::MAJOR`]) and
+/// through [`Scale::all`]. Other scales can be made from a formula with [`Scale::custom`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scale {
+    /// The name of the scale.
+    name: Cow<'static, str>,
+    /// The semitone steps of the scale, relative to the root note.
+    intervals: Cow<'static, [u8]>,
+    /// Other names the scale is known by.
+    aliases: &'static [&'static str],
 }
 
 impl fmt::Display for Scale {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let name = match self {
-            Scale::MajorPentatonic => "Major Pentatonic",
-            Scale::MinorPentatonic => "Minor Pentatonic",
-            Scale::Major => "Major",
-            Scale::NaturalMinor => "Natural Minor",
-            Scale::Blues => "Blues",
-            Scale::Mixolydian => "Mixolydian",
-            Scale::Dorian => "Dorian",
-            Scale::Lydian => "Lydian",
-        };
-        write!(f, "{name}")
+        write!(f, "{}", self.name)
+    }
+}
+
+impl FromStr for Scale {
+    type Err = Error;
+
+    /// Parses a scale from its name or alias, or else from a formula like `1 2 b3 4 5 b6 7`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(scale) = Scale::from_name(s) {
+            Ok(scale)
+        } else if s.contains(|c: char| c.is_ascii_digit()) {
+            Scale::custom(s.trim(), s)
+        } else {
+            Err(Error::UnknownScale(s.to_string()))
+        }
     }
 }
 
 impl Scale {
+    pub const MAJOR_PENTATONIC: Scale =
+        Scale::new_static("Major Pentatonic", &[0, 2, 4, 7, 9], &[]);
+    pub const MINOR_PENTATONIC: Scale =
+        Scale::new_static("Minor Pentatonic", &[0, 3, 5, 7, 10], &[]);
+    pub const BLUES: Scale = Scale::new_static("Blues", &[0, 3, 5, 6, 7, 10], &["Minor Blues"]);
+    pub const MAJOR_BLUES: Scale = Scale::new_static("Major Blues", &[0, 2, 3, 4, 7, 9], &[]);
+    pub const MAJOR: Scale = Scale::new_static("Major", &[0, 2, 4, 5, 7, 9, 11], &["Ionian"]);
+    pub const DORIAN: Scale = Scale::new_static("Dorian", &[0, 2, 3, 5, 7, 9, 10], &[]);
+    pub const PHRYGIAN: Scale = Scale::new_static("Phrygian", &[0, 1, 3, 5, 7, 8, 10], &[]);
+    pub const LYDIAN: Scale = Scale::new_static("Lydian", &[0, 2, 4, 6, 7, 9, 11], &[]);
+    pub const MIXOLYDIAN: Scale =
+        Scale::new_static("Mixolydian", &[0, 2, 4, 5, 7, 9, 10], &["Dominant"]);
+    pub const NATURAL_MINOR: Scale = Scale::new_static(
+        "Natural Minor",
+        &[0, 2, 3, 5, 7, 8, 10],
+        &["Minor", "Aeolian"],
+    );
+    pub const LOCRIAN: Scale = Scale::new_static("Locrian", &[0, 1, 3, 5, 6, 8, 10], &[]);
+    pub const HARMONIC_MINOR: Scale =
+        Scale::new_static("Harmonic Minor", &[0, 2, 3, 5, 7, 8, 11], &[]);
+    pub const LOCRIAN_NATURAL_6: Scale = Scale::new_static(
+        "Locrian ♮6",
+        &[0, 1, 3, 5, 6, 9, 10],
+        &["Locrian Natural 6"],
+    );
+    pub const IONIAN_SHARP_5: Scale =
+        Scale::new_static("Ionian #5", &[0, 2, 4, 5, 8, 9, 11], &["Augmented Major"]);
+    pub const DORIAN_SHARP_4: Scale = Scale::new_static(
+        "Dorian #4",
+        &[0, 2, 3, 6, 7, 9, 10],
+        &["Ukrainian Dorian", "Romanian Minor"],
+    );
+    pub const PHRYGIAN_DOMINANT: Scale = Scale::new_static(
+        "Phrygian Dominant",
+        &[0, 1, 4, 5, 7, 8, 10],
+        &["Spanish Phrygian", "Freygish"],
+    );
+    pub const LYDIAN_SHARP_2: Scale = Scale::new_static("Lydian #2", &[0, 3, 4, 6, 7, 9, 11], &[]);
+    pub const ULTRALOCRIAN: Scale = Scale::new_static(
+        "Ultralocrian",
+        &[0, 1, 3, 4, 6, 8, 9],
+        &["Super Locrian bb7", "Altered Diminished"],
+    );
+    pub const MELODIC_MINOR: Scale =
+        Scale::new_static("Melodic Minor", &[0, 2, 3, 5, 7, 9, 11], &["Jazz Minor"]);
+    pub const DORIAN_FLAT_2: Scale =
+        Scale::new_static("Dorian b2", &[0, 1, 3, 5, 7, 9, 10], &["Phrygian #6"]);
+    pub const LYDIAN_AUGMENTED: Scale =
+        Scale::new_static("Lydian Augmented", &[0, 2, 4, 6, 8, 9, 11], &["Lydian #5"]);
+    pub const LYDIAN_DOMINANT: Scale = Scale::new_static(
+        "Lydian Dominant",
+        &[0, 2, 4, 6, 7, 9, 10],
+        &["Overtone", "Acoustic"],
+    );
+    pub const MIXOLYDIAN_FLAT_6: Scale = Scale::new_static(
+        "Mixolydian b6",
+        &[0, 2, 4, 5, 7, 8, 10],
+        &["Aeolian Dominant", "Hindu"],
+    );
+    pub const LOCRIAN_NATURAL_2: Scale = Scale::new_static(
+        "Locrian ♮2",
+        &[0, 2, 3, 5, 6, 8, 10],
+        &["Locrian Natural 2", "Half Diminished"],
+    );
+    pub const ALTERED: Scale = Scale::new_static(
+        "Altered",
+        &[0, 1, 3, 4, 6, 8, 10],
+        &["Super Locrian", "Altered Dominant"],
+    );
+    pub const WHOLE_TONE: Scale = Scale::new_static("Whole Tone", &[0, 2, 4, 6, 8, 10], &[]);
+    pub const DIMINISHED: Scale = Scale::new_static(
+        "Diminished",
+        &[0, 2, 3, 5, 6, 8, 9, 11],
+        &["Whole-Half Diminished", "Octatonic"],
+    );
+    pub const DOMINANT_DIMINISHED: Scale = Scale::new_static(
+        "Dominant Diminished",
+        &[0, 1, 3, 4, 6, 7, 9, 10],
+        &["Half-Whole Diminished"],
+    );
+    pub const HUNGARIAN_MINOR: Scale =
+        Scale::new_static("Hungarian Minor", &[0, 2, 3, 6, 7, 8, 11], &["Gypsy Minor"]);
+    pub const DOUBLE_HARMONIC: Scale = Scale::new_static(
+        "Double Harmonic",
+        &[0, 1, 4, 5, 7, 8, 11],
+        &["Byzantine", "Arabic"],
+    );
+    pub const NEAPOLITAN_MINOR: Scale =
+        Scale::new_static("Neapolitan Minor", &[0, 1, 3, 5, 7, 8, 11], &[]);
+    pub const NEAPOLITAN_MAJOR: Scale =
+        Scale::new_static("Neapolitan Major", &[0, 1, 3, 5, 7, 9, 11], &[]);
+    pub const HIRAJOSHI: Scale = Scale::new_static("Hirajoshi", &[0, 2, 3, 7, 8], &[]);
+    pub const IN_SEN: Scale = Scale::new_static("In Sen", &[0, 1, 5, 7, 10], &["Insen"]);
+    pub const CHROMATIC: Scale =
+        Scale::new_static("Chromatic", &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11], &[]);
+
+    /// Creates a built-in scale.
+    const fn new_static(
+        name: &'static str,
+        intervals: &'static [u8],
+        aliases: &'static [&'static str],
+    ) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            intervals: Cow::Borrowed(intervals),
+            aliases,
+        }
+    }
+
+    /// Creates a scale from a formula of degrees relative to the major scale, e.g. `1 2 b3 4 5 b6 7`.
+    ///
+    /// Degrees are separated by spaces or commas and may have any number of flats
+    /// (`b`, `♭`) or sharps (`#`, `♯`). Degrees above 7 are reduced to the octave,
+    /// and the root is always included.
+    pub fn custom(name: impl Into<String>, formula: &str) -> Result<Self, Error> {
+        let mut intervals = vec![0];
+        for degree in formula
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|degree| !degree.is_empty())
+        {
+            intervals.push(parse_degree(degree)?);
+        }
+        intervals.sort_unstable();
+        intervals.dedup();
+        Ok(Self {
+            name: Cow::Owned(name.into()),
+            intervals: Cow::Owned(intervals),
+            aliases: &[],
+        })
+    }
+
+    /// Returns all built-in scales.
+    pub fn all() -> &'static [Scale] {
+        SCALES
+    }
+
+    /// Returns the built-in scale with the given name or alias, ignoring case, spaces and dashes.
+    pub fn from_name(name: &str) -> Option<Scale> {
+        let name = normalize_name(name);
+        SCALES
+            .iter()
+            .find(|scale| {
+                normalize_name(&scale.name) == name
+                    || scale
+                        .aliases
+                        .iter()
+                        .any(|alias| normalize_name(alias) == name)
+            })
+            .cloned()
+    }
+
+    /// Returns the name of the scale.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the other names the scale is known by.
+    pub fn aliases(&self) -> &[&'static str] {
+        self.aliases
+    }
+
     /// Returns the pitch classes (0-11) of the scale, ignoring octave.
     pub fn pitch_classes(&self, root: Note) -> Vec<u8> {
-        let root_pc = root.semitone_index() % 12;
+        let root_pc = root.pitch_class();
         self.intervals()
             .iter()
             .map(|&interval| (root_pc + interval) % 12)
@@ -44,17 +257,32 @@ impl Scale {
     }
 
     /// Returns the semitone steps for the scale, relative to the root note.
-    pub fn intervals(&self) -> &'static [u8] {
-        match self {
-            Scale::MajorPentatonic => &[0, 2, 4, 7, 9],
-            Scale::MinorPentatonic => &[0, 3, 5, 7, 10],
-            Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
-            Scale::NaturalMinor => &[0, 2, 3, 5, 7, 8, 10],
-            Scale::Blues => &[0, 3, 5, 6, 7, 10],
-            Scale::Mixolydian => &[0, 2, 4, 5, 7, 9, 10],
-            Scale::Dorian => &[0, 2, 3, 5, 7, 9, 10],
-            Scale::Lydian => &[0, 2, 4, 6, 7, 9, 11],
-        }
+    pub fn intervals(&self) -> &[u8] {
+        &self.intervals
+    }
+
+    /// Returns the scale starting on the given degree of this one, `1` being the scale itself.
+    ///
+    /// E.g. the second mode of the major scale is the dorian scale.
+    pub fn mode(&self, degree: usize) -> Option<Scale> {
+        let offset = *self.intervals.get(degree.checked_sub(1)?)?;
+        let mut intervals: Vec<u8> = self
+            .intervals
+            .iter()
+            .map(|interval| (interval + 12 - offset) % 12)
+            .collect();
+        intervals.sort_unstable();
+        Some(
+            SCALES
+                .iter()
+                .find(|scale| *scale.intervals == intervals)
+                .cloned()
+                .unwrap_or_else(|| Self {
+                    name: Cow::Owned(format!("{} mode {degree}", self.name)),
+                    intervals: Cow::Owned(intervals),
+                    aliases: &[],
+                }),
+        )
     }
 
     /// Generates the notes of the scale starting from the given root note.
@@ -65,6 +293,24 @@ impl Scale {
             .collect()
     }
 
+    /// Returns the spelling of the scale, based on whether it has a major or minor third.
+    pub fn spelling(&self, root: Note) -> Spelling {
+        if self.intervals.contains(&4) {
+            Spelling::Key(Key::major(root))
+        } else {
+            Spelling::Key(Key::minor(root))
+        }
+    }
+
+    /// Returns the names of the notes of the scale, spelled with [`Scale::spelling`].
+    pub fn note_names(&self, root: Note) -> Vec<String> {
+        let spelling = self.spelling(root);
+        self.notes(root)
+            .iter()
+            .map(|note| note.spelled_name(spelling))
+            .collect()
+    }
+
     /// Returns all notes of this scale found on the given tuning and fret range.
     pub fn fretboard_notes(&self, root: Note, frets: &RangeInclusive<u8>) -> Vec<Note> {
         let scale_pcs = self.pitch_classes(root);
@@ -84,19 +330,59 @@ impl Scale {
             .collect()
     }
 
-    /// Returns the next scale in a predefined sequence.
+    /// Returns the next built-in scale.
+    ///
+    /// Scales that are not built in are followed by the first one.
     pub fn next(&self) -> Scale {
-        match self {
-            Scale::MajorPentatonic => Scale::MinorPentatonic,
-            Scale::MinorPentatonic => Scale::Blues,
-            Scale::Major => Scale::NaturalMinor,
-            Scale::NaturalMinor => Scale::MajorPentatonic,
-            Scale::Blues => Scale::Mixolydian,
-            Scale::Mixolydian => Scale::Dorian,
-            Scale::Dorian => Scale::Lydian,
-            Scale::Lydian => Scale::Major,
-        }
+        let next = self
+            .position()
+            .map_or(0, |index| (index + 1) % SCALES.len());
+        SCALES[next].clone()
+    }
+
+    /// Returns the previous built-in scale.
+    ///
+    /// Scales that are not built in are preceded by the last one.
+    pub fn previous(&self) -> Scale {
+        let previous = self.position().map_or(SCALES.len() - 1, |index| {
+            (index + SCALES.len() - 1) % SCALES.len()
+        });
+        SCALES[previous].clone()
+    }
+
+    /// Returns the index of the scale in the built-in scales.
+    fn position(&self) -> Option<usize> {
+        SCALES
+            .iter()
+            .position(|scale| scale.intervals == self.intervals)
+    }
+}
+
+/// Returns the semitones above the root of a degree like `b3` or `#11`.
+fn parse_degree(degree: &str) -> Result<u8, Error> {
+    let invalid = || Error::InvalidDegree(degree.to_string());
+    let number_start = degree
+        .find(|c: char| c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (accidentals, number) = degree.split_at(number_start);
+    let number: usize = number.parse().map_err(|_| invalid())?;
+    let mut semitones = MAJOR_DEGREES[number.checked_sub(1).ok_or_else(invalid)? % 7] as i16;
+    for accidental in accidentals.chars() {
+        semitones += match accidental {
+            '#' | '♯' => 1,
+            'b' | '♭' => -1,
+            _ => return Err(invalid()),
+        };
     }
+    Ok(semitones.rem_euclid(12) as u8)
+}
+
+/// Returns the name in lowercase, without spaces, dashes and underscores.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
 }
 
 #[cfg(test)]
@@ -107,14 +393,14 @@ mod tests {
     use Note::*;
 
     #[rstest]
-    #[case::major_pentatonic(Scale::MajorPentatonic, vec!["C4", "D4", "E4", "G4", "A4"])]
-    #[case::minor_pentatonic(Scale::MinorPentatonic, vec!["C4", "D#4", "F4", "G4", "A#4"])]
-    #[case::major(Scale::Major, vec!["C4", "D4", "E4", "F4", "G4", "A4", "B4"])]
-    #[case::natural_minor(Scale::NaturalMinor, vec!["C4", "D4", "D#4", "F4", "G4", "G#4", "A#4"])]
-    #[case::scales(Scale::Blues, vec!["C4", "D#4", "F4", "F#4", "G4", "A#4"])]
-    #[case::mixolydian(Scale::Mixolydian, vec!["C4", "D4", "E4", "F4", "G4", "A4", "A#4"])]
-    #[case::dorian(Scale::Dorian, vec!["C4", "D4", "D#4", "F4", "G4", "A4", "A#4"])]
-    #[case::lydian(Scale::Lydian, vec!["C4", "D4", "E4", "F#4", "G4", "A4", "B4"])]
+    #[case::major_pentatonic(Scale::MAJOR_PENTATONIC, vec!["C4", "D4", "E4", "G4", "A4"])]
+    #[case::minor_pentatonic(Scale::MINOR_PENTATONIC, vec!["C4", "D#4", "F4", "G4", "A#4"])]
+    #[case::major(Scale::MAJOR, vec!["C4", "D4", "E4", "F4", "G4", "A4", "B4"])]
+    #[case::natural_minor(Scale::NATURAL_MINOR, vec!["C4", "D4", "D#4", "F4", "G4", "G#4", "A#4"])]
+    #[case::scales(Scale::BLUES, vec!["C4", "D#4", "F4", "F#4", "G4", "A#4"])]
+    #[case::mixolydian(Scale::MIXOLYDIAN, vec!["C4", "D4", "E4", "F4", "G4", "A4", "A#4"])]
+    #[case::dorian(Scale::DORIAN, vec!["C4", "D4", "D#4", "F4", "G4", "A4", "A#4"])]
+    #[case::lydian(Scale::LYDIAN, vec!["C4", "D4", "E4", "F#4", "G4", "A4", "B4"])]
     fn test_scales(#[case] scale: Scale, #[case] expected: Vec<&str>) {
         let root = Note::C(4);
         let notes = scale.notes(root);
@@ -123,12 +409,23 @@ mod tests {
     }
 
     #[rstest]
-    #[case(Scale::MajorPentatonic, Note::A(4), 0..=5, vec![
+    #[case(Scale::MAJOR, F(4), vec!["F", "G", "A", "Bb", "C", "D", "E"])]
+    #[case(Scale::NATURAL_MINOR, C(4), vec!["C", "D", "Eb", "F", "G", "Ab", "Bb"])]
+    #[case(Scale::BLUES, A(4), vec!["A", "C", "D", "Eb", "E", "G"])]
+    #[case(Scale::BLUES, G(4), vec!["G", "Bb", "C", "Db", "D", "F"])]
+    #[case(Scale::LYDIAN, F(4), vec!["F", "G", "A", "B", "C", "D", "E"])]
+    #[case(Scale::MAJOR_PENTATONIC, FSharp(4), vec!["F#", "G#", "A#", "C#", "D#"])]
+    fn test_note_names(#[case] scale: Scale, #[case] root: Note, #[case] expected: Vec<&str>) {
+        assert_eq!(expected, scale.note_names(root));
+    }
+
+    #[rstest]
+    #[case(Scale::MAJOR_PENTATONIC, Note::A(4), 0..=5, vec![
         E(2), FSharp(2), A(2), A(2), B(2), CSharp(3),
         E(3), FSharp(3), A(3), B(3), B(3), CSharp(4),
         E(4), E(4), FSharp(4), A(4)
     ])]
-    #[case(Scale::MinorPentatonic, Note::E(2), 0..=3, vec![
+    #[case(Scale::MINOR_PENTATONIC, Note::E(2), 0..=3, vec![
         E(2), G(2), A(2), B(2),
         D(3), E(3), G(3), A(3), B(3),
         D(4), E(4), G(4)
@@ -142,4 +439,108 @@ mod tests {
         let result = scale.fretboard_notes(root, &frets);
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_catalogue() {
+        for (i, scale) in Scale::all().iter().enumerate() {
+            assert_eq!(Some(&0), scale.intervals().first(), "{scale}");
+            assert!(scale
+                .intervals()
+                .windows(2)
+                .all(|w| w[0] < w[1] && w[1] < 12));
+            for other in &Scale::all()[i + 1..] {
+                assert_ne!(scale.intervals(), other.intervals(), "{scale} and {other}");
+                assert_ne!(scale.name(), other.name());
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::major(Scale::MAJOR, vec!["Major", "Dorian", "Phrygian", "Lydian", "Mixolydian", "Natural Minor", "Locrian"])]
+    #[case::harmonic_minor(Scale::HARMONIC_MINOR, vec!["Harmonic Minor", "Locrian ♮6", "Ionian #5", "Dorian #4", "Phrygian Dominant", "Lydian #2", "Ultralocrian"])]
+    #[case::melodic_minor(Scale::MELODIC_MINOR, vec!["Melodic Minor", "Dorian b2", "Lydian Augmented", "Lydian Dominant", "Mixolydian b6", "Locrian ♮2", "Altered"])]
+    #[case::pentatonic(Scale::MAJOR_PENTATONIC, vec!["Major Pentatonic", "Major Pentatonic mode 2", "Major Pentatonic mode 3", "Major Pentatonic mode 4", "Minor Pentatonic"])]
+    fn test_modes(#[case] scale: Scale, #[case] expected: Vec<&str>) {
+        let modes: Vec<String> = (1..=scale.intervals().len())
+            .filter_map(|degree| scale.mode(degree))
+            .map(|mode| mode.to_string())
+            .collect();
+        assert_eq!(expected, modes);
+        assert_eq!(None, scale.mode(0));
+        assert_eq!(None, scale.mode(scale.intervals().len() + 1));
+    }
+
+    #[rstest]
+    #[case("1 2 b3 4 5 b6 7", &[0, 2, 3, 5, 7, 8, 11])]
+    #[case("1, b3, 4, #4, 5, b7", &[0, 3, 5, 6, 7, 10])]
+    #[case("3 5 7 9 #11", &[0, 2, 4, 6, 7, 11])]
+    #[case("1 ♭2 ♯4 bb7", &[0, 1, 6, 9])]
+    #[case("", &[0])]
+    fn test_custom(#[case] formula: &str, #[case] expected: &[u8]) {
+        let scale = Scale::custom("Custom", formula).unwrap();
+        assert_eq!(expected, scale.intervals());
+        assert_eq!("Custom", scale.to_string());
+    }
+
+    #[rstest]
+    #[case("1 2 x3", "x3")]
+    #[case("1 0", "0")]
+    #[case("1 b", "b")]
+    #[case("1 3b", "3b")]
+    fn test_custom_error(#[case] formula: &str, #[case] degree: &str) {
+        assert_eq!(
+            Err(Error::InvalidDegree(degree.to_string())),
+            Scale::custom("Custom", formula)
+        );
+    }
+
+    #[rstest]
+    #[case("Major", Some(Scale::MAJOR))]
+    #[case("ionian", Some(Scale::MAJOR))]
+    #[case("AEOLIAN", Some(Scale::NATURAL_MINOR))]
+    #[case("harmonic minor", Some(Scale::HARMONIC_MINOR))]
+    #[case("super-locrian", Some(Scale::ALTERED))]
+    #[case("Whole_Tone", Some(Scale::WHOLE_TONE))]
+    #[case("Locrian ♮2", Some(Scale::LOCRIAN_NATURAL_2))]
+    #[case("Gypsy", None)]
+    fn test_from_name(#[case] name: &str, #[case] expected: Option<Scale>) {
+        assert_eq!(expected, Scale::from_name(name));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(Scale::DORIAN), "dorian".parse());
+        assert_eq!(
+            Ok(&[0, 1, 5, 7, 8][..]),
+            "1 b2 4 5 b6"
+                .parse::<Scale>()
+                .as_ref()
+                .map(Scale::intervals)
+        );
+        assert_eq!(
+            Err(Error::UnknownScale("Gypsy".to_string())),
+            "Gypsy".parse::<Scale>()
+        );
+    }
+
+    #[test]
+    fn test_next_and_previous() {
+        assert_eq!(Scale::MINOR_PENTATONIC, Scale::MAJOR_PENTATONIC.next());
+        assert_eq!(Scale::MAJOR_PENTATONIC, Scale::MINOR_PENTATONIC.previous());
+        assert_eq!(Scale::MAJOR_PENTATONIC, Scale::CHROMATIC.next());
+        assert_eq!(Scale::CHROMATIC, Scale::MAJOR_PENTATONIC.previous());
+
+        let custom = Scale::custom("Custom", "1 b2 4 5 b6").unwrap();
+        assert_eq!(Scale::MAJOR_PENTATONIC, custom.next());
+        assert_eq!(Scale::CHROMATIC, custom.previous());
+        // A custom scale with built-in intervals continues from the built-in one
+        let minor = Scale::custom("My Minor", "1 2 b3 4 5 b6 b7").unwrap();
+        assert_eq!(Scale::LOCRIAN, minor.next());
+
+        let mut scale = Scale::MAJOR;
+        for _ in 0..Scale::all().len() {
+            scale = scale.next();
+        }
+        assert_eq!(Scale::MAJOR, scale);
+    }
 }
@@ -0,0 +1,437 @@
+//! Staff notation widget.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::StatefulWidget,
+};
+
+use crate::{note::Note, TabState};
+
+/// The number of rows between the bottom and top line of the staff.
+const STAFF_STEPS: i16 = 8;
+
+/// The width of the clef on the left side.
+const CLEF_WIDTH: u16 = 3;
+
+/// The width of a beat: the accidental, the note head and a gap.
+const BEAT_WIDTH: u16 = 4;
+
+/// The default number of played beats that stay visible before the cursor.
+const DEFAULT_LOOKBEHIND: usize = 2;
+
+/// The clef of a staff.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Clef {
+    /// Treble clef, the bottom line is E4.
+    Treble,
+    /// Bass clef, the bottom line is G2.
+    Bass,
+    /// Treble clef sounding an octave lower, as used for guitar music.
+    #[default]
+    Guitar,
+}
+
+impl Clef {
+    /// Returns the symbol of the clef.
+    pub fn symbol(&self) -> char {
+        match self {
+            Clef::Treble | Clef::Guitar => '𝄞',
+            Clef::Bass => '𝄢',
+        }
+    }
+
+    /// Returns the diatonic step of the bottom line.
+    fn bottom_line(&self) -> i16 {
+        match self {
+            Clef::Treble | Clef::Guitar => diatonic_step(Note::E(4)),
+            Clef::Bass => diatonic_step(Note::G(2)),
+        }
+    }
+
+    /// Returns the number of steps above the bottom line the clef symbol is drawn at.
+    fn symbol_offset(&self) -> i16 {
+        match self {
+            Clef::Treble | Clef::Guitar => 2,
+            Clef::Bass => 6,
+        }
+    }
+
+    /// Returns how many semitones the notes are written above their pitch.
+    fn transposition(&self) -> i16 {
+        match self {
+            Clef::Guitar => 12,
+            Clef::Treble | Clef::Bass => 0,
+        }
+    }
+
+    /// Returns the notes that are written on the staff or the spaces right next to it.
+    pub fn staff_range(&self) -> std::ops::RangeInclusive<Note> {
+        let (low, high) = match self {
+            Clef::Treble => (Note::D(4), Note::G(5)),
+            Clef::Bass => (Note::F(2), Note::B(3)),
+            Clef::Guitar => (Note::D(3), Note::G(4)),
+        };
+        low..=high
+    }
+}
+
+/// Returns the position of the note on the staff, counting lines and spaces from C0.
+///
+/// Sharps share the position of the natural note below them.
+pub fn diatonic_step(note: Note) -> i16 {
+    let index = match note {
+        Note::C(_) | Note::CSharp(_) => 0,
+        Note::D(_) | Note::DSharp(_) => 1,
+        Note::E(_) => 2,
+        Note::F(_) | Note::FSharp(_) => 3,
+        Note::G(_) | Note::GSharp(_) => 4,
+        Note::A(_) | Note::ASharp(_) => 5,
+        Note::B(_) => 6,
+    };
+    (note.semitone_index() / 12) as i16 * 7 + index
+}
+
+/// A five-line staff that shows notes with ledger lines and accidentals.
+///
+/// Uses the same state as the [`Tab`](crate::Tab) widget, so the current
+/// beat, played and missed notes are shown the same way.
+pub struct Staff {
+    /// The clef of the staff.
+    clef: Clef,
+    /// The notes played at the same time, for each beat.
+    beats: Vec<Vec<Note>>,
+    /// The number of played beats that stay visible before the cursor.
+    lookbehind: usize,
+    /// The style for the staff and ledger lines.
+    line_style: Style,
+    /// The style for the clef.
+    clef_style: Style,
+    /// The symbol drawn for the clef instead of the one of the clef.
+    clef_symbol: Option<char>,
+    /// The symbol used for note heads.
+    note_symbol: char,
+    /// The style for notes that were played.
+    played_style: Style,
+    /// The style for notes that are yet to be played.
+    upcoming_style: Style,
+    /// The style for notes that were missed.
+    missed_style: Style,
+    /// The style added to the notes of the current beat.
+    cursor_style: Style,
+}
+
+impl Default for Staff {
+    /// Creates an empty `Staff` with a guitar clef.
+    fn default() -> Self {
+        Self {
+            clef: Clef::default(),
+            beats: Vec::new(),
+            lookbehind: DEFAULT_LOOKBEHIND,
+            line_style: Style::default().fg(Color::DarkGray),
+            clef_style: Style::default().fg(Color::Green),
+            clef_symbol: None,
+            note_symbol: '●',
+            played_style: Style::default().fg(Color::Gray),
+            upcoming_style: Style::default().fg(Color::Blue),
+            missed_style: Style::default().fg(Color::Red),
+            cursor_style: Style::default().fg(Color::Yellow),
+        }
+    }
+}
+
+impl Staff {
+    /// Creates a new `Staff` with default settings.
+    ///
+    /// This is equivalent to `Staff::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the clef of the staff.
+    pub fn with_clef(mut self, clef: Clef) -> Self {
+        self.clef = clef;
+        self
+    }
+
+    /// Sets the notes played at the same time, for each beat.
+    pub fn with_beats(mut self, beats: Vec<Vec<Note>>) -> Self {
+        self.beats = beats;
+        self
+    }
+
+    /// Sets the number of played beats that stay visible before the cursor.
+    pub fn with_lookbehind(mut self, lookbehind: usize) -> Self {
+        self.lookbehind = lookbehind;
+        self
+    }
+
+    /// Sets the style for the staff and ledger lines.
+    pub fn with_line_style(mut self, style: Style) -> Self {
+        self.line_style = style;
+        self
+    }
+
+    /// Sets the style for the clef.
+    pub fn with_clef_style(mut self, style: Style) -> Self {
+        self.clef_style = style;
+        self
+    }
+
+    /// Sets the symbol drawn for the clef, e.g. for fonts without the clef symbols.
+    pub fn with_clef_symbol(mut self, symbol: char) -> Self {
+        self.clef_symbol = Some(symbol);
+        self
+    }
+
+    /// Sets the symbol used for note heads.
+    pub fn with_note_symbol(mut self, symbol: char) -> Self {
+        self.note_symbol = symbol;
+        self
+    }
+
+    /// Sets the style for notes that were played.
+    pub fn with_played_style(mut self, style: Style) -> Self {
+        self.played_style = style;
+        self
+    }
+
+    /// Sets the style for notes that are yet to be played.
+    pub fn with_upcoming_style(mut self, style: Style) -> Self {
+        self.upcoming_style = style;
+        self
+    }
+
+    /// Sets the style for notes that were missed.
+    pub fn with_missed_style(mut self, style: Style) -> Self {
+        self.missed_style = style;
+        self
+    }
+
+    /// Sets the style added to the notes of the current beat.
+    pub fn with_cursor_style(mut self, style: Style) -> Self {
+        self.cursor_style = style;
+        self
+    }
+
+    /// Returns the style of the notes of the given beat.
+    fn note_style(&self, index: usize, state: &TabState) -> Style {
+        let style = if state.missed.contains(&index) {
+            self.missed_style
+        } else if index < state.cursor {
+            self.played_style
+        } else {
+            self.upcoming_style
+        };
+        if index == state.cursor {
+            style.patch(self.cursor_style)
+        } else {
+            style
+        }
+    }
+}
+
+impl StatefulWidget for &Staff {
+    type State = TabState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+        let bottom_line = self.clef.bottom_line();
+        let top_line = bottom_line + STAFF_STEPS;
+        // Center the staff, rows outside of the area are not drawn
+        let above = (area.height as i16 - STAFF_STEPS - 1) / 2;
+        let row = |step: i16| -> Option<u16> {
+            let y = area.y as i16 + above + (top_line - step);
+            (y >= area.y as i16 && y < area.bottom() as i16).then_some(y as u16)
+        };
+        let mut put = |x: u16, y: Option<u16>, symbol: char, style: Style| {
+            if let Some(y) = y {
+                if x < area.right() {
+                    buf[(x, y)].set_char(symbol).set_style(style);
+                }
+            }
+        };
+
+        for step in (bottom_line..=top_line).step_by(2) {
+            for x in area.left()..area.right() {
+                put(x, row(step), '─', self.line_style);
+            }
+        }
+        put(
+            area.x + 1,
+            row(bottom_line + self.clef.symbol_offset()),
+            self.clef_symbol.unwrap_or(self.clef.symbol()),
+            self.clef_style,
+        );
+
+        let first = state.cursor.saturating_sub(self.lookbehind);
+        let mut x = area.x + CLEF_WIDTH;
+        for (index, beat) in self.beats.iter().enumerate().skip(first) {
+            if x + BEAT_WIDTH > area.right() + 1 {
+                break;
+            }
+            let style = self.note_style(index, state);
+            let steps: Vec<(i16, bool)> = beat
+                .iter()
+                // Notes that would be written above G10 are left out
+                .filter_map(|note| note.checked_transpose(self.clef.transposition()))
+                .map(|written| (diatonic_step(written), !written.is_natural()))
+                .collect();
+
+            // Ledger lines up to the highest and down to the lowest note
+            let highest = steps.iter().map(|(step, _)| *step).max();
+            let lowest = steps.iter().map(|(step, _)| *step).min();
+            let mut ledgers = Vec::new();
+            if let Some(highest) = highest {
+                ledgers.extend((top_line + 2..=highest).step_by(2));
+            }
+            if let Some(lowest) = lowest {
+                ledgers.extend(
+                    (lowest..=bottom_line - 2)
+                        .rev()
+                        .step_by(2)
+                        .filter(|step| (bottom_line - step) % 2 == 0),
+                );
+            }
+            for step in ledgers {
+                for dx in 0..3 {
+                    put(x + dx, row(step), '─', self.line_style);
+                }
+            }
+
+            for (step, sharp) in steps {
+                put(x + 1, row(step), self.note_symbol, style);
+                if sharp {
+                    put(x, row(step), '♯', style);
+                }
+            }
+            x += BEAT_WIDTH;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{buffer::Buffer, layout::Rect};
+    use rstest::*;
+
+    fn plain(clef: Clef) -> Staff {
+        Staff::default()
+            .with_clef(clef)
+            .with_line_style(Style::default())
+            .with_clef_style(Style::default())
+            .with_played_style(Style::default())
+            .with_upcoming_style(Style::default())
+            .with_missed_style(Style::default())
+            .with_cursor_style(Style::default())
+    }
+
+    #[rstest]
+    #[case::treble(
+        Clef::Treble,
+        vec![vec![Note::E(4)], vec![Note::FSharp(5)], vec![Note::C(4), Note::G(4)]],
+        Buffer::with_lines([
+            "                ",
+            "                ",
+            "───────♯●───────",
+            "                ",
+            "────────────────",
+            "                ",
+            "────────────────",
+            "                ",
+            "─𝄞──────────●───",
+            "                ",
+            "────●───────────",
+            "                ",
+            "           ─●─  ",
+        ])
+    )]
+    #[case::guitar_ledger_lines(
+        Clef::Guitar,
+        vec![vec![Note::E(3)], vec![Note::A(4)]],
+        Buffer::with_lines([
+            "       ─●─      ",
+            "                ",
+            "────────────────",
+            "                ",
+            "────────────────",
+            "                ",
+            "────────────────",
+            "                ",
+            "─𝄞──────────────",
+            "                ",
+            "────●───────────",
+            "                ",
+            "                ",
+        ])
+    )]
+    #[case::bass(
+        Clef::Bass,
+        vec![vec![Note::E(2)]],
+        Buffer::with_lines([
+            "                ",
+            "                ",
+            "────────────────",
+            "                ",
+            "─𝄢──────────────",
+            "                ",
+            "────────────────",
+            "                ",
+            "────────────────",
+            "                ",
+            "────────────────",
+            "                ",
+            "   ─●─          ",
+        ])
+    )]
+    fn render_staff(#[case] clef: Clef, #[case] beats: Vec<Vec<Note>>, #[case] expected: Buffer) {
+        let area = Rect::new(0, 0, 16, 13);
+        let mut buf = Buffer::empty(area);
+        plain(clef)
+            .with_beats(beats)
+            .render(area, &mut buf, &mut TabState::new());
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn render_clef_symbol_without_unwritable_notes() {
+        let area = Rect::new(0, 0, 16, 13);
+        let mut buf = Buffer::empty(area);
+        plain(Clef::Guitar)
+            .with_clef_symbol('G')
+            .with_beats(vec![vec![Note::A(9)], vec![Note::E(3), Note::G(10)]])
+            .render(area, &mut buf, &mut TabState::new());
+        assert_eq!(
+            buf,
+            Buffer::with_lines([
+                "                ",
+                "                ",
+                "────────────────",
+                "                ",
+                "────────────────",
+                "                ",
+                "────────────────",
+                "                ",
+                "─G──────────────",
+                "                ",
+                "────────●───────",
+                "                ",
+                "                ",
+            ])
+        );
+    }
+
+    #[rstest]
+    #[case(Note::C(4), 28)]
+    #[case(Note::CSharp(4), 28)]
+    #[case(Note::B(3), 27)]
+    #[case(Note::E(2), 16)]
+    fn test_diatonic_step(#[case] note: Note, #[case] expected: i16) {
+        assert_eq!(expected, diatonic_step(note));
+    }
+}
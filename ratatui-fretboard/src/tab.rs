@@ -0,0 +1,352 @@
+//! Tablature widget.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::StatefulWidget,
+};
+
+use crate::{
+    note::{Note, STANDARD_TUNING},
+    tablature::{TabBeat, Tablature},
+};
+
+/// The default number of played beats that stay visible before the cursor.
+const DEFAULT_LOOKBEHIND: usize = 2;
+
+/// State for the tablature and staff widgets.
+#[derive(Clone, Debug, Default)]
+pub struct TabState {
+    /// The index of the current beat.
+    pub cursor: usize,
+    /// The indices of the beats that were missed.
+    pub missed: Vec<usize>,
+}
+
+impl TabState {
+    /// Creates a new `TabState` with the cursor at the first beat.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the cursor to the given beat.
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor;
+    }
+
+    /// Moves the cursor to the next beat.
+    ///
+    /// If `missed` is `true`, the current beat is marked as missed.
+    pub fn advance(&mut self, missed: bool) {
+        if missed && !self.missed.contains(&self.cursor) {
+            self.missed.push(self.cursor);
+        }
+        self.cursor += 1;
+    }
+
+    /// Moves the cursor back to the first beat and forgets the missed beats.
+    pub fn reset(&mut self) {
+        self.cursor = 0;
+        self.missed.clear();
+    }
+}
+
+/// A scrolling tablature staff that follows the current beat.
+///
+/// Each string of the tuning gets a line, with the highest string on top.
+pub struct Tab {
+    /// The tuning of the strings, from the lowest string to the highest.
+    tuning: Vec<Note>,
+    /// The beats of the tablature.
+    beats: Vec<TabBeat>,
+    /// The number of played beats that stay visible before the cursor.
+    lookbehind: usize,
+    /// The style for string names.
+    note_name_style: Style,
+    /// The style for the strings.
+    string_style: Style,
+    /// The style for notes that were played.
+    played_style: Style,
+    /// The style for notes that are yet to be played.
+    upcoming_style: Style,
+    /// The style for notes that were missed.
+    missed_style: Style,
+    /// The style added to the column of the current beat.
+    cursor_style: Style,
+}
+
+impl Default for Tab {
+    /// Creates an empty `Tab` with standard guitar tuning.
+    fn default() -> Self {
+        Self {
+            tuning: STANDARD_TUNING.to_vec(),
+            beats: Vec::new(),
+            lookbehind: DEFAULT_LOOKBEHIND,
+            note_name_style: Style::default().fg(Color::Green),
+            string_style: Style::default().fg(Color::DarkGray),
+            played_style: Style::default().fg(Color::Gray),
+            upcoming_style: Style::default().fg(Color::Blue),
+            missed_style: Style::default().fg(Color::Red),
+            cursor_style: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+impl From<&Tablature> for Tab {
+    fn from(tablature: &Tablature) -> Self {
+        Self::default()
+            .with_tuning(tablature.tuning().to_vec())
+            .with_beats(tablature.beats().to_vec())
+    }
+}
+
+impl Tab {
+    /// Creates a new `Tab` with default settings.
+    ///
+    /// This is equivalent to `Tab::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the tuning of the strings, from the lowest string to the highest.
+    pub fn with_tuning(mut self, tuning: Vec<Note>) -> Self {
+        self.tuning = tuning;
+        self
+    }
+
+    /// Sets the beats of the tablature.
+    pub fn with_beats(mut self, beats: Vec<TabBeat>) -> Self {
+        self.beats = beats;
+        self
+    }
+
+    /// Sets the number of played beats that stay visible before the cursor.
+    pub fn with_lookbehind(mut self, lookbehind: usize) -> Self {
+        self.lookbehind = lookbehind;
+        self
+    }
+
+    /// Sets the style for string names.
+    pub fn with_note_name_style(mut self, style: Style) -> Self {
+        self.note_name_style = style;
+        self
+    }
+
+    /// Sets the style for the strings.
+    pub fn with_string_style(mut self, style: Style) -> Self {
+        self.string_style = style;
+        self
+    }
+
+    /// Sets the style for notes that were played.
+    pub fn with_played_style(mut self, style: Style) -> Self {
+        self.played_style = style;
+        self
+    }
+
+    /// Sets the style for notes that are yet to be played.
+    pub fn with_upcoming_style(mut self, style: Style) -> Self {
+        self.upcoming_style = style;
+        self
+    }
+
+    /// Sets the style for notes that were missed.
+    pub fn with_missed_style(mut self, style: Style) -> Self {
+        self.missed_style = style;
+        self
+    }
+
+    /// Sets the style added to the column of the current beat.
+    pub fn with_cursor_style(mut self, style: Style) -> Self {
+        self.cursor_style = style;
+        self
+    }
+
+    /// Returns the style of the notes of the given beat.
+    fn note_style(&self, index: usize, state: &TabState) -> Style {
+        if state.missed.contains(&index) {
+            self.missed_style
+        } else if index < state.cursor {
+            self.played_style
+        } else {
+            self.upcoming_style
+        }
+    }
+}
+
+impl StatefulWidget for &Tab {
+    type State = TabState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let strings = self.tuning.len();
+        let first = state.cursor.saturating_sub(self.lookbehind);
+
+        // The cells of the visible beats, with the strings from the highest to the lowest
+        let label_width = 4;
+        let mut width = label_width;
+        let mut columns: Vec<(usize, Vec<String>)> = Vec::new();
+        for (index, beat) in self.beats.iter().enumerate().skip(first) {
+            let cells = beat.cells(strings, '─');
+            width += cells.first().map_or(0, |cell| cell.chars().count());
+            if width > area.width as usize {
+                break;
+            }
+            columns.push((index, cells));
+        }
+
+        for (row, string_note) in self
+            .tuning
+            .iter()
+            .rev()
+            .enumerate()
+            .take(area.height as usize)
+        {
+            let mut spans: Vec<Span> = vec![
+                Span::from(format!("{:<3}", string_note.to_string())).style(self.note_name_style),
+                Span::styled("║", self.string_style),
+            ];
+
+            for (index, cells) in &columns {
+                let (mut string_style, mut note_style) =
+                    (self.string_style, self.note_style(*index, state));
+                if *index == state.cursor {
+                    string_style = string_style.patch(self.cursor_style);
+                    note_style = note_style.patch(self.cursor_style);
+                }
+                spans.extend(cells[row].chars().map(|c| {
+                    let style = if c == '─' { string_style } else { note_style };
+                    Span::styled(c.to_string(), style)
+                }));
+            }
+
+            // Fill the rest of the line with the string
+            let used: usize = spans.iter().map(|span| span.width()).sum();
+            let rest = (area.width as usize).saturating_sub(used);
+            spans.push(Span::styled("─".repeat(rest), self.string_style));
+
+            buf.set_line(area.x, area.y + row as u16, &Line::from(spans), area.width);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{position::FretPosition, tablature::Technique};
+    use rstest::*;
+
+    fn beat(positions: &[(usize, u8)]) -> TabBeat {
+        TabBeat::new(
+            positions
+                .iter()
+                .map(|&(string, fret)| FretPosition::new(string, fret))
+                .collect(),
+        )
+    }
+
+    fn plain(tab: Tab) -> Tab {
+        tab.with_note_name_style(Style::default())
+            .with_string_style(Style::default())
+            .with_played_style(Style::default())
+            .with_upcoming_style(Style::default())
+            .with_missed_style(Style::default())
+            .with_cursor_style(Style::default())
+    }
+
+    fn riff() -> Vec<TabBeat> {
+        vec![
+            beat(&[(0, 0)]),
+            beat(&[(0, 3)]),
+            beat(&[(0, 5)]).with_technique(Technique::HammerOn),
+            beat(&[(1, 0), (2, 2)]),
+            beat(&[(1, 12)]).with_technique(Technique::Vibrato),
+        ]
+    }
+
+    #[rstest]
+    #[case::start(
+        Rect::new(0, 0, 20, 6),
+        0,
+        Buffer::with_lines([
+            "E4 ║────────────────",
+            "B3 ║────────────────",
+            "G3 ║────────────────",
+            "D3 ║───────2────────",
+            "A2 ║───────0─12~────",
+            "E2 ║─0─3h5──────────",
+        ])
+    )]
+    #[case::scrolled(
+        Rect::new(0, 0, 16, 6),
+        4,
+        Buffer::with_lines([
+            "E4 ║────────────",
+            "B3 ║────────────",
+            "G3 ║────────────",
+            "D3 ║───2────────",
+            "A2 ║───0─12~────",
+            "E2 ║h5──────────",
+        ])
+    )]
+    #[case::clipped(
+        Rect::new(0, 0, 10, 2),
+        0,
+        Buffer::with_lines(["E4 ║──────", "B3 ║──────"])
+    )]
+    fn render_tab(#[case] area: Rect, #[case] cursor: usize, #[case] expected: Buffer) {
+        let tab = plain(Tab::default().with_beats(riff()));
+        let mut state = TabState {
+            cursor,
+            missed: Vec::new(),
+        };
+        let mut buf = Buffer::empty(area);
+        tab.render(area, &mut buf, &mut state);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn render_styles() {
+        let tab = plain(Tab::default().with_beats(riff()))
+            .with_played_style(Style::default().fg(Color::Gray))
+            .with_upcoming_style(Style::default().fg(Color::Blue))
+            .with_missed_style(Style::default().fg(Color::Red))
+            .with_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = TabState::new();
+        state.advance(false);
+        state.advance(true);
+
+        let area = Rect::new(0, 0, 20, 6);
+        let mut buf = Buffer::empty(area);
+        tab.render(area, &mut buf, &mut state);
+
+        // Played, missed, current and upcoming notes on the low E string
+        assert_eq!(Some(Color::Gray), buf[(5, 5)].style().fg);
+        assert_eq!(Some(Color::Red), buf[(7, 5)].style().fg);
+        assert_eq!(Some(Color::Blue), buf[(9, 5)].style().fg);
+        assert!(buf[(9, 5)]
+            .style()
+            .add_modifier
+            .contains(Modifier::REVERSED));
+        assert!(buf[(9, 0)]
+            .style()
+            .add_modifier
+            .contains(Modifier::REVERSED));
+        assert_eq!(Some(Color::Blue), buf[(13, 4)].style().fg);
+    }
+
+    #[test]
+    fn advance_and_reset() {
+        let mut state = TabState::new();
+        state.advance(true);
+        state.advance(false);
+        assert_eq!(2, state.cursor);
+        assert_eq!(vec![0], state.missed);
+        state.reset();
+        assert_eq!(0, state.cursor);
+        assert!(state.missed.is_empty());
+    }
+}
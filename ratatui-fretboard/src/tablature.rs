@@ -0,0 +1,425 @@
+//! ASCII tablature.
+
+use std::{fmt, ops::RangeInclusive};
+
+use crate::{
+    note::Note,
+    position::{choose_positions, FretPosition},
+};
+
+/// The default maximum width of a tablature line.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// The default number of quarter notes in a measure.
+pub const DEFAULT_BEATS_PER_MEASURE: u8 = 4;
+
+/// The number of columns a quarter note takes when the timing is known.
+const COLUMNS_PER_QUARTER: f64 = 2.0;
+
+/// Tolerance for rounding errors when adding up durations.
+const EPSILON: f64 = 1e-6;
+
+/// A playing technique marked next to the fret number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Technique {
+    /// Hammer-on from the previous note (`h`).
+    HammerOn,
+    /// Pull-off from the previous note (`p`).
+    PullOff,
+    /// Slide from the previous note (`/`).
+    Slide,
+    /// Bend up (`b`).
+    Bend,
+    /// Vibrato (`~`).
+    Vibrato,
+}
+
+impl Technique {
+    /// Returns the symbol of the technique.
+    pub fn symbol(&self) -> char {
+        match self {
+            Technique::HammerOn => 'h',
+            Technique::PullOff => 'p',
+            Technique::Slide => '/',
+            Technique::Bend => 'b',
+            Technique::Vibrato => '~',
+        }
+    }
+
+    /// Returns `true` if the symbol is written before the fret number.
+    ///
+    /// Techniques that connect to the previous note come before the fret,
+    /// the others come after it.
+    pub fn is_prefix(&self) -> bool {
+        matches!(
+            self,
+            Technique::HammerOn | Technique::PullOff | Technique::Slide
+        )
+    }
+}
+
+/// The notes played at the same time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TabBeat {
+    /// The positions of the notes.
+    pub positions: Vec<FretPosition>,
+    /// The length of the beat in quarter notes, if known.
+    pub duration: Option<f64>,
+    /// The technique the notes are played with.
+    pub technique: Option<Technique>,
+}
+
+impl TabBeat {
+    /// Creates a new beat without timing.
+    pub fn new(positions: Vec<FretPosition>) -> Self {
+        Self {
+            positions,
+            duration: None,
+            technique: None,
+        }
+    }
+
+    /// Sets the length of the beat in quarter notes.
+    pub fn with_duration(mut self, duration: f64) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Sets the technique the notes are played with.
+    pub fn with_technique(mut self, technique: Technique) -> Self {
+        self.technique = Some(technique);
+        self
+    }
+
+    /// Returns the text of each string, from the highest string to the lowest.
+    ///
+    /// The texts have the same width, strings without a note are filled with `fill`.
+    pub fn cells(&self, strings: usize, fill: char) -> Vec<String> {
+        let frets: Vec<Option<String>> = (0..strings)
+            .rev()
+            .map(|string| {
+                self.positions
+                    .iter()
+                    .find(|position| position.string == string)
+                    .map(|position| position.fret.to_string())
+            })
+            .collect();
+        let width = frets.iter().flatten().map(|f| f.len()).max().unwrap_or(1);
+        let (prefix, suffix) = match self.technique {
+            Some(technique) if technique.is_prefix() => (Some(technique.symbol()), None),
+            Some(technique) => (None, Some(technique.symbol())),
+            None => (None, None),
+        };
+
+        frets
+            .into_iter()
+            .map(|fret| {
+                let mut cell = String::new();
+                match &fret {
+                    Some(fret) => {
+                        cell.push(prefix.unwrap_or(fill));
+                        cell.push_str(fret);
+                        cell.push_str(&fill.to_string().repeat(width - fret.len()));
+                        cell.extend(suffix);
+                    }
+                    None => {
+                        let width = 1 + width + suffix.map_or(0, |_| 1);
+                        cell.push_str(&fill.to_string().repeat(width));
+                    }
+                }
+                cell
+            })
+            .collect()
+    }
+}
+
+/// A guitar tablature that can be rendered as ASCII text.
+///
+/// Measure bars are added when the duration of every beat is known.
+#[derive(Clone, Debug)]
+pub struct Tablature {
+    /// The tuning of the strings, from the lowest string to the highest.
+    tuning: Vec<Note>,
+    /// The beats of the tablature.
+    beats: Vec<TabBeat>,
+    /// The number of quarter notes in a measure.
+    beats_per_measure: u8,
+    /// The maximum width of a line.
+    width: usize,
+}
+
+/// A part of a tablature line.
+enum Column {
+    /// A beat, with the text of each string.
+    Beat(Vec<String>),
+    /// A measure bar.
+    Bar,
+}
+
+impl Column {
+    fn width(&self) -> usize {
+        match self {
+            Column::Beat(strings) => strings.first().map_or(0, |s| s.chars().count()),
+            Column::Bar => 2,
+        }
+    }
+
+    fn text(&self, string: usize) -> &str {
+        match self {
+            Column::Beat(strings) => &strings[string],
+            Column::Bar => "-|",
+        }
+    }
+}
+
+impl Tablature {
+    /// Creates an empty tablature for the given tuning.
+    pub fn new(tuning: Vec<Note>) -> Self {
+        Self {
+            tuning,
+            beats: Vec::new(),
+            beats_per_measure: DEFAULT_BEATS_PER_MEASURE,
+            width: DEFAULT_WIDTH,
+        }
+    }
+
+    /// Creates a tablature from notes, picking the fret positions automatically.
+    pub fn from_notes<B: AsRef<[Note]>>(
+        beats: &[B],
+        tuning: Vec<Note>,
+        frets: &RangeInclusive<u8>,
+    ) -> Self {
+        let beats = choose_positions(beats, &tuning, frets)
+            .into_iter()
+            .map(TabBeat::new)
+            .collect();
+        Self::new(tuning).with_beats(beats)
+    }
+
+    pub fn with_beats(mut self, beats: Vec<TabBeat>) -> Self {
+        self.beats = beats;
+        self
+    }
+
+    pub fn with_beats_per_measure(mut self, beats_per_measure: u8) -> Self {
+        self.beats_per_measure = beats_per_measure.max(1);
+        self
+    }
+
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Adds a beat to the end of the tablature.
+    pub fn push(&mut self, beat: TabBeat) {
+        self.beats.push(beat);
+    }
+
+    /// Returns the beats of the tablature.
+    pub fn beats(&self) -> &[TabBeat] {
+        &self.beats
+    }
+
+    /// Returns the tuning of the tablature.
+    pub fn tuning(&self) -> &[Note] {
+        &self.tuning
+    }
+
+    /// Returns the labels of the strings, from the highest string to the lowest.
+    fn labels(&self) -> Vec<String> {
+        let names: Vec<&str> = self.tuning.iter().map(|note| note.name()).collect();
+        let width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+        names
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, name)| {
+                // Tell the high string apart from a lower string with the same name
+                let name = if i == names.len() - 1 && names[..i].contains(name) {
+                    name.to_lowercase()
+                } else {
+                    name.to_string()
+                };
+                format!("{name:<width$}")
+            })
+            .collect()
+    }
+
+    /// Builds the columns of the tablature, with the strings from the highest to the lowest.
+    fn columns(&self) -> Vec<Column> {
+        let timed = !self.beats.is_empty() && self.beats.iter().all(|b| b.duration.is_some());
+        let measure = self.beats_per_measure as f64;
+        let mut position_in_measure = 0.0;
+
+        let mut columns = Vec::new();
+        for beat in &self.beats {
+            let padding = match beat.duration {
+                Some(duration) if timed => {
+                    ((duration * COLUMNS_PER_QUARTER).round() as usize).saturating_sub(1)
+                }
+                _ => 0,
+            };
+            columns.push(Column::Beat(
+                beat.cells(self.tuning.len(), '-')
+                    .into_iter()
+                    .map(|cell| format!("{cell}{}", "-".repeat(padding)))
+                    .collect(),
+            ));
+
+            if let Some(duration) = beat.duration.filter(|_| timed) {
+                position_in_measure += duration;
+                if position_in_measure + EPSILON >= measure {
+                    position_in_measure = (position_in_measure - measure).max(0.0);
+                    columns.push(Column::Bar);
+                }
+            }
+        }
+        columns
+    }
+}
+
+impl fmt::Display for Tablature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels = self.labels();
+        let prefix_width = labels.first().map_or(0, |label| label.len()) + 1;
+
+        // Split the columns into lines that fit into the width
+        let mut lines: Vec<Vec<Column>> = vec![Vec::new()];
+        let mut line_width = prefix_width;
+        for column in self.columns() {
+            let width = column.width();
+            let current = lines.last_mut().expect("there is always a line");
+            if !current.is_empty() && line_width + width + 2 > self.width {
+                lines.push(Vec::new());
+                line_width = prefix_width;
+            }
+            let current = lines.last_mut().expect("there is always a line");
+            if current.is_empty() && matches!(column, Column::Bar) {
+                continue;
+            }
+            line_width += width;
+            current.push(column);
+        }
+
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let closed = matches!(line.last(), Some(Column::Bar));
+            for (string, label) in labels.iter().enumerate() {
+                write!(f, "{label}|")?;
+                for column in line {
+                    write!(f, "{}", column.text(string))?;
+                }
+                writeln!(f, "{}", if closed { "" } else { "-|" })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::STANDARD_TUNING;
+    use Note::*;
+
+    fn beat(positions: &[(usize, u8)]) -> TabBeat {
+        TabBeat::new(
+            positions
+                .iter()
+                .map(|&(string, fret)| FretPosition::new(string, fret))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_render() {
+        let tab = Tablature::new(STANDARD_TUNING.to_vec()).with_beats(vec![
+            beat(&[(2, 0), (3, 0)]),
+            beat(&[(2, 3), (3, 3)]),
+            beat(&[(1, 10)]),
+        ]);
+        assert_eq!(
+            [
+                "e|--------|",
+                "B|--------|",
+                "G|-0-3----|",
+                "D|-0-3----|",
+                "A|-----10-|",
+                "E|--------|",
+                "",
+            ]
+            .join("\n"),
+            tab.to_string()
+        );
+    }
+
+    #[test]
+    fn test_render_measures() {
+        let tab = Tablature::new(STANDARD_TUNING.to_vec())
+            .with_beats_per_measure(2)
+            .with_beats(vec![
+                beat(&[(0, 0)]).with_duration(1.0),
+                beat(&[(0, 3)]).with_duration(0.5),
+                beat(&[(0, 5)]).with_duration(0.5),
+                beat(&[(1, 2)]).with_duration(2.0),
+            ]);
+        assert_eq!(
+            [
+                "e|--------|------|",
+                "B|--------|------|",
+                "G|--------|------|",
+                "D|--------|------|",
+                "A|--------|-2----|",
+                "E|-0--3-5-|------|",
+                "",
+            ]
+            .join("\n"),
+            tab.to_string()
+        );
+    }
+
+    #[test]
+    fn test_render_techniques() {
+        let tab = Tablature::new(STANDARD_TUNING.to_vec()).with_beats(vec![
+            beat(&[(3, 5)]),
+            beat(&[(3, 7)]).with_technique(Technique::HammerOn),
+            beat(&[(3, 7)]).with_technique(Technique::Bend),
+            beat(&[(2, 9), (3, 9)]).with_technique(Technique::Slide),
+        ]);
+        assert_eq!(
+            [
+                "e|----------|",
+                "B|----------|",
+                "G|-5h7-7b/9-|",
+                "D|-------/9-|",
+                "A|----------|",
+                "E|----------|",
+                "",
+            ]
+            .join("\n"),
+            tab.to_string()
+        );
+    }
+
+    #[test]
+    fn test_wrap_lines() {
+        let beats = vec![beat(&[(5, 12)]); 6];
+        let tab = Tablature::new(STANDARD_TUNING.to_vec())
+            .with_beats(beats)
+            .with_width(12);
+        let lines: Vec<_> = tab.to_string().lines().map(String::from).collect();
+        assert_eq!(20, lines.len());
+        assert_eq!("e|-12-12-|", lines[0]);
+        assert_eq!("", lines[6]);
+        assert!(lines.iter().all(|line| line.chars().count() <= 12));
+    }
+
+    #[test]
+    fn test_from_notes() {
+        let tab = Tablature::from_notes(&[[E(2)], [A(2)]], STANDARD_TUNING.to_vec(), &(0..=12));
+        assert_eq!(vec![beat(&[(0, 0)]), beat(&[(1, 0)])], tab.beats().to_vec());
+    }
+}
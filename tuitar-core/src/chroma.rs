@@ -0,0 +1,164 @@
+use ratatui_fretboard::{
+    chord::{Chord, ChordType},
+    note::{Note, A4_FREQUENCY},
+};
+
+use crate::harmonics::bin_width;
+
+/// The lowest frequency taken into account, below it a bin spans several semitones.
+const MIN_FREQ_HZ: f64 = 100.0;
+
+/// The highest frequency taken into account, above it only weak harmonics are left.
+const MAX_FREQ_HZ: f64 = 2000.0;
+
+/// The octave of the roots of detected chords.
+const ROOT_OCTAVE: u8 = 3;
+
+/// The distribution of energy over the twelve pitch classes, starting from C.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Chroma {
+    /// The share of the energy of each pitch class (`0.0..=1.0`, summing up to 1).
+    pub energy: [f64; 12],
+}
+
+impl Chroma {
+    /// Folds the magnitude spectrum into pitch classes.
+    ///
+    /// Returns `None` if the spectrum has no energy in the guitar range.
+    pub fn new(magnitudes: &[f64], sample_rate: f64) -> Option<Self> {
+        let bin_width = bin_width(sample_rate, magnitudes.len())?;
+        let mut energy = [0.0; 12];
+        for (i, magnitude) in magnitudes.iter().enumerate() {
+            let frequency = i as f64 * bin_width;
+            if !(MIN_FREQ_HZ..=MAX_FREQ_HZ).contains(&frequency) {
+                continue;
+            }
+            if let Some(note) = Note::from_frequency(frequency, A4_FREQUENCY) {
+                energy[note.pitch_class() as usize] += magnitude * magnitude;
+            }
+        }
+        Self::normalized(energy)
+    }
+
+    /// Creates a chroma with the same energy for each of the notes.
+    pub fn from_notes(notes: &[Note]) -> Option<Self> {
+        let mut energy = [0.0; 12];
+        for note in notes {
+            energy[note.pitch_class() as usize] += 1.0;
+        }
+        Self::normalized(energy)
+    }
+
+    fn normalized(mut energy: [f64; 12]) -> Option<Self> {
+        let total: f64 = energy.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+        energy.iter_mut().for_each(|e| *e /= total);
+        Some(Self { energy })
+    }
+
+    /// Returns how well the chroma matches the tones of the chord (`0.0..=1.0`).
+    ///
+    /// This is the cosine similarity with a template that has the same energy
+    /// for every chord tone.
+    pub fn similarity(&self, chord: &Chord) -> f64 {
+        let pitch_classes = chord.pitch_classes();
+        let dot: f64 = pitch_classes
+            .iter()
+            .map(|pitch_class| self.energy[*pitch_class as usize])
+            .sum();
+        let norm = self.energy.iter().map(|e| e * e).sum::<f64>().sqrt()
+            * (pitch_classes.len() as f64).sqrt();
+        if norm == 0.0 {
+            return 0.0;
+        }
+        dot / norm
+    }
+
+    /// Returns the candidate that matches best, if it is at least `min_similarity` similar.
+    ///
+    /// On a tie the earlier candidate wins.
+    pub fn recognize(&self, candidates: &[Chord], min_similarity: f64) -> Option<Chord> {
+        let mut best: Option<(f64, Chord)> = None;
+        for chord in candidates {
+            let similarity = self.similarity(chord);
+            if similarity < min_similarity {
+                continue;
+            }
+            if !matches!(best, Some((best, _)) if best >= similarity) {
+                best = Some((similarity, *chord));
+            }
+        }
+        best.map(|(_, chord)| chord)
+    }
+
+    /// Returns the chord of any root and type that matches best.
+    ///
+    /// Triads win over seventh chords that match equally well.
+    pub fn detect(&self, min_similarity: f64) -> Option<Chord> {
+        let candidates: Vec<Chord> = ChordType::ALL
+            .iter()
+            .flat_map(|chord_type| {
+                (0..12).map(|pitch_class| {
+                    Chord::new(
+                        Note::from_semitone_index(ROOT_OCTAVE * 12 + pitch_class),
+                        *chord_type,
+                    )
+                })
+            })
+            .collect();
+        self.recognize(&candidates, min_similarity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f64 = 10_240.0;
+    const BINS: usize = 512;
+
+    #[test]
+    fn folds_spectrum_into_pitch_classes() {
+        let bin_width = bin_width(SAMPLE_RATE, BINS).unwrap();
+        let mut magnitudes = vec![0.0; BINS];
+        // C4, E4 and G4 with an octave of C, plus rumble below the guitar range
+        for (frequency, magnitude) in [
+            (261.6, 1.0),
+            (329.6, 1.0),
+            (392.0, 1.0),
+            (523.3, 1.0),
+            (50.0, 4.0),
+        ] {
+            magnitudes[(frequency / bin_width).round() as usize] = magnitude;
+        }
+        let chroma = Chroma::new(&magnitudes, SAMPLE_RATE).unwrap();
+        assert_eq!(0.5, chroma.energy[0]);
+        assert_eq!(0.25, chroma.energy[4]);
+        assert_eq!(0.25, chroma.energy[7]);
+        assert_eq!(
+            Some("C".to_string()),
+            chroma.detect(0.9).map(|chord| chord.name())
+        );
+
+        assert_eq!(None, Chroma::new(&vec![0.0; BINS], SAMPLE_RATE));
+        assert_eq!(None, Chroma::new(&magnitudes, 0.0));
+    }
+
+    #[test]
+    fn recognizes_chords() {
+        let e_minor = Chord::new(Note::E(3), ChordType::Minor);
+        let g_major = Chord::new(Note::G(3), ChordType::Major);
+        let chroma = Chroma::from_notes(&[Note::E(2), Note::B(2), Note::G(3)]).unwrap();
+        assert_eq!(1.0, (chroma.similarity(&e_minor) * 1e9).round() / 1e9);
+        assert_eq!(Some(e_minor), chroma.recognize(&[g_major, e_minor], 0.8));
+        assert_eq!(None, chroma.recognize(&[g_major], 0.8));
+        assert_eq!(Some(e_minor), chroma.detect(0.8));
+
+        let dominant = Chroma::from_notes(&[Note::G(2), Note::B(2), Note::D(3), Note::F(3)])
+            .unwrap()
+            .detect(0.8);
+        assert_eq!(Some("G7".to_string()), dominant.map(|chord| chord.name()));
+    }
+}
@@ -0,0 +1,755 @@
+use std::{fmt, ops::RangeInclusive, time::Duration};
+
+use ratatui_fretboard::{
+    key::Key,
+    note::{Note, STANDARD_TUNING},
+    position::FretPosition,
+};
+
+/// The number of scores kept in the high score table.
+pub const MAX_HIGH_SCORES: usize = 5;
+
+/// A preset of [`GameOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    /// Natural notes on the two lowest strings, close to each other.
+    #[default]
+    Easy,
+    /// Natural notes on every string.
+    Medium,
+    /// Every note on every string, with a quickly shrinking time limit.
+    Hard,
+    /// The notes of a key on every string.
+    InKey,
+}
+
+impl Difficulty {
+    /// Returns the next preset, wrapping around to the first one.
+    pub fn next(&self) -> Difficulty {
+        match self {
+            Difficulty::Easy => Difficulty::Medium,
+            Difficulty::Medium => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::InKey,
+            Difficulty::InKey => Difficulty::Easy,
+        }
+    }
+
+    /// Returns the options of the preset, using the key for [`Difficulty::InKey`].
+    pub fn options(&self, key: Key) -> GameOptions {
+        let defaults = GameOptions::default();
+        match self {
+            Difficulty::Easy => GameOptions {
+                strings: vec![0, 1],
+                natural_notes: true,
+                max_jump: Some(3),
+                time_limit: Duration::from_secs(8),
+                min_time_limit: Duration::from_secs(4),
+                lives: Some(5),
+                ..defaults
+            },
+            Difficulty::Medium => GameOptions {
+                natural_notes: true,
+                max_jump: Some(5),
+                time_limit: Duration::from_secs(6),
+                ..defaults
+            },
+            Difficulty::Hard => GameOptions {
+                time_step: Duration::from_millis(250),
+                min_time_limit: Duration::from_millis(1500),
+                ..defaults
+            },
+            Difficulty::InKey => GameOptions {
+                key: Some(key),
+                max_jump: Some(4),
+                time_limit: Duration::from_secs(6),
+                ..defaults
+            },
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::InKey => "In key",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Which notes the random note game asks for and how quickly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameOptions {
+    /// The strings the notes are picked on, all of them if empty.
+    pub strings: Vec<usize>,
+    /// Whether only notes without sharps are picked.
+    pub natural_notes: bool,
+    /// The key the notes are picked from, if any.
+    pub key: Option<Key>,
+    /// How many frets away from the previous note the next one can be, if limited.
+    pub max_jump: Option<u8>,
+    /// The time to play the first note of a streak.
+    pub time_limit: Duration,
+    /// How much the time limit shrinks with every note of the streak.
+    pub time_step: Duration,
+    /// The time limit never shrinks below this.
+    pub min_time_limit: Duration,
+    /// The number of notes that can be missed before the game is over, or `None` to play forever.
+    pub lives: Option<u8>,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        Self {
+            strings: Vec::new(),
+            natural_notes: false,
+            key: None,
+            max_jump: None,
+            time_limit: Duration::from_secs(5),
+            time_step: Duration::from_millis(100),
+            min_time_limit: Duration::from_secs(2),
+            lives: Some(3),
+        }
+    }
+}
+
+impl GameOptions {
+    /// Returns `true` if the note can be asked for.
+    pub fn allows(&self, note: Note) -> bool {
+        if self.natural_notes && !note.is_natural() {
+            return false;
+        }
+        match self.key {
+            Some(key) => key
+                .notes()
+                .iter()
+                .any(|key_note| key_note.pitch_class() == note.pitch_class()),
+            None => true,
+        }
+    }
+
+    /// Returns the positions within the frets that can be asked for.
+    pub fn positions(&self, frets: &RangeInclusive<u8>) -> Vec<FretPosition> {
+        (0..STANDARD_TUNING.len())
+            .filter(|string| self.strings.is_empty() || self.strings.contains(string))
+            .flat_map(|string| {
+                frets
+                    .clone()
+                    .map(move |fret| FretPosition::new(string, fret))
+            })
+            .filter(|position| {
+                position
+                    .note(&STANDARD_TUNING)
+                    .is_some_and(|note| self.allows(note))
+            })
+            .collect()
+    }
+}
+
+/// What happened in a [`NoteGame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The target note was played in time.
+    Hit,
+    /// The time ran out before the target note was played.
+    Missed,
+    /// The last life was lost.
+    GameOver(Score),
+}
+
+/// The result of a finished game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    /// The preset the game was played with.
+    pub difficulty: Difficulty,
+    /// The number of notes played in time.
+    pub points: usize,
+    /// The longest run of notes played in time.
+    pub best_streak: usize,
+}
+
+/// A position to play against a time limit, never picked twice in a row.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Target {
+    /// The position to play.
+    position: Option<FretPosition>,
+    /// The position of the last target.
+    previous: Option<FretPosition>,
+    /// When the target was picked.
+    since: Duration,
+}
+
+impl Target {
+    /// Returns the position to play, if one was picked.
+    pub(crate) fn position(&self) -> Option<FretPosition> {
+        self.position
+    }
+
+    /// Returns the note to play, if one was picked.
+    pub(crate) fn note(&self) -> Option<Note> {
+        self.position?.note(&STANDARD_TUNING)
+    }
+
+    /// Returns the position of the last target.
+    pub(crate) fn previous(&self) -> Option<FretPosition> {
+        self.previous
+    }
+
+    /// Removes the last target from the positions, unless it is the only one.
+    pub(crate) fn retain_new(&self, positions: &mut Vec<FretPosition>) {
+        if positions.len() > 1 {
+            positions.retain(|position| Some(*position) != self.previous);
+        }
+    }
+
+    /// Picks the position at the given stream time.
+    pub(crate) fn set(&mut self, position: FretPosition, time: Duration) {
+        self.position = Some(position);
+        self.previous = Some(position);
+        self.since = time;
+    }
+
+    /// Returns the time since the target was picked.
+    pub(crate) fn elapsed(&self, time: Duration) -> Duration {
+        time.saturating_sub(self.since)
+    }
+
+    /// Returns how much of the time limit is left (`0.0..=1.0`).
+    pub(crate) fn remaining(&self, time: Duration, limit: Duration) -> f64 {
+        if self.position.is_none() {
+            return 1.0;
+        }
+        let elapsed = self.elapsed(time).as_secs_f64();
+        (1.0 - elapsed / limit.as_secs_f64()).clamp(0.0, 1.0)
+    }
+
+    /// Takes the position once it was played.
+    pub(crate) fn take(&mut self) -> Option<FretPosition> {
+        self.position.take()
+    }
+
+    /// Takes the position if the time limit ran out at the given stream time.
+    pub(crate) fn expire(&mut self, time: Duration, limit: Duration) -> Option<FretPosition> {
+        self.position?;
+        if self.elapsed(time) <= limit {
+            return None;
+        }
+        self.take()
+    }
+}
+
+/// A game of playing randomly picked notes before the time runs out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NoteGame {
+    /// The preset the options were taken from.
+    difficulty: Difficulty,
+    /// Which notes are asked for and how quickly.
+    options: GameOptions,
+    /// The position of the note to play.
+    target: Target,
+    /// The number of notes played in time.
+    points: usize,
+    /// The number of notes played in time since the last miss.
+    streak: usize,
+    /// The longest streak of the game.
+    best_streak: usize,
+    /// The number of notes that can still be missed.
+    lives: Option<u8>,
+}
+
+impl NoteGame {
+    /// Creates a game with the options of the preset.
+    pub fn new(difficulty: Difficulty, options: GameOptions) -> Self {
+        Self {
+            difficulty,
+            lives: options.lives,
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the preset the game is played with.
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Returns the options of the game.
+    pub fn options(&self) -> &GameOptions {
+        &self.options
+    }
+
+    /// Returns the position of the note to play, if one was picked.
+    pub fn target(&self) -> Option<FretPosition> {
+        self.target.position()
+    }
+
+    /// Returns the note to play, if one was picked.
+    pub fn target_note(&self) -> Option<Note> {
+        self.target.note()
+    }
+
+    /// Returns the number of notes played in time.
+    pub fn points(&self) -> usize {
+        self.points
+    }
+
+    /// Returns the number of notes played in time since the last miss.
+    pub fn streak(&self) -> usize {
+        self.streak
+    }
+
+    /// Returns the number of notes that can still be missed, or `None` if there is no limit.
+    pub fn lives(&self) -> Option<u8> {
+        self.lives
+    }
+
+    /// Returns `true` once every life was lost.
+    pub fn is_over(&self) -> bool {
+        self.lives == Some(0)
+    }
+
+    /// Returns the result of the game so far.
+    pub fn score(&self) -> Score {
+        Score {
+            difficulty: self.difficulty,
+            points: self.points,
+            best_streak: self.best_streak,
+        }
+    }
+
+    /// Returns the time to play the target, which shrinks as the streak grows.
+    pub fn time_limit(&self) -> Duration {
+        let shrink = self.options.time_step.saturating_mul(self.streak as u32);
+        self.options
+            .time_limit
+            .saturating_sub(shrink)
+            .max(self.options.min_time_limit)
+    }
+
+    /// Returns how much of the time limit is left (`0.0..=1.0`).
+    pub fn remaining(&self, time: Duration) -> f64 {
+        self.target.remaining(time, self.time_limit())
+    }
+
+    /// Picks the next target within the frets at the given stream time.
+    ///
+    /// `pick` returns a random index below the given length. Targets stay
+    /// within the max jump from the previous one if possible and never
+    /// repeat it. Returns `None` if no position can be asked for.
+    pub fn next_target(
+        &mut self,
+        frets: &RangeInclusive<u8>,
+        time: Duration,
+        mut pick: impl FnMut(usize) -> usize,
+    ) -> Option<FretPosition> {
+        if self.is_over() {
+            return None;
+        }
+        let mut positions = self.options.positions(frets);
+        self.target.retain_new(&mut positions);
+        if let Some(previous) = self.target.previous() {
+            if let Some(max_jump) = self.options.max_jump {
+                let close: Vec<FretPosition> = positions
+                    .iter()
+                    .copied()
+                    .filter(|position| position.fret.abs_diff(previous.fret) <= max_jump)
+                    .collect();
+                if !close.is_empty() {
+                    positions = close;
+                }
+            }
+        }
+        if positions.is_empty() {
+            return None;
+        }
+        let target = positions[pick(positions.len()).min(positions.len() - 1)];
+        self.target.set(target, time);
+        Some(target)
+    }
+
+    /// Plays a note, scoring a point if it is the target.
+    ///
+    /// The note has to match in pitch and octave.
+    pub fn play(&mut self, note: Note) -> Option<Outcome> {
+        if self.target_note() != Some(note) {
+            return None;
+        }
+        self.target.take();
+        self.points += 1;
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+        Some(Outcome::Hit)
+    }
+
+    /// Checks the time limit at the given stream time, taking a life if the target was missed.
+    pub fn update(&mut self, time: Duration) -> Option<Outcome> {
+        self.target.expire(time, self.time_limit())?;
+        self.streak = 0;
+        if let Some(lives) = self.lives.as_mut() {
+            *lives = lives.saturating_sub(1);
+        }
+        if self.is_over() {
+            return Some(Outcome::GameOver(self.score()));
+        }
+        Some(Outcome::Missed)
+    }
+
+    /// Starts the game over with the same options.
+    pub fn restart(&mut self) {
+        *self = Self::new(self.difficulty, self.options.clone());
+    }
+}
+
+/// Where a [`NoteHunt`] looks for the positions of its note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HuntScope {
+    /// Every position within the fret window.
+    #[default]
+    Window,
+    /// Every octave anywhere on the neck, one position each.
+    Octaves,
+}
+
+impl HuntScope {
+    /// Returns the other scope.
+    pub fn next(&self) -> HuntScope {
+        match self {
+            HuntScope::Window => HuntScope::Octaves,
+            HuntScope::Octaves => HuntScope::Window,
+        }
+    }
+}
+
+impl fmt::Display for HuntScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HuntScope::Window => write!(f, "Window"),
+            HuntScope::Octaves => write!(f, "Octaves"),
+        }
+    }
+}
+
+/// A game of finding every position of a pitch class against the clock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteHunt {
+    /// The note to find, only its pitch class matters.
+    note: Note,
+    /// Where the positions are looked for.
+    scope: HuntScope,
+    /// The positions to find.
+    targets: Vec<FretPosition>,
+    /// The positions found so far.
+    found: Vec<FretPosition>,
+    /// When the hunt started.
+    started: Duration,
+    /// When the last position was found.
+    finished: Option<Duration>,
+}
+
+impl NoteHunt {
+    /// Starts hunting the pitch class of the note within the frets at the given stream time.
+    ///
+    /// In the [`HuntScope::Octaves`] scope only the lowest position of each
+    /// octave is a target.
+    pub fn new(note: Note, scope: HuntScope, frets: RangeInclusive<u8>, time: Duration) -> Self {
+        let mut targets: Vec<FretPosition> = (0..STANDARD_TUNING.len())
+            .flat_map(|string| {
+                frets
+                    .clone()
+                    .map(move |fret| FretPosition::new(string, fret))
+            })
+            .filter(|position| {
+                position
+                    .note(&STANDARD_TUNING)
+                    .is_some_and(|played| played.pitch_class() == note.pitch_class())
+            })
+            .collect();
+        if scope == HuntScope::Octaves {
+            let mut octaves = Vec::new();
+            targets.retain(|position| {
+                let played = position.note(&STANDARD_TUNING);
+                if octaves.contains(&played) {
+                    return false;
+                }
+                octaves.push(played);
+                true
+            });
+        }
+        Self {
+            note,
+            scope,
+            targets,
+            found: Vec::new(),
+            started: time,
+            finished: None,
+        }
+    }
+
+    /// Returns the note to find.
+    pub fn note(&self) -> Note {
+        self.note
+    }
+
+    /// Returns where the positions are looked for.
+    pub fn scope(&self) -> HuntScope {
+        self.scope
+    }
+
+    /// Returns the positions to find.
+    pub fn targets(&self) -> &[FretPosition] {
+        &self.targets
+    }
+
+    /// Returns the positions found so far.
+    pub fn found(&self) -> &[FretPosition] {
+        &self.found
+    }
+
+    /// Returns `true` once every position was found.
+    pub fn is_finished(&self) -> bool {
+        self.finished.is_some()
+    }
+
+    /// Returns the time spent hunting, which stops once every position was found.
+    pub fn elapsed(&self, time: Duration) -> Duration {
+        self.finished.unwrap_or(time).saturating_sub(self.started)
+    }
+
+    /// Plays a note at the given stream time, returning the newly found position.
+    ///
+    /// Unisons on different strings can not be told apart by pitch, so each
+    /// play finds the first position of the note that was not found yet.
+    pub fn play(&mut self, note: Note, time: Duration) -> Option<FretPosition> {
+        if self.is_finished() {
+            return None;
+        }
+        let position = self.targets.iter().copied().find(|target| {
+            !self.found.contains(target) && target.note(&STANDARD_TUNING) == Some(note)
+        })?;
+        self.found.push(position);
+        if self.found.len() == self.targets.len() {
+            self.finished = Some(time);
+        }
+        Some(position)
+    }
+}
+
+/// The best scores, from the highest to the lowest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HighScores {
+    scores: Vec<Score>,
+}
+
+impl HighScores {
+    /// Returns the scores, from the highest to the lowest.
+    pub fn scores(&self) -> &[Score] {
+        &self.scores
+    }
+
+    /// Adds a score, returning its rank (starting at `0`) if it made it into the table.
+    ///
+    /// Games without points are not recorded and later scores rank below equal ones.
+    pub fn add(&mut self, score: Score) -> Option<usize> {
+        if score.points == 0 {
+            return None;
+        }
+        let rank = self
+            .scores
+            .iter()
+            .position(|high| high.points < score.points)
+            .unwrap_or(self.scores.len());
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.scores.insert(rank, score);
+        self.scores.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first(_: usize) -> usize {
+        0
+    }
+
+    #[test]
+    fn filters_positions() {
+        let options = Difficulty::Easy.options(Key::major(Note::C(4)));
+        let positions = options.positions(&(0..=3));
+        // E F G on the low E string, A B C on the A string
+        assert_eq!(6, positions.len());
+        assert!(positions.iter().all(|position| position.string < 2));
+
+        let options = Difficulty::InKey.options(Key::major(Note::G(4)));
+        let notes: Vec<Note> = options
+            .positions(&(0..=2))
+            .iter()
+            .filter_map(|position| position.note(&STANDARD_TUNING))
+            .collect();
+        assert!(notes.contains(&Note::FSharp(2)));
+        assert!(!notes.contains(&Note::F(2)));
+    }
+
+    #[test]
+    fn picks_targets_within_jump() {
+        let options = GameOptions {
+            strings: vec![0],
+            max_jump: Some(2),
+            ..GameOptions::default()
+        };
+        let mut game = NoteGame::new(Difficulty::Hard, options);
+        assert_eq!(
+            Some(FretPosition::new(0, 0)),
+            game.next_target(&(0..=12), Duration::ZERO, first)
+        );
+        assert_eq!(Some(Note::E(2)), game.target_note());
+        // The previous position is never repeated
+        assert_eq!(
+            Some(FretPosition::new(0, 1)),
+            game.next_target(&(0..=12), Duration::ZERO, first)
+        );
+        assert_eq!(
+            Some(FretPosition::new(0, 3)),
+            game.next_target(&(0..=12), Duration::ZERO, |len| len - 1)
+        );
+        // Far away frets are used when nothing is close enough
+        assert_eq!(
+            Some(FretPosition::new(0, 10)),
+            game.next_target(&(10..=12), Duration::ZERO, first)
+        );
+    }
+
+    #[test]
+    fn shrinks_time_limit_and_takes_lives() {
+        let options = GameOptions {
+            time_limit: Duration::from_secs(3),
+            time_step: Duration::from_secs(1),
+            min_time_limit: Duration::from_millis(1500),
+            lives: Some(2),
+            ..GameOptions::default()
+        };
+        let mut game = NoteGame::new(Difficulty::Medium, options);
+        game.next_target(&(0..=0), Duration::ZERO, first);
+        assert_eq!(None, game.play(Note::A(2)));
+        assert_eq!(Some(Outcome::Hit), game.play(Note::E(2)));
+        assert_eq!(Duration::from_secs(2), game.time_limit());
+        game.next_target(&(0..=0), Duration::ZERO, first);
+        game.play(Note::A(2));
+        assert_eq!(Duration::from_millis(1500), game.time_limit());
+
+        game.next_target(&(0..=0), Duration::from_secs(10), first);
+        assert_eq!(0.5, game.remaining(Duration::from_millis(10_750)));
+        assert_eq!(None, game.update(Duration::from_millis(11_500)));
+        assert_eq!(Some(Outcome::Missed), game.update(Duration::from_secs(12)));
+        assert_eq!(0, game.streak());
+        assert_eq!(Some(1), game.lives());
+
+        game.next_target(&(0..=0), Duration::from_secs(20), first);
+        let score = Score {
+            difficulty: Difficulty::Medium,
+            points: 2,
+            best_streak: 2,
+        };
+        assert_eq!(
+            Some(Outcome::GameOver(score)),
+            game.update(Duration::from_secs(30))
+        );
+        assert!(game.is_over());
+        assert_eq!(None, game.next_target(&(0..=0), Duration::ZERO, first));
+
+        game.restart();
+        assert_eq!(Some(2), game.lives());
+        assert_eq!(0, game.points());
+    }
+
+    #[test]
+    fn keeps_high_scores() {
+        let score = |points| Score {
+            difficulty: Difficulty::Easy,
+            points,
+            best_streak: points,
+        };
+        let mut high_scores = HighScores::default();
+        assert_eq!(None, high_scores.add(score(0)));
+        for points in [3, 5, 1, 4, 2] {
+            high_scores.add(score(points));
+        }
+        assert_eq!(Some(2), high_scores.add(score(4)));
+        assert_eq!(None, high_scores.add(score(1)));
+        let points: Vec<usize> = high_scores
+            .scores()
+            .iter()
+            .map(|score| score.points)
+            .collect();
+        assert_eq!(vec![5, 4, 4, 3, 2], points);
+    }
+
+    #[test]
+    fn hunts_positions_in_window() {
+        let mut hunt = NoteHunt::new(Note::G(4), HuntScope::Window, 0..=5, Duration::ZERO);
+        // The open G string and its unison on the D string are both targets
+        assert_eq!(
+            vec![
+                FretPosition::new(0, 3),
+                FretPosition::new(2, 5),
+                FretPosition::new(3, 0),
+                FretPosition::new(5, 3),
+            ],
+            hunt.targets()
+        );
+        assert_eq!(None, hunt.play(Note::A(2), Duration::from_secs(1)));
+        assert_eq!(
+            Some(FretPosition::new(2, 5)),
+            hunt.play(Note::G(3), Duration::from_secs(2))
+        );
+        assert_eq!(
+            Some(FretPosition::new(3, 0)),
+            hunt.play(Note::G(3), Duration::from_secs(3))
+        );
+        assert_eq!(None, hunt.play(Note::G(3), Duration::from_secs(3)));
+        assert_eq!(
+            Some(FretPosition::new(0, 3)),
+            hunt.play(Note::G(2), Duration::from_secs(4))
+        );
+        assert!(!hunt.is_finished());
+        assert_eq!(
+            Some(FretPosition::new(5, 3)),
+            hunt.play(Note::G(4), Duration::from_secs(5))
+        );
+        assert!(hunt.is_finished());
+        // The timer stops once everything was found
+        assert_eq!(Duration::from_secs(5), hunt.elapsed(Duration::from_secs(9)));
+    }
+
+    #[test]
+    fn hunts_octaves_on_neck() {
+        let mut hunt = NoteHunt::new(Note::E(2), HuntScope::Octaves, 0..=12, Duration::ZERO);
+        let octaves: Vec<Option<Note>> = hunt
+            .targets()
+            .iter()
+            .map(|position| position.note(&STANDARD_TUNING))
+            .collect();
+        assert_eq!(
+            vec![
+                Some(Note::E(2)),
+                Some(Note::E(3)),
+                Some(Note::E(4)),
+                Some(Note::E(5))
+            ],
+            octaves
+        );
+        for (i, note) in [Note::E(5), Note::E(4), Note::E(3)].into_iter().enumerate() {
+            assert!(hunt.play(note, Duration::from_secs(i as u64)).is_some());
+        }
+        assert_eq!(Duration::from_secs(7), hunt.elapsed(Duration::from_secs(7)));
+        hunt.play(Note::E(2), Duration::from_secs(8));
+        assert!(hunt.is_finished());
+        assert_eq!(4, hunt.found().len());
+    }
+}
@@ -0,0 +1,244 @@
+/// The default number of harmonics to look for.
+pub const DEFAULT_HARMONIC_COUNT: usize = 8;
+
+/// A single partial found in the spectrum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Harmonic {
+    /// The harmonic number (1 is the fundamental).
+    pub number: usize,
+    /// The measured frequency of the partial in Hz.
+    pub frequency: f64,
+    /// The linear magnitude of the partial.
+    pub magnitude: f64,
+    /// The level of the partial relative to the fundamental in dB.
+    pub level_db: f64,
+    /// The deviation from the ideal `number * fundamental` frequency in cents.
+    pub deviation_cents: f64,
+}
+
+/// The harmonic content of a detected note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarmonicAnalysis {
+    /// The fundamental frequency the analysis is based on.
+    pub fundamental: f64,
+    /// The partials that were found, starting with the fundamental.
+    pub harmonics: Vec<Harmonic>,
+    /// The estimated inharmonicity coefficient (`B`).
+    ///
+    /// Partials of a stiff string follow `f(n) = n * f0 * sqrt(1 + B * n²)`,
+    /// so `0.0` means perfectly harmonic.
+    pub inharmonicity: f64,
+    /// The spectral centroid in Hz, i.e. the "brightness" of the sound.
+    pub spectral_centroid: f64,
+}
+
+impl HarmonicAnalysis {
+    /// Analyzes the given magnitude spectrum.
+    ///
+    /// `magnitudes` is the positive half of the spectrum as returned by
+    /// [`Transformer::fft_data`](crate::transform::Transformer::fft_data).
+    ///
+    /// Returns `None` if the fundamental is not inside the spectrum.
+    pub fn new(
+        magnitudes: &[f64],
+        sample_rate: f64,
+        fundamental: f64,
+        count: usize,
+    ) -> Option<Self> {
+        let bin_width = bin_width(sample_rate, magnitudes.len())?;
+        if fundamental <= 0.0 {
+            return None;
+        }
+
+        let mut harmonics: Vec<Harmonic> = Vec::with_capacity(count);
+        for number in 1..=count {
+            let expected = fundamental * number as f64;
+            // Search half way to the neighbouring partials, partials of real strings run sharp
+            let low = ((expected - fundamental / 2.0) / bin_width).ceil().max(1.0) as usize;
+            let high = ((expected + fundamental / 2.0) / bin_width).floor() as usize;
+            if high >= magnitudes.len() || low > high {
+                break;
+            }
+
+            let (peak, magnitude) = (low..=high)
+                .map(|bin| (bin, magnitudes[bin]))
+                .fold((low, 0.0), |max, bin| if bin.1 > max.1 { bin } else { max });
+            if magnitude <= 0.0 {
+                continue;
+            }
+
+            let frequency = interpolate_peak(magnitudes, peak) * bin_width;
+            let reference = harmonics.first().map_or(magnitude, |h| h.magnitude);
+            harmonics.push(Harmonic {
+                number,
+                frequency,
+                magnitude,
+                level_db: 20.0 * (magnitude / reference).log10(),
+                deviation_cents: 1200.0 * (frequency / expected).log2(),
+            });
+        }
+
+        if harmonics.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            fundamental,
+            inharmonicity: inharmonicity(&harmonics),
+            spectral_centroid: spectral_centroid(magnitudes, sample_rate),
+            harmonics,
+        })
+    }
+}
+
+/// Returns the width of a single frequency bin in Hz.
+///
+/// `bins` is the length of the positive half of the spectrum.
+pub fn bin_width(sample_rate: f64, bins: usize) -> Option<f64> {
+    if bins == 0 || sample_rate <= 0.0 {
+        return None;
+    }
+    Some(sample_rate / 2.0 / bins as f64)
+}
+
+/// Returns the spectral centroid of the magnitude spectrum in Hz.
+pub fn spectral_centroid(magnitudes: &[f64], sample_rate: f64) -> f64 {
+    let Some(bin_width) = bin_width(sample_rate, magnitudes.len()) else {
+        return 0.0;
+    };
+
+    // Skip the DC bin
+    let (weighted, total) = magnitudes
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold((0.0, 0.0), |(weighted, total), (i, &m)| {
+            (weighted + i as f64 * bin_width * m, total + m)
+        });
+
+    if total > 0.0 {
+        weighted / total
+    } else {
+        0.0
+    }
+}
+
+/// Refines the peak position using parabolic interpolation on log-magnitudes.
+fn interpolate_peak(magnitudes: &[f64], peak: usize) -> f64 {
+    if peak == 0 || peak + 1 >= magnitudes.len() {
+        return peak as f64;
+    }
+
+    let y0 = magnitudes[peak - 1].max(1e-12).ln();
+    let y1 = magnitudes[peak].max(1e-12).ln();
+    let y2 = magnitudes[peak + 1].max(1e-12).ln();
+    let denominator = y0 - 2.0 * y1 + y2;
+    if denominator.abs() < f64::EPSILON {
+        return peak as f64;
+    }
+
+    peak as f64 + 0.5 * (y0 - y2) / denominator
+}
+
+/// Estimates the inharmonicity coefficient from the measured partials.
+///
+/// Uses the first partial as reference and averages the per-partial estimates
+/// of `B` over the upper partials.
+fn inharmonicity(harmonics: &[Harmonic]) -> f64 {
+    let Some(first) = harmonics.iter().find(|h| h.number == 1) else {
+        return 0.0;
+    };
+
+    let estimates: Vec<f64> = harmonics
+        .iter()
+        .filter(|h| h.number > 1)
+        .map(|h| {
+            let n = h.number as f64;
+            let ratio = (h.frequency / (n * first.frequency)).powi(2);
+            // Solve ratio = (1 + B * n²) / (1 + B) for B
+            (ratio - 1.0) / (n * n - ratio)
+        })
+        .collect();
+
+    if estimates.is_empty() {
+        return 0.0;
+    }
+
+    (estimates.iter().sum::<f64>() / estimates.len() as f64).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f64 = 10_240.0;
+    const BINS: usize = 512;
+
+    /// Builds a spectrum with a partial at each of the given frequencies.
+    fn spectrum(partials: &[(f64, f64)]) -> Vec<f64> {
+        let bin_width = bin_width(SAMPLE_RATE, BINS).unwrap();
+        let mut magnitudes = vec![0.0; BINS];
+        for &(frequency, magnitude) in partials {
+            magnitudes[(frequency / bin_width).round() as usize] = magnitude;
+        }
+        magnitudes
+    }
+
+    #[test]
+    fn finds_harmonic_levels() {
+        let magnitudes = spectrum(&[(100.0, 1.0), (200.0, 0.5), (300.0, 0.25), (400.0, 0.1)]);
+        let analysis = HarmonicAnalysis::new(&magnitudes, SAMPLE_RATE, 100.0, 4).unwrap();
+
+        let numbers: Vec<_> = analysis.harmonics.iter().map(|h| h.number).collect();
+        assert_eq!(vec![1, 2, 3, 4], numbers);
+
+        let levels: Vec<_> = analysis
+            .harmonics
+            .iter()
+            .map(|h| (h.level_db * 10.0).round() / 10.0)
+            .collect();
+        assert_eq!(vec![0.0, -6.0, -12.0, -20.0], levels);
+
+        assert!(analysis
+            .harmonics
+            .iter()
+            .all(|h| h.deviation_cents.abs() < 1e-9));
+        assert!(analysis.inharmonicity.abs() < 1e-9);
+    }
+
+    #[test]
+    fn measures_sharp_partials() {
+        // Third partial is 10 Hz sharp
+        let magnitudes = spectrum(&[(100.0, 1.0), (200.0, 0.5), (310.0, 0.25)]);
+        let analysis = HarmonicAnalysis::new(&magnitudes, SAMPLE_RATE, 100.0, 3).unwrap();
+
+        let third = analysis.harmonics[2];
+        assert_eq!(310.0, third.frequency);
+        assert!((third.deviation_cents - 1200.0 * (310.0_f64 / 300.0).log2()).abs() < 1e-9);
+        assert!(analysis.inharmonicity > 0.0);
+    }
+
+    #[test]
+    fn stops_at_nyquist() {
+        let magnitudes = spectrum(&[(2000.0, 1.0), (4000.0, 0.5)]);
+        let analysis = HarmonicAnalysis::new(&magnitudes, SAMPLE_RATE, 2000.0, 8).unwrap();
+        assert_eq!(2, analysis.harmonics.len());
+    }
+
+    #[test]
+    fn computes_spectral_centroid() {
+        let magnitudes = spectrum(&[(100.0, 1.0), (300.0, 1.0)]);
+        assert_eq!(200.0, spectral_centroid(&magnitudes, SAMPLE_RATE));
+        assert_eq!(0.0, spectral_centroid(&[], SAMPLE_RATE));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(None, HarmonicAnalysis::new(&[], SAMPLE_RATE, 100.0, 4));
+        let magnitudes = spectrum(&[(100.0, 1.0)]);
+        assert_eq!(
+            None,
+            HarmonicAnalysis::new(&magnitudes, SAMPLE_RATE, 0.0, 4)
+        );
+    }
+}
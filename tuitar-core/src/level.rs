@@ -0,0 +1,229 @@
+use std::ops::RangeInclusive;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+/// The default noise gate threshold in dBFS.
+pub const DEFAULT_NOISE_GATE: f64 = -50.0;
+
+/// The lowest level shown on the meter in dBFS.
+const METER_FLOOR: f64 = -60.0;
+
+/// The lowest level that can be reported in dBFS.
+const LEVEL_FLOOR: f64 = -120.0;
+
+/// Samples closer than this ratio of the input range to its edges count as clipping.
+const CLIP_MARGIN: f64 = 0.01;
+
+/// The input level of a buffer of samples.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Level {
+    /// The RMS amplitude relative to full scale (`0.0..=1.0`).
+    pub rms: f64,
+    /// The peak amplitude relative to full scale (`0.0..=1.0`).
+    pub peak: f64,
+    /// Whether any of the samples hit the edges of the input range.
+    pub clipping: bool,
+}
+
+impl Level {
+    /// Measures the level of the given samples.
+    ///
+    /// `range` is the range of values the input can produce, e.g. `0..=4095`
+    /// for a 12-bit ADC. The DC offset of the signal is removed before measuring.
+    pub fn new(samples: &[i16], range: &RangeInclusive<i16>) -> Self {
+        let (min, max) = (*range.start() as f64, *range.end() as f64);
+        let full_scale = (max - min) / 2.0;
+        if samples.is_empty() || full_scale <= 0.0 {
+            return Self::default();
+        }
+
+        let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / samples.len() as f64;
+        let (sum_squares, peak) = samples.iter().fold((0.0, 0.0_f64), |(sum, peak), &s| {
+            let ac = s as f64 - mean;
+            (sum + ac * ac, peak.max(ac.abs()))
+        });
+
+        let margin = (max - min) * CLIP_MARGIN;
+        let clipping = samples
+            .iter()
+            .any(|&s| (s as f64) <= min + margin || (s as f64) >= max - margin);
+
+        Self {
+            rms: (sum_squares / samples.len() as f64).sqrt() / full_scale,
+            peak: peak / full_scale,
+            clipping,
+        }
+    }
+
+    /// Returns the RMS level in dBFS.
+    pub fn rms_dbfs(&self) -> f64 {
+        to_dbfs(self.rms)
+    }
+
+    /// Returns the peak level in dBFS.
+    pub fn peak_dbfs(&self) -> f64 {
+        to_dbfs(self.peak)
+    }
+}
+
+/// Converts a ratio of full scale to dBFS.
+fn to_dbfs(ratio: f64) -> f64 {
+    (20.0 * ratio.log10()).max(LEVEL_FLOOR)
+}
+
+/// A horizontal dBFS level meter with a clip indicator.
+#[derive(Debug, Default)]
+pub struct LevelMeter {
+    /// The level to display.
+    level: Level,
+    /// The noise gate threshold to mark on the meter.
+    noise_gate: Option<f64>,
+}
+
+impl LevelMeter {
+    pub fn new(level: Level) -> Self {
+        Self {
+            level,
+            noise_gate: None,
+        }
+    }
+
+    pub fn with_noise_gate(mut self, noise_gate: Option<f64>) -> Self {
+        self.noise_gate = noise_gate;
+        self
+    }
+
+    /// Returns the style of the meter at the given level.
+    fn style(&self, db: f64) -> Style {
+        if self
+            .noise_gate
+            .is_some_and(|gate| self.level.rms_dbfs() < gate)
+        {
+            Style::default().fg(Color::DarkGray)
+        } else if db > -6.0 {
+            Style::default().fg(Color::Red)
+        } else if db > -18.0 {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::Green)
+        }
+    }
+}
+
+impl Widget for &LevelMeter {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let label = if self.level.clipping {
+            Span::styled("CLIP", Style::default().fg(Color::White).bg(Color::Red))
+        } else {
+            Span::raw(format!("{:.0}dB", self.level.rms_dbfs().max(METER_FLOOR)))
+        };
+        // Leave out the label on narrow screens
+        let label_width = if area.width >= 12 { 6 } else { 0 };
+        let bar_width = area.width.saturating_sub(label_width) as usize;
+        if bar_width == 0 {
+            return;
+        }
+
+        let position = |db: f64| {
+            let ratio = ((db - METER_FLOOR) / -METER_FLOOR).clamp(0.0, 1.0);
+            (ratio * bar_width as f64).round() as usize
+        };
+        let filled = position(self.level.rms_dbfs());
+        let peak = position(self.level.peak_dbfs()).min(bar_width);
+        let gate = self.noise_gate.map(position);
+
+        let mut spans: Vec<Span> = (0..bar_width)
+            .map(|i| {
+                let db = METER_FLOOR + (i as f64 + 0.5) / bar_width as f64 * -METER_FLOOR;
+                if i < filled {
+                    Span::styled("█", self.style(db))
+                } else if i + 1 == peak {
+                    Span::styled("▏", self.style(db))
+                } else if gate == Some(i) {
+                    Span::styled("│", Color::DarkGray)
+                } else {
+                    Span::styled("─", Color::DarkGray)
+                }
+            })
+            .collect();
+
+        if self.level.clipping && label_width == 0 {
+            // No room for the label, color the whole bar instead
+            spans
+                .iter_mut()
+                .for_each(|span| span.style.bg = Some(Color::Red));
+        } else if label_width > 0 {
+            spans.push(Span::raw(" "));
+            spans.push(label);
+        }
+
+        buf.set_line(area.x, area.y, &Line::from(spans), area.width);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_full_scale_square() {
+        let samples = [i16::MAX, -i16::MAX, i16::MAX, -i16::MAX];
+        let level = Level::new(&samples, &(i16::MIN..=i16::MAX));
+        assert!(level.rms_dbfs().abs() < 0.01);
+        assert!(level.peak_dbfs().abs() < 0.01);
+        assert!(level.clipping);
+    }
+
+    #[test]
+    fn removes_dc_offset() {
+        // 12-bit ADC idling in the middle of its range
+        let samples = [2048, 2148, 2048, 1948];
+        let level = Level::new(&samples, &(0..=4095));
+        assert!((level.peak - 100.0 / 2047.5).abs() < 1e-9);
+        assert!((level.rms - (5000.0_f64).sqrt() / 2047.5).abs() < 1e-9);
+        assert!(!level.clipping);
+    }
+
+    #[test]
+    fn reports_silence() {
+        let level = Level::new(&[100; 16], &(i16::MIN..=i16::MAX));
+        assert_eq!(0.0, level.rms);
+        assert_eq!(LEVEL_FLOOR, level.rms_dbfs());
+        assert_eq!(Level::default(), Level::new(&[], &(0..=4095)));
+    }
+
+    #[test]
+    fn render_meter() {
+        let level = Level {
+            rms: 0.1,
+            peak: 0.5,
+            clipping: false,
+        };
+        let meter = LevelMeter::new(level).with_noise_gate(Some(-18.0));
+        let area = Rect::new(0, 0, 17, 1);
+        let mut buf = Buffer::empty(area);
+        meter.render(area, &mut buf);
+
+        let line: String = buf.content().iter().map(|cell| cell.symbol()).collect();
+        assert_eq!("███████─│▏─ -20dB", line);
+    }
+
+    #[test]
+    fn render_clipping_on_narrow_meter() {
+        let level = Level {
+            rms: 1.0,
+            peak: 1.0,
+            clipping: true,
+        };
+        let area = Rect::new(0, 0, 4, 1);
+        let mut buf = Buffer::empty(area);
+        LevelMeter::new(level).render(area, &mut buf);
+        assert!(buf.content().iter().all(|cell| cell.bg == Color::Red));
+    }
+}
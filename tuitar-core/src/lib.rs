@@ -14,3 +14,36 @@ pub mod fps;
 
 /// Song handling.
 pub mod songs;
+
+/// Harmonic analysis.
+pub mod harmonics;
+
+/// Oscilloscope handling.
+pub mod scope;
+
+/// Input level measurement.
+pub mod level;
+
+/// Note stabilization.
+pub mod stabilizer;
+
+/// Onset detection.
+pub mod onset;
+
+/// Note segmentation.
+pub mod segment;
+
+/// Note transcription.
+pub mod transcription;
+
+/// Practice sequences.
+pub mod practice;
+
+/// Chord recognition.
+pub mod chroma;
+
+/// Note games.
+pub mod game;
+
+/// Fretboard memorization training.
+pub mod trainer;
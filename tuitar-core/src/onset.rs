@@ -0,0 +1,199 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::level::Level;
+
+/// The default factor the spectral flux has to exceed its recent average by.
+pub const DEFAULT_SENSITIVITY: f64 = 2.0;
+
+/// The default minimum time between two onsets.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_millis(80);
+
+/// The minimum spectral flux of an onset, no matter how quiet the recent frames were.
+const MIN_FLUX: f64 = 0.3;
+
+/// The minimum level increase between two frames that counts as an onset (dB).
+const ENERGY_JUMP_DB: f64 = 6.0;
+
+/// The number of frames used for the adaptive flux threshold.
+const FLUX_HISTORY: usize = 8;
+
+/// Detects the start of new notes from consecutive frames.
+///
+/// Combines the spectral flux, which catches new notes even at the same level,
+/// with the energy increase, which catches repeated picks of the same note.
+#[derive(Debug, Clone)]
+pub struct OnsetDetector {
+    /// How many times the spectral flux has to exceed its recent average.
+    pub sensitivity: f64,
+    /// The minimum time between two onsets.
+    pub min_interval: Duration,
+    /// The magnitude spectrum of the previous frame.
+    previous: Vec<f64>,
+    /// The spectral flux of the recent frames.
+    flux_history: VecDeque<f64>,
+    /// The RMS level of the previous frame in dBFS.
+    previous_db: Option<f64>,
+    /// The time of the last onset.
+    last_onset: Option<Duration>,
+}
+
+impl Default for OnsetDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_SENSITIVITY, DEFAULT_MIN_INTERVAL)
+    }
+}
+
+impl OnsetDetector {
+    pub fn new(sensitivity: f64, min_interval: Duration) -> Self {
+        Self {
+            sensitivity,
+            min_interval,
+            previous: Vec::new(),
+            flux_history: VecDeque::with_capacity(FLUX_HISTORY),
+            previous_db: None,
+            last_onset: None,
+        }
+    }
+
+    /// Processes the next frame and returns `true` if a note started in it.
+    ///
+    /// `time` is the stream time of the frame.
+    pub fn process(&mut self, magnitudes: &[f64], level: &Level, time: Duration) -> bool {
+        let flux = if self.previous.len() == magnitudes.len() {
+            spectral_flux(&self.previous, magnitudes)
+        } else {
+            0.0
+        };
+        let average = if self.flux_history.is_empty() {
+            0.0
+        } else {
+            self.flux_history.iter().sum::<f64>() / self.flux_history.len() as f64
+        };
+        let db = level.rms_dbfs();
+
+        let flux_onset = flux >= MIN_FLUX && flux > average * self.sensitivity;
+        let energy_onset = self
+            .previous_db
+            .is_some_and(|previous| db - previous >= ENERGY_JUMP_DB);
+        let ready = match self.last_onset {
+            Some(last) => time.saturating_sub(last) >= self.min_interval,
+            None => true,
+        };
+
+        self.previous = magnitudes.to_vec();
+        self.previous_db = Some(db);
+        if self.flux_history.len() == FLUX_HISTORY {
+            self.flux_history.pop_front();
+        }
+        self.flux_history.push_back(flux);
+
+        let onset = ready && (flux_onset || energy_onset);
+        if onset {
+            self.last_onset = Some(time);
+        }
+        onset
+    }
+
+    /// Forgets the previous frames.
+    pub fn clear(&mut self) {
+        self.previous.clear();
+        self.flux_history.clear();
+        self.previous_db = None;
+        self.last_onset = None;
+    }
+}
+
+/// Returns the half-wave rectified spectral flux between two magnitude spectra.
+///
+/// The flux is relative to the energy of the current frame, so `0.0` means
+/// nothing new appeared and `1.0` means everything in the frame is new.
+pub fn spectral_flux(previous: &[f64], current: &[f64]) -> f64 {
+    let (increase, total) = previous.iter().zip(current).fold(
+        (0.0, 0.0),
+        |(increase, total), (&previous, &current)| {
+            (increase + (current - previous).max(0.0), total + current)
+        },
+    );
+
+    if total > 0.0 {
+        increase / total
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A level with the given RMS level in dBFS.
+    fn level(db: f64) -> Level {
+        Level {
+            rms: 10.0_f64.powf(db / 20.0),
+            peak: 10.0_f64.powf(db / 20.0),
+            clipping: false,
+        }
+    }
+
+    /// A spectrum with a single partial in the given bin.
+    fn spectrum(bin: usize, magnitude: f64) -> Vec<f64> {
+        let mut magnitudes = vec![0.0; 16];
+        magnitudes[bin] = magnitude;
+        magnitudes
+    }
+
+    /// Returns the stream time of the given frame, with 20 ms per frame.
+    fn frame(index: u64) -> Duration {
+        Duration::from_millis(index * 20)
+    }
+
+    #[test]
+    fn computes_spectral_flux() {
+        assert_eq!(0.0, spectral_flux(&[1.0, 2.0], &[1.0, 2.0]));
+        assert_eq!(0.0, spectral_flux(&[1.0, 2.0], &[0.5, 1.0]));
+        assert_eq!(0.5, spectral_flux(&[1.0, 1.0], &[2.0, 2.0]));
+        assert_eq!(1.0, spectral_flux(&[1.0, 0.0], &[0.0, 1.0]));
+        assert_eq!(0.0, spectral_flux(&[], &[]));
+    }
+
+    #[test]
+    fn detects_new_note() {
+        let mut detector = OnsetDetector::default();
+        for i in 0..5 {
+            assert!(!detector.process(&spectrum(4, 1.0), &level(-20.0), frame(i)));
+        }
+        assert!(detector.process(&spectrum(6, 1.0), &level(-20.0), frame(5)));
+        assert!(!detector.process(&spectrum(6, 1.0), &level(-20.0), frame(6)));
+    }
+
+    #[test]
+    fn detects_repeated_pick() {
+        let mut detector = OnsetDetector::default();
+        detector.process(&spectrum(4, 1.0), &level(-20.0), frame(0));
+        for i in 1..5 {
+            assert!(!detector.process(&spectrum(4, 0.9), &level(-21.0), frame(i)));
+        }
+        // Same note, picked again
+        assert!(detector.process(&spectrum(4, 3.0), &level(-12.0), frame(5)));
+    }
+
+    #[test]
+    fn ignores_decay() {
+        let mut detector = OnsetDetector::default();
+        detector.process(&spectrum(4, 1.0), &level(-20.0), frame(0));
+        for i in 1..10 {
+            let magnitude = 1.0 - i as f64 * 0.05;
+            assert!(!detector.process(&spectrum(4, magnitude), &level(-20.0 - i as f64), frame(i)));
+        }
+    }
+
+    #[test]
+    fn respects_min_interval() {
+        let mut detector = OnsetDetector::default();
+        assert!(!detector.process(&spectrum(4, 1.0), &level(-40.0), frame(0)));
+        assert!(detector.process(&spectrum(4, 1.0), &level(-20.0), frame(1)));
+        // 20 ms later, too soon for another onset
+        assert!(!detector.process(&spectrum(8, 1.0), &level(-10.0), frame(2)));
+    }
+}
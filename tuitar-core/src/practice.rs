@@ -0,0 +1,675 @@
+use std::{fmt, ops::RangeInclusive, time::Duration};
+
+use ratatui_fretboard::{
+    chord::{parse_progression, Chord},
+    error::Error,
+    fingering::FingeringSolver,
+    key::Key,
+    note::{Note, STANDARD_TUNING},
+    position::FretPosition,
+    scale::Scale,
+};
+
+/// The order a sequence is played in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    /// From the lowest note to the highest.
+    #[default]
+    Ascending,
+    /// From the highest note to the lowest.
+    Descending,
+}
+
+impl Direction {
+    /// Returns the opposite direction.
+    pub fn reversed(&self) -> Direction {
+        match self {
+            Direction::Ascending => Direction::Descending,
+            Direction::Descending => Direction::Ascending,
+        }
+    }
+
+    /// Returns an arrow pointing in the direction.
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            Direction::Ascending => "↑",
+            Direction::Descending => "↓",
+        }
+    }
+}
+
+/// The order the notes of a scale are played in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaleRun {
+    /// From the lowest note to the highest.
+    #[default]
+    Ascending,
+    /// From the highest note to the lowest.
+    Descending,
+    /// Pairs of notes a third apart, moving up one note at a time.
+    Thirds,
+    /// Groups of four consecutive notes, moving up one note at a time.
+    FourNoteGroups,
+}
+
+impl ScaleRun {
+    /// Returns the next order, wrapping around to the first one.
+    pub fn next(&self) -> ScaleRun {
+        match self {
+            ScaleRun::Ascending => ScaleRun::Descending,
+            ScaleRun::Descending => ScaleRun::Thirds,
+            ScaleRun::Thirds => ScaleRun::FourNoteGroups,
+            ScaleRun::FourNoteGroups => ScaleRun::Ascending,
+        }
+    }
+
+    /// Arranges the notes, given from the lowest to the highest, in this order.
+    pub fn arrange(&self, notes: &[Note]) -> Vec<Note> {
+        match self {
+            ScaleRun::Ascending => notes.to_vec(),
+            ScaleRun::Descending => notes.iter().rev().copied().collect(),
+            ScaleRun::Thirds => notes
+                .windows(3)
+                .flat_map(|window| [window[0], window[2]])
+                .collect(),
+            ScaleRun::FourNoteGroups => notes.windows(4).flatten().copied().collect(),
+        }
+    }
+}
+
+impl fmt::Display for ScaleRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ScaleRun::Ascending => "Ascending",
+            ScaleRun::Descending => "Descending",
+            ScaleRun::Thirds => "3rds",
+            ScaleRun::FourNoteGroups => "Groups of 4",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The result of playing a whole [`Sequence`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    /// The time between the first and the last note.
+    pub duration: Duration,
+    /// The number of notes in the sequence.
+    pub notes: usize,
+    /// The number of wrong notes played on the way.
+    pub mistakes: usize,
+}
+
+impl Summary {
+    /// Returns the ratio of correct notes to all played notes (`0.0..=1.0`).
+    pub fn accuracy(&self) -> f64 {
+        let played = self.notes + self.mistakes;
+        if played == 0 {
+            return 0.0;
+        }
+        self.notes as f64 / played as f64
+    }
+
+    /// Returns the average tempo in notes per minute.
+    pub fn tempo(&self) -> f64 {
+        let seconds = self.duration.as_secs_f64();
+        if self.notes < 2 || seconds == 0.0 {
+            return 0.0;
+        }
+        (self.notes - 1) as f64 * 60.0 / seconds
+    }
+}
+
+/// The outcome of playing a note in a [`Sequence`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// The expected note was played, the sequence moved on.
+    Correct,
+    /// Another note was played, the sequence did not move.
+    Wrong,
+    /// The last note was played.
+    Finished(Summary),
+}
+
+/// A sequence of notes that has to be played in order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sequence {
+    /// The notes to play.
+    notes: Vec<Note>,
+    /// The positions to play the notes at, if they were chosen.
+    positions: Vec<FretPosition>,
+    /// The index of the next note to play.
+    cursor: usize,
+    /// When the first note was played.
+    started: Option<Duration>,
+    /// The number of wrong notes played since the first one.
+    mistakes: usize,
+    /// The result, once the sequence is finished.
+    summary: Option<Summary>,
+}
+
+impl Sequence {
+    /// Creates a sequence of the given notes.
+    pub fn new(notes: Vec<Note>) -> Self {
+        Self {
+            notes,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a sequence of the chord tones within the frets, in the given direction.
+    pub fn arpeggio(chord: &Chord, frets: &RangeInclusive<u8>, direction: Direction) -> Self {
+        let mut notes = chord.fretboard_notes(frets);
+        if direction == Direction::Descending {
+            notes.reverse();
+        }
+        Self::new(notes)
+    }
+
+    /// Creates a sequence of the scale tones within the frets, in the given order.
+    ///
+    /// Each note is assigned a position in the frets, chosen to keep hand movement small.
+    pub fn scale_run(scale: &Scale, root: Note, frets: &RangeInclusive<u8>, run: ScaleRun) -> Self {
+        let mut notes = scale.fretboard_notes(root, frets);
+        notes.sort_by_key(Note::semitone_index);
+        notes.dedup();
+        let notes = run.arrange(&notes);
+        let beats: Vec<[Note; 1]> = notes.iter().map(|note| [*note]).collect();
+        let positions = FingeringSolver::new(frets.clone())
+            .solve(&beats, &STANDARD_TUNING)
+            .into_iter()
+            .filter_map(|positions| positions.first().copied())
+            .collect();
+        Self {
+            notes,
+            positions,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the notes of the sequence.
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    /// Returns the positions the notes are played at, or an empty slice if they were not chosen.
+    pub fn positions(&self) -> &[FretPosition] {
+        &self.positions
+    }
+
+    /// Returns the index of the next note to play.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns the next note to play, or `None` once the sequence is finished.
+    pub fn next_note(&self) -> Option<Note> {
+        self.notes.get(self.cursor).copied()
+    }
+
+    /// Returns the position of the next note to play, if it was chosen.
+    pub fn next_position(&self) -> Option<FretPosition> {
+        self.positions.get(self.cursor).copied()
+    }
+
+    /// Returns the number of wrong notes played since the first one.
+    pub fn mistakes(&self) -> usize {
+        self.mistakes
+    }
+
+    /// Returns `true` if every note was played.
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.notes.len()
+    }
+
+    /// Returns the result of the sequence, once it is finished.
+    pub fn summary(&self) -> Option<Summary> {
+        self.summary
+    }
+
+    /// Plays a note at the given stream time.
+    ///
+    /// The timer starts with the first expected note, wrong notes before it are not counted.
+    pub fn play(&mut self, note: Note, time: Duration) -> Step {
+        if self.is_finished() {
+            return Step::Wrong;
+        }
+        if self.next_note() != Some(note) {
+            if self.started.is_some() {
+                self.mistakes += 1;
+            }
+            return Step::Wrong;
+        }
+        let started = *self.started.get_or_insert(time);
+        self.cursor += 1;
+        if !self.is_finished() {
+            return Step::Correct;
+        }
+        let summary = Summary {
+            duration: time.saturating_sub(started),
+            notes: self.notes.len(),
+            mistakes: self.mistakes,
+        };
+        self.summary = Some(summary);
+        Step::Finished(summary)
+    }
+
+    /// Starts the sequence over.
+    pub fn restart(&mut self) {
+        self.cursor = 0;
+        self.started = None;
+        self.mistakes = 0;
+        self.summary = None;
+    }
+}
+
+/// A chord progression to practice changes with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progression {
+    /// Degrees of the key the progression is played in, `1` being the tonic.
+    Degrees(&'static [u8]),
+    /// A fixed list of chords, played in any key.
+    Chords(Vec<Chord>),
+}
+
+impl Progression {
+    /// Returns the built-in progressions to choose from.
+    pub fn all() -> Vec<Progression> {
+        vec![
+            Progression::Degrees(&[1, 5, 6, 4]),
+            Progression::Degrees(&[1, 4, 5]),
+            Progression::Degrees(&[2, 5, 1]),
+            Progression::Degrees(&[1, 6, 4, 5]),
+            Progression::from_chords("Em C G D").expect("the chord names are valid"),
+        ]
+    }
+
+    /// Makes a progression from chord names, e.g. `G D Em C`, failing on an unknown
+    /// chord or when there are no chords at all.
+    pub fn from_chords(names: &str) -> Result<Self, Error> {
+        let chords = parse_progression(names)?;
+        if chords.is_empty() {
+            return Err(Error::EmptyProgression);
+        }
+        Ok(Progression::Chords(chords))
+    }
+
+    /// Returns the chords of the progression, building degrees on the key.
+    pub fn chords(&self, key: &Key) -> Vec<Chord> {
+        match self {
+            Progression::Degrees(degrees) => degrees
+                .iter()
+                .filter_map(|degree| key.chord(*degree))
+                .collect(),
+            Progression::Chords(chords) => chords.clone(),
+        }
+    }
+}
+
+/// When a [`ChordChanges`] drill moves on to the next chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Advance {
+    /// As soon as the current chord is recognized.
+    #[default]
+    OnChord,
+    /// Every time the given duration passes, like a metronome.
+    Timer(Duration),
+}
+
+impl fmt::Display for Advance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Advance::OnChord => write!(f, "On chord"),
+            Advance::Timer(duration) => write!(f, "{:.0}s", duration.as_secs_f64()),
+        }
+    }
+}
+
+/// A drill that cycles through a chord progression, counting clean changes.
+///
+/// A change is clean when the next chord is recognized before the drill moves
+/// on. The drill starts once the first chord is recognized.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChordChanges {
+    /// The chords to play in a loop.
+    chords: Vec<Chord>,
+    /// When to move on to the next chord.
+    advance: Advance,
+    /// The index of the current chord.
+    cursor: usize,
+    /// When the first chord was recognized.
+    started: Option<Duration>,
+    /// The number of timer periods since the start, when advancing on a timer.
+    period: u32,
+    /// Whether the current chord was recognized in its period.
+    recognized: bool,
+    /// The number of clean changes since the start.
+    clean_changes: usize,
+    /// The time of the last update.
+    time: Duration,
+}
+
+impl ChordChanges {
+    /// Creates a drill of the given chords.
+    pub fn new(chords: Vec<Chord>, advance: Advance) -> Self {
+        Self {
+            chords,
+            advance,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the chords of the drill.
+    pub fn chords(&self) -> &[Chord] {
+        &self.chords
+    }
+
+    /// Returns when the drill moves on to the next chord.
+    pub fn advance(&self) -> Advance {
+        self.advance
+    }
+
+    /// Returns the chord to play now.
+    pub fn current_chord(&self) -> Option<Chord> {
+        self.chords.get(self.cursor).copied()
+    }
+
+    /// Returns the chord to play after the current one.
+    pub fn next_chord(&self) -> Option<Chord> {
+        if self.chords.is_empty() {
+            return None;
+        }
+        self.chords
+            .get((self.cursor + 1) % self.chords.len())
+            .copied()
+    }
+
+    /// Returns `true` once the first chord was recognized.
+    pub fn is_started(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Returns the number of clean changes since the start.
+    pub fn clean_changes(&self) -> usize {
+        self.clean_changes
+    }
+
+    /// Returns the number of clean changes per minute since the start.
+    pub fn changes_per_minute(&self) -> f64 {
+        let Some(started) = self.started else {
+            return 0.0;
+        };
+        let seconds = self.time.saturating_sub(started).as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.clean_changes as f64 * 60.0 / seconds
+    }
+
+    /// Returns how much of the current timer period has passed (`0.0..=1.0`),
+    /// or `None` if the drill doesn't advance on a timer or didn't start yet.
+    pub fn progress(&self) -> Option<f64> {
+        let (Advance::Timer(period), Some(started)) = (self.advance, self.started) else {
+            return None;
+        };
+        if period.is_zero() {
+            return None;
+        }
+        let elapsed = self.time.saturating_sub(started).as_secs_f64();
+        Some((elapsed / period.as_secs_f64()).fract())
+    }
+
+    /// Updates the drill with the chord recognized at the given stream time.
+    ///
+    /// Returns `true` if the drill moved on to the next chord.
+    pub fn update(&mut self, recognized: Option<Chord>, time: Duration) -> bool {
+        self.time = time;
+        if self.chords.is_empty() {
+            return false;
+        }
+        let mut moved = false;
+        if let (Advance::Timer(period), Some(started)) = (self.advance, self.started) {
+            let elapsed = time.saturating_sub(started);
+            let period = if period.is_zero() {
+                0
+            } else {
+                (elapsed.as_nanos() / period.as_nanos()) as u32
+            };
+            if period > self.period {
+                let skipped = (period - self.period) as usize;
+                self.cursor = (self.cursor + skipped) % self.chords.len();
+                self.period = period;
+                self.recognized = false;
+                moved = true;
+            }
+        }
+
+        let is_current = match (recognized, self.current_chord()) {
+            (Some(recognized), Some(current)) => recognized.matches(&current),
+            _ => false,
+        };
+        if !is_current || self.recognized {
+            return moved;
+        }
+        if self.started.is_some() {
+            self.clean_changes += 1;
+        } else {
+            self.started = Some(time);
+        }
+        if self.advance == Advance::OnChord {
+            self.cursor = (self.cursor + 1) % self.chords.len();
+            return true;
+        }
+        self.recognized = true;
+        moved
+    }
+
+    /// Starts the drill over.
+    pub fn restart(&mut self) {
+        self.cursor = 0;
+        self.started = None;
+        self.period = 0;
+        self.recognized = false;
+        self.clean_changes = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui_fretboard::chord::ChordType;
+
+    #[test]
+    fn plays_notes_in_order() {
+        let mut sequence = Sequence::new(vec![Note::A(2), Note::C(3), Note::E(3)]);
+        assert_eq!(Some(Note::A(2)), sequence.next_note());
+        assert_eq!(Step::Wrong, sequence.play(Note::C(3), Duration::ZERO));
+        assert_eq!(
+            Step::Correct,
+            sequence.play(Note::A(2), Duration::from_secs(1))
+        );
+        assert_eq!(
+            Step::Correct,
+            sequence.play(Note::C(3), Duration::from_secs(2))
+        );
+        assert_eq!(
+            Step::Wrong,
+            sequence.play(Note::A(2), Duration::from_secs(3))
+        );
+        let summary = Summary {
+            duration: Duration::from_secs(3),
+            notes: 3,
+            mistakes: 1,
+        };
+        assert_eq!(
+            Step::Finished(summary),
+            sequence.play(Note::E(3), Duration::from_secs(4))
+        );
+        assert!(sequence.is_finished());
+        assert_eq!(None, sequence.next_note());
+        assert_eq!(Some(summary), sequence.summary());
+        assert_eq!(0.75, summary.accuracy());
+        assert_eq!(40.0, summary.tempo());
+
+        sequence.restart();
+        assert_eq!(0, sequence.cursor());
+        assert_eq!(0, sequence.mistakes());
+        assert_eq!(None, sequence.summary());
+    }
+
+    #[test]
+    fn builds_arpeggios() {
+        let chord = Chord::new(Note::E(2), ChordType::Minor);
+        let ascending = Sequence::arpeggio(&chord, &(0..=2), Direction::Ascending);
+        assert_eq!(
+            &[
+                Note::E(2),
+                Note::B(2),
+                Note::E(3),
+                Note::G(3),
+                Note::B(3),
+                Note::E(4)
+            ],
+            ascending.notes()
+        );
+        let descending = Sequence::arpeggio(&chord, &(0..=2), Direction::Descending);
+        assert_eq!(Some(Note::E(4)), descending.next_note());
+        assert_eq!(Direction::Ascending, Direction::Descending.reversed());
+    }
+
+    #[test]
+    fn arranges_scale_runs() {
+        let notes = [Note::C(3), Note::D(3), Note::E(3), Note::F(3), Note::G(3)];
+        assert_eq!(
+            vec![
+                Note::C(3),
+                Note::E(3),
+                Note::D(3),
+                Note::F(3),
+                Note::E(3),
+                Note::G(3)
+            ],
+            ScaleRun::Thirds.arrange(&notes)
+        );
+        assert_eq!(
+            vec![
+                Note::C(3),
+                Note::D(3),
+                Note::E(3),
+                Note::F(3),
+                Note::D(3),
+                Note::E(3),
+                Note::F(3),
+                Note::G(3),
+            ],
+            ScaleRun::FourNoteGroups.arrange(&notes)
+        );
+        assert_eq!(Note::G(3), ScaleRun::Descending.arrange(&notes)[0]);
+        assert_eq!(ScaleRun::Ascending, ScaleRun::FourNoteGroups.next());
+    }
+
+    #[test]
+    fn builds_scale_runs_with_positions() {
+        let sequence = Sequence::scale_run(
+            &Scale::MINOR_PENTATONIC,
+            Note::A(2),
+            &(5..=8),
+            ScaleRun::Ascending,
+        );
+        assert_eq!(12, sequence.notes().len());
+        assert_eq!(sequence.notes().len(), sequence.positions().len());
+        assert_eq!(Some(FretPosition::new(0, 5)), sequence.next_position());
+        for (note, position) in sequence.notes().iter().zip(sequence.positions()) {
+            assert_eq!(Some(*note), position.note(&STANDARD_TUNING));
+            assert!((5..=8).contains(&position.fret));
+        }
+    }
+
+    #[test]
+    fn ignores_notes_before_start_and_after_finish() {
+        let mut sequence = Sequence::new(vec![Note::A(2)]);
+        sequence.play(Note::B(2), Duration::ZERO);
+        assert_eq!(0, sequence.mistakes());
+        assert!(matches!(
+            sequence.play(Note::A(2), Duration::ZERO),
+            Step::Finished(_)
+        ));
+        assert_eq!(Step::Wrong, sequence.play(Note::A(2), Duration::ZERO));
+        assert_eq!(
+            Some(1.0),
+            sequence.summary().map(|summary| summary.accuracy())
+        );
+    }
+
+    #[test]
+    fn builds_progressions() {
+        let names = |progression: &Progression| -> Vec<String> {
+            progression
+                .chords(&Key::major(Note::G(4)))
+                .iter()
+                .map(Chord::name)
+                .collect()
+        };
+        let progressions = Progression::all();
+        assert_eq!(vec!["G", "D", "Em", "C"], names(&progressions[0]));
+        assert_eq!(vec!["Em", "C", "G", "D"], names(&progressions[4]));
+        for progression in &progressions {
+            assert!(!progression.chords(&Key::major(Note::C(4))).is_empty());
+        }
+        let custom = Progression::from_chords("A, E | F#m D").unwrap();
+        assert_eq!(vec!["A", "E", "F#m", "D"], names(&custom));
+        assert_eq!(
+            Err(Error::UnknownChord("Gsus9".to_string())),
+            Progression::from_chords("G Gsus9")
+        );
+        assert_eq!(
+            Err(Error::EmptyProgression),
+            Progression::from_chords(" | ")
+        );
+    }
+
+    #[test]
+    fn changes_chords_when_recognized() {
+        let chords = parse_progression("G C").unwrap();
+        let (g, c) = (chords[0], chords[1]);
+        let mut changes = ChordChanges::new(chords, Advance::OnChord);
+        assert!(!changes.update(Some(c), Duration::ZERO));
+        assert!(!changes.is_started());
+        assert!(changes.update(Some(g), Duration::from_secs(1)));
+        assert_eq!(Some(c), changes.current_chord());
+        assert_eq!(Some(g), changes.next_chord());
+        // The last chord is still ringing
+        assert!(!changes.update(Some(g), Duration::from_secs(2)));
+        assert!(changes.update(Some(c), Duration::from_secs(3)));
+        assert!(changes.update(Some(g), Duration::from_secs(4)));
+        assert_eq!(2, changes.clean_changes());
+        assert_eq!(40.0, changes.changes_per_minute());
+        assert_eq!(None, changes.progress());
+
+        changes.restart();
+        assert_eq!(Some(g), changes.current_chord());
+        assert_eq!(0.0, changes.changes_per_minute());
+    }
+
+    #[test]
+    fn changes_chords_on_timer() {
+        let chords = parse_progression("G C D").unwrap();
+        let (g, c, d) = (chords[0], chords[1], chords[2]);
+        let mut changes = ChordChanges::new(chords, Advance::Timer(Duration::from_secs(2)));
+        assert!(!changes.update(Some(g), Duration::from_secs(1)));
+        assert!(changes.is_started());
+        assert_eq!(Some(0.0), changes.progress());
+        // Holding the chord doesn't count again
+        assert!(!changes.update(Some(g), Duration::from_secs(2)));
+        assert_eq!(Some(0.5), changes.progress());
+        assert!(changes.update(None, Duration::from_secs(3)));
+        assert_eq!(Some(c), changes.current_chord());
+        assert!(!changes.update(Some(c), Duration::from_secs(4)));
+        // D is missed, the timer moves on anyway
+        assert!(changes.update(Some(c), Duration::from_secs(5)));
+        assert_eq!(Some(d), changes.current_chord());
+        assert!(changes.update(Some(g), Duration::from_secs(7)));
+        assert_eq!(2, changes.clean_changes());
+        assert_eq!(20.0, changes.changes_per_minute());
+    }
+}
@@ -0,0 +1,365 @@
+use std::fmt;
+
+/// The default number of periods of the fundamental to show.
+pub const DEFAULT_PERIODS: usize = 2;
+
+/// The maximum number of periods of the fundamental to show.
+pub const MAX_PERIODS: usize = 8;
+
+/// The minimum vertical span when auto-scaling.
+///
+/// Keeps the noise floor from filling up the whole screen.
+const MIN_AUTO_SPAN: f64 = 64.0;
+
+/// The trigger hysteresis relative to the peak-to-peak amplitude.
+const HYSTERESIS_RATIO: f64 = 0.05;
+
+/// The edge of the signal that starts a sweep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Trigger {
+    /// Free running, the sweep starts at the beginning of the buffer.
+    #[default]
+    Off,
+    /// Start the sweep when the signal rises through the trigger level.
+    Rising,
+    /// Start the sweep when the signal falls through the trigger level.
+    Falling,
+}
+
+impl Trigger {
+    /// Returns the next trigger mode.
+    pub fn next(&self) -> Self {
+        match self {
+            Trigger::Off => Trigger::Rising,
+            Trigger::Rising => Trigger::Falling,
+            Trigger::Falling => Trigger::Off,
+        }
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Trigger::Off => "Free",
+            Trigger::Rising => "Rise",
+            Trigger::Falling => "Fall",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Oscilloscope state for the waveform view.
+#[derive(Debug, Clone)]
+pub struct Oscilloscope {
+    /// The trigger mode.
+    pub trigger: Trigger,
+    /// The trigger level.
+    ///
+    /// `None` triggers on the zero crossing, i.e. the mean of the signal.
+    pub trigger_level: Option<f64>,
+    /// The number of periods of the fundamental to show (timebase).
+    pub periods: usize,
+    /// Whether the vertical bounds follow the signal.
+    pub auto_scale: bool,
+    /// Whether the current trace is frozen.
+    hold: bool,
+    /// The samples of the current sweep.
+    trace: Vec<i16>,
+    /// The sample rate of the current sweep.
+    sample_rate: f64,
+    /// The level used for the current sweep.
+    level: f64,
+    /// Whether the current sweep was started by the trigger.
+    triggered: bool,
+}
+
+impl Default for Oscilloscope {
+    fn default() -> Self {
+        Self {
+            trigger: Trigger::default(),
+            trigger_level: None,
+            periods: DEFAULT_PERIODS,
+            auto_scale: false,
+            hold: false,
+            trace: Vec::new(),
+            sample_rate: 0.0,
+            level: 0.0,
+            triggered: false,
+        }
+    }
+}
+
+impl Oscilloscope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_trigger(mut self, trigger: Trigger) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    pub fn with_periods(mut self, periods: usize) -> Self {
+        self.set_periods(periods);
+        self
+    }
+
+    pub fn with_auto_scale(mut self, auto_scale: bool) -> Self {
+        self.auto_scale = auto_scale;
+        self
+    }
+
+    /// Switches to the next trigger mode.
+    pub fn next_trigger(&mut self) {
+        self.trigger = self.trigger.next();
+    }
+
+    /// Sets the number of periods to show, clamped to `1..=MAX_PERIODS`.
+    pub fn set_periods(&mut self, periods: usize) {
+        self.periods = periods.clamp(1, MAX_PERIODS);
+    }
+
+    /// Shows one more period of the fundamental.
+    pub fn increase_periods(&mut self) {
+        self.set_periods(self.periods + 1);
+    }
+
+    /// Shows one less period of the fundamental.
+    pub fn decrease_periods(&mut self) {
+        self.set_periods(self.periods.saturating_sub(1));
+    }
+
+    /// Moves the trigger level by the given amount.
+    ///
+    /// Starts from the level of the current sweep when triggering on zero crossings.
+    pub fn adjust_trigger_level(&mut self, delta: f64) {
+        self.trigger_level = Some(self.trigger_level.unwrap_or(self.level) + delta);
+    }
+
+    /// Goes back to triggering on zero crossings.
+    pub fn reset_trigger_level(&mut self) {
+        self.trigger_level = None;
+    }
+
+    /// Freezes or unfreezes the current trace.
+    pub fn toggle_hold(&mut self) {
+        self.hold = !self.hold;
+    }
+
+    /// Returns `true` if the current trace is frozen.
+    pub fn is_held(&self) -> bool {
+        self.hold
+    }
+
+    /// Returns `true` if the current sweep was started by the trigger.
+    pub fn is_triggered(&self) -> bool {
+        self.triggered
+    }
+
+    /// Returns the samples of the current sweep.
+    pub fn trace(&self) -> &[i16] {
+        &self.trace
+    }
+
+    /// Returns the sample rate of the current sweep.
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Returns the trigger level used for the current sweep.
+    pub fn level(&self) -> f64 {
+        self.level
+    }
+
+    /// Starts a new sweep from the given samples, unless the trace is frozen.
+    ///
+    /// When a fundamental frequency is known, the sweep is `periods` long.
+    pub fn update(&mut self, samples: &[i16], sample_rate: f64, fundamental: Option<f64>) {
+        if self.hold || samples.is_empty() {
+            return;
+        }
+        self.sample_rate = sample_rate;
+
+        let (min, max, mean) = stats(samples);
+        self.level = self.trigger_level.unwrap_or(mean);
+
+        if self.trigger == Trigger::Off {
+            self.triggered = false;
+            self.trace = samples.to_vec();
+            return;
+        }
+
+        // Leave room for the trigger to be found when there is no fundamental
+        let window = match fundamental {
+            Some(frequency) if frequency > 0.0 => {
+                (self.periods as f64 * sample_rate / frequency).round() as usize
+            }
+            _ => samples.len() / 2,
+        }
+        .clamp(2.min(samples.len()), samples.len());
+
+        let hysteresis = (max - min) * HYSTERESIS_RATIO;
+        let start = find_trigger(
+            &samples[..=samples.len() - window],
+            self.trigger,
+            self.level,
+            hysteresis,
+        );
+        self.triggered = start.is_some();
+
+        let start = start.unwrap_or_default();
+        self.trace = samples[start..start + window].to_vec();
+    }
+
+    /// Returns the vertical bounds for the current trace.
+    ///
+    /// Falls back to the given bounds if auto-scaling is disabled.
+    pub fn bounds(&self, default: (f64, f64)) -> (f64, f64) {
+        if !self.auto_scale || self.trace.is_empty() {
+            return default;
+        }
+
+        let (min, max, _) = stats(&self.trace);
+        let center = (min + max) / 2.0;
+        // Add a 10% margin so that the peaks are not drawn on the border
+        let half_span = ((max - min) * 1.1).max(MIN_AUTO_SPAN) / 2.0;
+        (center - half_span, center + half_span)
+    }
+}
+
+/// Returns the index of the first trigger event in the given samples.
+///
+/// The signal must move `hysteresis` away from the level on the other side
+/// before the trigger is armed, which keeps noise from re-triggering.
+pub fn find_trigger(
+    samples: &[i16],
+    trigger: Trigger,
+    level: f64,
+    hysteresis: f64,
+) -> Option<usize> {
+    let mut armed = false;
+    for (i, &sample) in samples.iter().enumerate() {
+        let sample = sample as f64;
+        match trigger {
+            Trigger::Off => return None,
+            Trigger::Rising => {
+                if sample < level - hysteresis {
+                    armed = true;
+                } else if armed && sample >= level {
+                    return Some(i);
+                }
+            }
+            Trigger::Falling => {
+                if sample > level + hysteresis {
+                    armed = true;
+                } else if armed && sample <= level {
+                    return Some(i);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Returns the minimum, maximum and mean of the samples.
+fn stats(samples: &[i16]) -> (f64, f64, f64) {
+    let (min, max, sum) =
+        samples
+            .iter()
+            .fold((f64::MAX, f64::MIN, 0.0), |(min, max, sum), &sample| {
+                let sample = sample as f64;
+                (min.min(sample), max.max(sample), sum + sample)
+            });
+    (min, max, sum / samples.len().max(1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A square wave with the given period, starting low.
+    fn square(period: usize, len: usize) -> Vec<i16> {
+        (0..len)
+            .map(|i| if i % period < period / 2 { -1000 } else { 1000 })
+            .collect()
+    }
+
+    #[test]
+    fn finds_edges() {
+        let samples = [0, -100, -50, 50, 100, 50, -50, -100];
+        assert_eq!(Some(3), find_trigger(&samples, Trigger::Rising, 0.0, 10.0));
+        assert_eq!(Some(6), find_trigger(&samples, Trigger::Falling, 0.0, 10.0));
+        assert_eq!(Some(4), find_trigger(&samples, Trigger::Rising, 60.0, 10.0));
+        assert_eq!(None, find_trigger(&samples, Trigger::Off, 0.0, 10.0));
+    }
+
+    #[test]
+    fn ignores_noise_within_hysteresis() {
+        let samples = [10, -5, 5, -5, 5, -200, 200];
+        assert_eq!(Some(6), find_trigger(&samples, Trigger::Rising, 0.0, 50.0));
+    }
+
+    #[test]
+    fn aligns_sweeps_to_trigger() {
+        let mut scope = Oscilloscope::new()
+            .with_trigger(Trigger::Rising)
+            .with_periods(2);
+
+        // Same signal, shifted by a few samples between buffers
+        let samples = square(10, 100);
+        scope.update(&samples[3..], 1000.0, Some(100.0));
+        let first = scope.trace().to_vec();
+        scope.update(&samples[7..], 1000.0, Some(100.0));
+
+        assert!(scope.is_triggered());
+        assert_eq!(20, first.len());
+        assert_eq!(first, scope.trace());
+        assert_eq!(1000, scope.trace()[0]);
+    }
+
+    #[test]
+    fn free_runs_without_trigger() {
+        let mut scope = Oscilloscope::new();
+        let samples = square(10, 100);
+        scope.update(&samples, 1000.0, Some(100.0));
+        assert!(!scope.is_triggered());
+        assert_eq!(samples, scope.trace());
+    }
+
+    #[test]
+    fn holds_trace() {
+        let mut scope = Oscilloscope::new();
+        scope.update(&[1, 2, 3], 1000.0, None);
+        scope.toggle_hold();
+        scope.update(&[4, 5, 6], 1000.0, None);
+        assert_eq!([1, 2, 3], scope.trace());
+        scope.toggle_hold();
+        scope.update(&[4, 5, 6], 1000.0, None);
+        assert_eq!([4, 5, 6], scope.trace());
+    }
+
+    #[test]
+    fn scales_to_signal() {
+        let mut scope = Oscilloscope::new();
+        scope.update(&[-100, 100], 1000.0, None);
+        assert_eq!((-1.0, 1.0), scope.bounds((-1.0, 1.0)));
+
+        scope.auto_scale = true;
+        let (min, max) = scope.bounds((-1.0, 1.0));
+        assert_eq!((-110.0, 110.0), (min.round(), max.round()));
+
+        scope.update(&[1000, 1001], 1000.0, None);
+        assert_eq!((968.5, 1032.5), scope.bounds((-1.0, 1.0)));
+    }
+
+    #[test]
+    fn clamps_periods() {
+        let mut scope = Oscilloscope::new().with_periods(0);
+        assert_eq!(1, scope.periods);
+        scope.decrease_periods();
+        assert_eq!(1, scope.periods);
+        scope.set_periods(100);
+        scope.increase_periods();
+        assert_eq!(MAX_PERIODS, scope.periods);
+    }
+}
@@ -0,0 +1,268 @@
+use std::time::Duration;
+
+use ratatui_fretboard::note::Note;
+
+use crate::level::Level;
+
+/// The default time to wait after an onset for the pitch to settle.
+pub const DEFAULT_SETTLE: Duration = Duration::from_millis(50);
+
+/// The default time after an onset to give up on finding its pitch.
+pub const DEFAULT_MAX_PENDING: Duration = Duration::from_millis(300);
+
+/// The quietest peak level that still maps to a velocity above the minimum (dBFS).
+const VELOCITY_FLOOR: f64 = -60.0;
+
+/// A note starting or ending in the input stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteEvent {
+    /// A note started.
+    NoteOn {
+        /// The note that started.
+        note: Note,
+        /// The stream time of the onset.
+        time: Duration,
+        /// How hard the note was played (`1..=127`).
+        velocity: u8,
+    },
+    /// A note ended.
+    NoteOff {
+        /// The note that ended.
+        note: Note,
+        /// The stream time the note ended.
+        time: Duration,
+    },
+}
+
+impl NoteEvent {
+    /// Returns the note of the event.
+    pub fn note(&self) -> Note {
+        match self {
+            NoteEvent::NoteOn { note, .. } | NoteEvent::NoteOff { note, .. } => *note,
+        }
+    }
+
+    /// Returns the stream time of the event.
+    pub fn time(&self) -> Duration {
+        match self {
+            NoteEvent::NoteOn { time, .. } | NoteEvent::NoteOff { time, .. } => *time,
+        }
+    }
+}
+
+/// Returns the MIDI-style velocity (`1..=127`) of the given level.
+pub fn velocity(level: &Level) -> u8 {
+    let ratio = ((level.peak_dbfs() - VELOCITY_FLOOR) / -VELOCITY_FLOOR).clamp(0.0, 1.0);
+    (1.0 + ratio * 126.0).round() as u8
+}
+
+/// Splits the stream of detected notes into note events.
+///
+/// A note starts at an onset once its pitch has settled, or whenever the
+/// detected pitch changes without an onset (e.g. a slide or hammer-on).
+/// It ends at the next onset, when the pitch changes or when it fades out.
+#[derive(Debug, Clone)]
+pub struct NoteSegmenter {
+    /// The time to wait after an onset for the pitch to settle.
+    pub settle: Duration,
+    /// The time after an onset to give up on finding its pitch.
+    pub max_pending: Duration,
+    /// The note that is currently sounding.
+    active: Option<Note>,
+    /// The time and velocity of an onset that is waiting for its pitch.
+    pending: Option<(Duration, u8)>,
+}
+
+impl Default for NoteSegmenter {
+    fn default() -> Self {
+        Self::new(DEFAULT_SETTLE, DEFAULT_MAX_PENDING)
+    }
+}
+
+impl NoteSegmenter {
+    pub fn new(settle: Duration, max_pending: Duration) -> Self {
+        Self {
+            settle,
+            max_pending,
+            active: None,
+            pending: None,
+        }
+    }
+
+    /// Returns the note that is currently sounding.
+    pub fn active(&self) -> Option<Note> {
+        self.active
+    }
+
+    /// Processes the next frame and returns the events it produced.
+    ///
+    /// `onset` is `true` if a note started in the frame, `velocity` is the
+    /// velocity of the frame and `note` the note that is currently detected.
+    pub fn update(
+        &mut self,
+        time: Duration,
+        onset: bool,
+        velocity: u8,
+        note: Option<Note>,
+    ) -> Vec<NoteEvent> {
+        let mut events = Vec::new();
+
+        if onset {
+            if let Some(note) = self.active.take() {
+                events.push(NoteEvent::NoteOff { note, time });
+            }
+            self.pending = Some((time, velocity));
+        }
+
+        if let Some((start, velocity)) = self.pending {
+            let elapsed = time.saturating_sub(start);
+            if elapsed < self.settle {
+                return events;
+            }
+            if let Some(note) = note {
+                events.push(NoteEvent::NoteOn {
+                    note,
+                    time: start,
+                    velocity,
+                });
+                self.active = Some(note);
+                self.pending = None;
+            } else if elapsed > self.max_pending {
+                self.pending = None;
+            }
+            return events;
+        }
+
+        if self.active != note {
+            if let Some(note) = self.active.take() {
+                events.push(NoteEvent::NoteOff { note, time });
+            }
+            if let Some(note) = note {
+                events.push(NoteEvent::NoteOn {
+                    note,
+                    time,
+                    velocity,
+                });
+                self.active = Some(note);
+            }
+        }
+
+        events
+    }
+
+    /// Ends the current note and forgets pending onsets.
+    pub fn clear(&mut self, time: Duration) -> Option<NoteEvent> {
+        self.pending = None;
+        self.active
+            .take()
+            .map(|note| NoteEvent::NoteOff { note, time })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A4: Note = Note::A(4);
+    const B4: Note = Note::B(4);
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn waits_for_pitch_after_onset() {
+        let mut segmenter = NoteSegmenter::default();
+        assert!(segmenter.update(ms(0), true, 100, None).is_empty());
+        assert!(segmenter.update(ms(20), false, 90, Some(A4)).is_empty());
+        assert_eq!(
+            vec![NoteEvent::NoteOn {
+                note: A4,
+                time: ms(0),
+                velocity: 100
+            }],
+            segmenter.update(ms(60), false, 80, Some(A4))
+        );
+        assert_eq!(Some(A4), segmenter.active());
+    }
+
+    #[test]
+    fn separates_repeated_picks() {
+        let mut segmenter = NoteSegmenter::default();
+        segmenter.update(ms(0), true, 100, Some(A4));
+        segmenter.update(ms(60), false, 80, Some(A4));
+
+        assert_eq!(
+            vec![NoteEvent::NoteOff {
+                note: A4,
+                time: ms(500)
+            }],
+            segmenter.update(ms(500), true, 110, Some(A4))
+        );
+        assert_eq!(
+            vec![NoteEvent::NoteOn {
+                note: A4,
+                time: ms(500),
+                velocity: 110
+            }],
+            segmenter.update(ms(560), false, 90, Some(A4))
+        );
+    }
+
+    #[test]
+    fn follows_pitch_changes_without_onset() {
+        let mut segmenter = NoteSegmenter::default();
+        segmenter.update(ms(0), true, 100, Some(A4));
+        segmenter.update(ms(60), false, 80, Some(A4));
+
+        let events = segmenter.update(ms(200), false, 70, Some(B4));
+        assert_eq!(
+            vec![
+                NoteEvent::NoteOff {
+                    note: A4,
+                    time: ms(200)
+                },
+                NoteEvent::NoteOn {
+                    note: B4,
+                    time: ms(200),
+                    velocity: 70
+                }
+            ],
+            events
+        );
+
+        let events = segmenter.update(ms(400), false, 10, None);
+        assert_eq!(
+            vec![NoteEvent::NoteOff {
+                note: B4,
+                time: ms(400)
+            }],
+            events
+        );
+        assert_eq!(None, segmenter.active());
+    }
+
+    #[test]
+    fn drops_onset_without_pitch() {
+        let mut segmenter = NoteSegmenter::default();
+        segmenter.update(ms(0), true, 100, None);
+        assert!(segmenter.update(ms(400), false, 50, None).is_empty());
+        // A note found later starts at its own time
+        assert_eq!(
+            ms(420),
+            segmenter.update(ms(420), false, 50, Some(A4))[0].time()
+        );
+    }
+
+    #[test]
+    fn maps_level_to_velocity() {
+        let level = |peak: f64| Level {
+            rms: peak,
+            peak,
+            clipping: false,
+        };
+        assert_eq!(127, velocity(&level(1.0)));
+        assert_eq!(64, velocity(&level(10.0_f64.powf(-30.0 / 20.0))));
+        assert_eq!(1, velocity(&level(0.0)));
+    }
+}
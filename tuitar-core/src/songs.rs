@@ -1,9 +1,22 @@
+use std::ops::RangeInclusive;
+
+use ratatui_fretboard::tablature::Tablature;
+
 include!(concat!(env!("OUT_DIR"), "/songs.rs"));
 
+impl Song {
+    /// Returns the tablature of the song.
+    ///
+    /// Songs only have pitches, so the fret positions are picked automatically.
+    pub fn tablature(&self, tuning: &[Note], frets: &RangeInclusive<u8>) -> Tablature {
+        Tablature::from_notes(self.notes, tuning.to_vec(), frets)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::StatefulWidget};
-    use ratatui_fretboard::{note::Note, Fretboard, FretboardState};
+    use ratatui_fretboard::{note::STANDARD_TUNING, Fretboard, FretboardState};
 
     use super::*;
 
@@ -60,6 +73,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn smoke_on_the_water_tablature() {
+        let tab = SMOKE_ON_THE_WATER
+            .tablature(&STANDARD_TUNING, &(0..=12))
+            .to_string();
+        let lines: Vec<_> = tab.lines().collect();
+        assert_eq!("G|-0-3", &lines[2][..6]);
+        assert_eq!("D|-0-3", &lines[3][..6]);
+    }
+
     #[test]
     fn render_unscripted_violence() {
         let song = UNSCRIPTED_VIOLENCE.clone();
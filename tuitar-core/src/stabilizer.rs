@@ -0,0 +1,345 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The default time a new note has to be detected before it is shown.
+pub const DEFAULT_ATTACK: Duration = Duration::from_millis(50);
+
+/// The default time a note is held after it stops being detected.
+pub const DEFAULT_RELEASE: Duration = Duration::from_millis(300);
+
+/// The default time window used for the median frequency and confidence.
+pub const DEFAULT_WINDOW: Duration = Duration::from_millis(250);
+
+/// Readings with a confidence below this are considered unreliable.
+pub const LOW_CONFIDENCE: f64 = 0.5;
+
+/// How far past the semitone boundary a frequency still counts as the current note (cents).
+const HYSTERESIS_CENTS: f64 = 15.0;
+
+/// A single detected frequency.
+#[derive(Debug, Clone, Copy)]
+struct Detection {
+    /// When the frequency was detected.
+    time: Instant,
+    /// The detected frequency in Hz.
+    frequency: f64,
+    /// The MIDI note number the frequency was assigned to.
+    key: i32,
+}
+
+/// The note that is currently shown.
+#[derive(Debug, Clone, Copy)]
+struct StableNote {
+    /// The MIDI note number.
+    key: i32,
+    /// The last time the note was detected.
+    last_seen: Instant,
+    /// The last known median frequency.
+    frequency: f64,
+}
+
+/// A stabilized note reading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    /// The median of the recently detected frequencies of the note in Hz.
+    pub frequency: f64,
+    /// How confident the reading is (`0.0..=1.0`).
+    pub confidence: f64,
+}
+
+/// Turns a noisy stream of detected frequencies into a stable note.
+///
+/// A new note is only accepted once it has been detected for the `attack` time
+/// and the current note is held for the `release` time after it stops being
+/// detected, regardless of the frame rate.
+#[derive(Debug, Clone)]
+pub struct NoteStabilizer {
+    /// The time a new note has to be detected before it is shown.
+    pub attack: Duration,
+    /// The time a note is held after it stops being detected.
+    pub release: Duration,
+    /// The time window used for the median frequency and confidence.
+    pub window: Duration,
+    /// The recent detections, oldest first.
+    detections: VecDeque<Detection>,
+    /// The note that is currently shown.
+    current: Option<StableNote>,
+    /// The note that is about to replace the current one and since when.
+    candidate: Option<(i32, Instant)>,
+}
+
+impl Default for NoteStabilizer {
+    fn default() -> Self {
+        Self::new(DEFAULT_ATTACK, DEFAULT_RELEASE)
+    }
+}
+
+impl NoteStabilizer {
+    pub fn new(attack: Duration, release: Duration) -> Self {
+        Self {
+            attack,
+            release,
+            window: DEFAULT_WINDOW,
+            detections: VecDeque::new(),
+            current: None,
+            candidate: None,
+        }
+    }
+
+    /// Adds a detected frequency.
+    pub fn push(&mut self, frequency: f64, now: Instant) {
+        let key = self.classify(frequency);
+        self.detections.push_back(Detection {
+            time: now,
+            frequency,
+            key,
+        });
+        self.prune(now);
+
+        if let Some(current) = self.current.as_mut().filter(|c| c.key == key) {
+            current.last_seen = now;
+            self.candidate = None;
+        } else {
+            let since = match self.candidate {
+                Some((candidate, since)) if candidate == key => since,
+                _ => now,
+            };
+            self.candidate = Some((key, since));
+
+            // The candidate has to be detected at least as often as the current note
+            let dominates = match self.current {
+                Some(current) => self.share(key) >= self.share(current.key),
+                None => true,
+            };
+            if now.duration_since(since) >= self.attack && dominates {
+                self.current = Some(StableNote {
+                    key,
+                    last_seen: now,
+                    frequency,
+                });
+                self.candidate = None;
+            }
+        }
+
+        if let Some(frequency) = self.current.and_then(|c| self.median(c.key)) {
+            if let Some(current) = self.current.as_mut() {
+                current.frequency = frequency;
+            }
+        }
+    }
+
+    /// Marks a frame without a detected frequency.
+    pub fn push_silence(&mut self, now: Instant) {
+        self.candidate = None;
+        self.prune(now);
+    }
+
+    /// Forgets the recent detections, e.g. when a new note is picked.
+    ///
+    /// The current note is kept, but any other note can replace it after
+    /// the attack time instead of having to outweigh its old detections.
+    pub fn retrigger(&mut self) {
+        self.detections.clear();
+        self.candidate = None;
+    }
+
+    /// Forgets everything, including the current note.
+    pub fn clear(&mut self) {
+        self.detections.clear();
+        self.current = None;
+        self.candidate = None;
+    }
+
+    /// Returns the current note reading, if any.
+    pub fn current(&self, now: Instant) -> Option<Reading> {
+        let current = self.current?;
+        let silence = now.saturating_duration_since(current.last_seen);
+        if silence > self.release {
+            return None;
+        }
+
+        // Fade out while the note is being released
+        let decay = if self.release.is_zero() {
+            1.0
+        } else {
+            1.0 - silence.as_secs_f64() / self.release.as_secs_f64()
+        };
+
+        Some(Reading {
+            frequency: self.median(current.key).unwrap_or(current.frequency),
+            confidence: (self.share(current.key) * decay).clamp(0.0, 1.0),
+        })
+    }
+
+    /// Assigns a frequency to a MIDI note number.
+    ///
+    /// Frequencies slightly past the boundary of the current note still count
+    /// as the current note, so that a note played between two semitones does
+    /// not flicker.
+    fn classify(&self, frequency: f64) -> i32 {
+        let position = 69.0 + 12.0 * (frequency / 440.0).log2();
+        if let Some(current) = self.current {
+            let cents = (position - current.key as f64) * 100.0;
+            if cents.abs() <= 50.0 + HYSTERESIS_CENTS {
+                return current.key;
+            }
+        }
+        position.round() as i32
+    }
+
+    /// Removes the detections that are outside of the time window.
+    fn prune(&mut self, now: Instant) {
+        while self
+            .detections
+            .front()
+            .is_some_and(|d| now.saturating_duration_since(d.time) > self.window)
+        {
+            self.detections.pop_front();
+        }
+    }
+
+    /// Returns the ratio of detections in the window that belong to the given note.
+    fn share(&self, key: i32) -> f64 {
+        if self.detections.is_empty() {
+            return 0.0;
+        }
+        let count = self.detections.iter().filter(|d| d.key == key).count();
+        count as f64 / self.detections.len() as f64
+    }
+
+    /// Returns the median frequency of the detections of the given note.
+    fn median(&self, key: i32) -> Option<f64> {
+        let mut frequencies: Vec<f64> = self
+            .detections
+            .iter()
+            .filter(|d| d.key == key)
+            .map(|d| d.frequency)
+            .collect();
+        if frequencies.is_empty() {
+            return None;
+        }
+
+        frequencies.sort_by(|a, b| a.total_cmp(b));
+        // Averages the two middle values for an even number of frequencies
+        let len = frequencies.len();
+        Some((frequencies[(len - 1) / 2] + frequencies[len / 2]) / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A4: f64 = 440.0;
+    const A_SHARP4: f64 = 466.16;
+
+    /// Returns the time at the given number of milliseconds after `start`.
+    fn at(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn waits_for_attack() {
+        let start = Instant::now();
+        let mut stabilizer = NoteStabilizer::default();
+
+        stabilizer.push(A4, start);
+        assert_eq!(None, stabilizer.current(start));
+
+        stabilizer.push(A4, at(start, 30));
+        assert_eq!(None, stabilizer.current(at(start, 30)));
+
+        stabilizer.push(A4, at(start, 60));
+        let reading = stabilizer.current(at(start, 60)).unwrap();
+        assert_eq!(A4, reading.frequency);
+        assert_eq!(1.0, reading.confidence);
+    }
+
+    #[test]
+    fn ignores_spurious_frames() {
+        let start = Instant::now();
+        let mut stabilizer = NoteStabilizer::new(Duration::ZERO, DEFAULT_RELEASE);
+        for i in 0..5 {
+            stabilizer.push(A4, at(start, i * 20));
+        }
+
+        // A single octave error does not replace the note
+        stabilizer.push(A4 * 2.0, at(start, 100));
+        let reading = stabilizer.current(at(start, 100)).unwrap();
+        assert_eq!(A4, reading.frequency);
+        assert!(reading.confidence < 1.0);
+
+        stabilizer.push(A4, at(start, 120));
+        assert_eq!(A4, stabilizer.current(at(start, 120)).unwrap().frequency);
+    }
+
+    #[test]
+    fn switches_to_dominant_note() {
+        let start = Instant::now();
+        let mut stabilizer = NoteStabilizer::default();
+        for i in 0..4 {
+            stabilizer.push(A4, at(start, i * 20));
+        }
+        for i in 4..20 {
+            stabilizer.push(A_SHARP4, at(start, i * 20));
+        }
+        let reading = stabilizer.current(at(start, 380)).unwrap();
+        assert_eq!(A_SHARP4, reading.frequency);
+        assert_eq!(1.0, reading.confidence);
+    }
+
+    #[test]
+    fn applies_hysteresis() {
+        let start = Instant::now();
+        let mut stabilizer = NoteStabilizer::new(Duration::ZERO, DEFAULT_RELEASE);
+        stabilizer.push(A4, start);
+
+        // 55 cents sharp is closer to A#4, but still counts as A4
+        let sharp = A4 * 2.0_f64.powf(55.0 / 1200.0);
+        stabilizer.push(sharp, at(start, 20));
+        let reading = stabilizer.current(at(start, 20)).unwrap();
+        assert_eq!(1.0, reading.confidence);
+        assert_eq!((A4 + sharp) / 2.0, reading.frequency);
+    }
+
+    #[test]
+    fn releases_after_silence() {
+        let start = Instant::now();
+        let mut stabilizer = NoteStabilizer::new(Duration::ZERO, DEFAULT_RELEASE);
+        stabilizer.push(A4, start);
+        stabilizer.push_silence(at(start, 150));
+
+        let reading = stabilizer.current(at(start, 150)).unwrap();
+        assert!(reading.confidence < 1.0);
+
+        stabilizer.push_silence(at(start, 400));
+        assert_eq!(None, stabilizer.current(at(start, 400)));
+    }
+
+    #[test]
+    fn retriggers_new_note() {
+        let start = Instant::now();
+        let mut stabilizer = NoteStabilizer::default();
+        for i in 0..10 {
+            stabilizer.push(A4, at(start, i * 20));
+        }
+
+        stabilizer.retrigger();
+        stabilizer.push(A_SHARP4, at(start, 200));
+        stabilizer.push(A_SHARP4, at(start, 260));
+        assert_eq!(
+            A_SHARP4,
+            stabilizer.current(at(start, 260)).unwrap().frequency
+        );
+    }
+
+    #[test]
+    fn uses_median_frequency() {
+        let start = Instant::now();
+        let mut stabilizer = NoteStabilizer::new(Duration::ZERO, DEFAULT_RELEASE);
+        for (i, frequency) in [439.0, 445.0, 440.0].into_iter().enumerate() {
+            stabilizer.push(frequency, at(start, i as u64 * 20));
+        }
+        assert_eq!(440.0, stabilizer.current(at(start, 40)).unwrap().frequency);
+    }
+}
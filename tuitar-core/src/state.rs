@@ -1,19 +1,50 @@
-use std::collections::VecDeque;
+use std::ops::RangeInclusive;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 use pitchy::Note;
+use ratatui_fretboard::note::{Note as FretboardNote, Spelling, A4_FREQUENCY};
 use tui_big_text::PixelSize;
 
+use crate::chroma::Chroma;
+use crate::level::{Level, DEFAULT_NOISE_GATE};
+use crate::onset::OnsetDetector;
+use crate::segment::{velocity, NoteEvent, NoteSegmenter};
+use crate::stabilizer::{NoteStabilizer, LOW_CONFIDENCE};
 use crate::transform::Transformer;
 
-const DEFAULT_MAX_HISTORY: usize = 2;
 const MIN_FREQ_HZ: f64 = 80.0;
 const MAX_FREQ_HZ: f64 = 1320.0;
 
-#[derive(Debug, Clone)]
-struct NoteHistory {
-    name: String,
-    fundamental_frequency: f64,
+/// The maximum number of note events kept until they are taken.
+const MAX_EVENTS: usize = 256;
+
+/// A stabilized note detected in the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectedNote {
+    /// The frequency of the note in Hz.
+    pub frequency: f64,
+    /// The deviation from the perfectly tuned note in cents.
+    pub cents: f64,
+    /// How confident the detection is (`0.0..=1.0`).
+    pub confidence: f64,
+}
+
+impl DetectedNote {
+    /// Returns the detected note.
+    pub fn note(&self) -> Note {
+        Note::new(self.frequency)
+    }
+
+    /// Returns the closest note that can be shown on the fretboard.
+    pub fn fretboard_note(&self) -> Option<FretboardNote> {
+        FretboardNote::from_frequency(self.frequency, A4_FREQUENCY)
+    }
+
+    /// Returns `true` if the detection is too uncertain to be trusted.
+    pub fn is_uncertain(&self) -> bool {
+        self.confidence < LOW_CONFIDENCE
+    }
 }
 
 /// The application state.
@@ -37,21 +68,52 @@ pub struct State<T: Transformer> {
     /// It takes the center of the frame as reference point.
     pub bottom_padding: u16,
 
-    /// A history of recent notes.
-    note_history: VecDeque<NoteHistory>,
+    /// How note names are written.
+    pub spelling: Spelling,
+
+    /// Turns the detected frequencies into a stable note.
+    ///
+    /// The attack and release times can be configured on it.
+    pub stabilizer: NoteStabilizer,
+
+    /// When the samples were last processed.
+    last_update: Instant,
 
-    /// The maximum number of notes to keep in history.
-    pub max_history: usize,
+    /// Detects the start of new notes.
+    pub onset_detector: OnsetDetector,
+
+    /// Splits the detected notes into note events.
+    pub segmenter: NoteSegmenter,
+
+    /// The note events that have not been taken yet.
+    events: Vec<NoteEvent>,
+
+    /// The stream time, i.e. the duration of all processed samples.
+    time: Duration,
+
+    /// The range of values the input can produce.
+    pub input_range: RangeInclusive<i16>,
+
+    /// The noise gate threshold in dBFS.
+    ///
+    /// Note detection is suppressed while the input level is below it.
+    pub noise_gate: Option<f64>,
+
+    /// The level of the last processed samples.
+    pub level: Level,
 }
 
 impl<T: Transformer> State<T> {
+    /// Creates a new state.
+    ///
+    /// Detected notes are smoothed over time by the [`NoteStabilizer`], whose
+    /// attack and release times can be changed through the `stabilizer` field.
     pub fn new(
         transform: T,
         buffer_size: usize,
         fret_count: u8,
         text_size: PixelSize,
         bottom_padding: u16,
-        max_history: Option<usize>,
     ) -> Self {
         Self {
             transform,
@@ -60,8 +122,16 @@ impl<T: Transformer> State<T> {
             fret_count,
             text_size,
             bottom_padding,
-            note_history: VecDeque::with_capacity(max_history.unwrap_or(DEFAULT_MAX_HISTORY)),
-            max_history: max_history.unwrap_or(DEFAULT_MAX_HISTORY),
+            spelling: Spelling::default(),
+            stabilizer: NoteStabilizer::default(),
+            last_update: Instant::now(),
+            onset_detector: OnsetDetector::default(),
+            segmenter: NoteSegmenter::default(),
+            events: Vec::new(),
+            time: Duration::ZERO,
+            input_range: i16::MIN..=i16::MAX,
+            noise_gate: Some(DEFAULT_NOISE_GATE),
+            level: Level::default(),
         }
     }
 
@@ -69,6 +139,34 @@ impl<T: Transformer> State<T> {
         self.samples = samples.to_vec();
         self.transform.process(samples);
         self.sample_rate = sample_rate;
+        self.level = Level::new(samples, &self.input_range);
+        self.last_update = Instant::now();
+
+        let time = self.time;
+        if sample_rate > 0.0 {
+            self.time += Duration::from_secs_f64(samples.len() as f64 / sample_rate);
+        }
+
+        let onset = self
+            .onset_detector
+            .process(&self.transform.fft_data(), &self.level, time)
+            && self.has_signal();
+        if onset {
+            self.stabilizer.retrigger();
+        }
+
+        self.detect_note(sample_rate);
+        self.segment(time, onset);
+    }
+
+    /// Feeds the fundamental frequency of the processed samples to the stabilizer.
+    fn detect_note(&mut self, sample_rate: f64) {
+        if !self.has_signal() {
+            // Let the last note fade out instead of showing it during silence
+            self.stabilizer.push_silence(self.last_update);
+            return;
+        }
+
         let fundamental_frequency = self.transform.find_fundamental_frequency(sample_rate);
 
         if !(MIN_FREQ_HZ..=MAX_FREQ_HZ).contains(&fundamental_frequency) {
@@ -79,47 +177,68 @@ impl<T: Transformer> State<T> {
                 MIN_FREQ_HZ,
                 MAX_FREQ_HZ
             );
+            self.stabilizer.push_silence(self.last_update);
             return;
         }
 
-        if let Some(name) = Note::new(fundamental_frequency).name() {
-            self.note_history.push_back(NoteHistory {
-                name,
-                fundamental_frequency,
-            });
-            if self.note_history.len() > self.max_history {
-                self.note_history.pop_front();
-            }
-        }
+        self.stabilizer
+            .push(fundamental_frequency, self.last_update);
 
         #[cfg(feature = "logging")]
         log::info!(
             "Sampled {} samples at {:.2} Hz | Fundamental frequency = {:.2} Hz",
-            samples.len(),
+            self.samples.len(),
             sample_rate,
             fundamental_frequency
         );
     }
 
-    /// Returns the last note if all notes in the history are the same.
-    fn get_most_frequent_note(&self) -> Option<f64> {
-        let h = &self.note_history;
-        if h.is_empty() {
-            return None;
+    /// Turns the current note into note events.
+    fn segment(&mut self, time: Duration, onset: bool) {
+        let note = self
+            .get_current_note()
+            .filter(|detected| self.has_signal() && !detected.is_uncertain())
+            .and_then(|detected| detected.fretboard_note());
+
+        let events = self
+            .segmenter
+            .update(time, onset, velocity(&self.level), note);
+        self.events.extend(events);
+
+        // Drop the oldest events if nobody is taking them
+        if self.events.len() > MAX_EVENTS {
+            self.events.drain(..self.events.len() - MAX_EVENTS);
         }
+    }
 
-        let first_name = &h[0].name;
-        if h.iter().all(|e| e.name == *first_name) {
-            Some(h.iter().last()?.fundamental_frequency)
-        } else {
-            None
+    /// Returns the note events since the last call.
+    pub fn take_events(&mut self) -> Vec<NoteEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Returns the stream time, i.e. the duration of all processed samples.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Returns `true` if the input level is above the noise gate.
+    pub fn has_signal(&self) -> bool {
+        !matches!(self.noise_gate, Some(gate) if self.level.rms_dbfs() < gate)
+    }
+
+    /// Returns the pitch class distribution of the processed samples, if there is a signal.
+    pub fn chroma(&self) -> Option<Chroma> {
+        if !self.has_signal() {
+            return None;
         }
+        Chroma::new(&self.transform.fft_data(), self.sample_rate)
     }
 
-    pub fn get_current_note(&self) -> Option<(Note, f64)> {
-        let frequency = self.get_most_frequent_note()?;
+    /// Returns the stabilized note, if any.
+    pub fn get_current_note(&self) -> Option<DetectedNote> {
+        let reading = self.stabilizer.current(self.last_update)?;
 
-        let note = Note::new(frequency);
+        let note = Note::new(reading.frequency);
         let note_name = note.name()?;
 
         let perfect_note = Note::from_str(&note_name).expect("failed to get perfect note");
@@ -129,6 +248,10 @@ impl<T: Transformer> State<T> {
         // cents = 1200 * log2(note.frequency() / target.frequency())
         let cents = 1200.0 * (note.frequency() / perfect_note.frequency()).log2();
 
-        Some((note, cents))
+        Some(DetectedNote {
+            frequency: reading.frequency,
+            cents,
+            confidence: reading.confidence,
+        })
     }
 }
@@ -0,0 +1,398 @@
+use std::{collections::BTreeMap, fmt, ops::RangeInclusive, time::Duration};
+
+use ratatui_fretboard::{
+    note::{Note, STANDARD_TUNING},
+    position::FretPosition,
+};
+
+use crate::game::Target;
+
+/// The level of a position once it is mastered.
+pub const MAX_LEVEL: u8 = 5;
+
+/// The time to play the target before it counts as missed.
+pub const TIME_LIMIT: Duration = Duration::from_secs(6);
+
+/// Slower answers keep a position on its level.
+const SLOW_RESPONSE: Duration = Duration::from_secs(3);
+
+/// What is known about playing a single position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PositionStats {
+    /// The number of times the position was played in time.
+    pub hits: u32,
+    /// The number of times the time ran out.
+    pub misses: u32,
+    /// The average time it took to play the position.
+    pub response_time: Duration,
+    /// How well the position is known (`0..=MAX_LEVEL`).
+    ///
+    /// Every quick answer moves it up a level, every miss back to the first one.
+    pub level: u8,
+    /// The round the position is due again.
+    pub due: u32,
+}
+
+impl PositionStats {
+    /// Returns how well the position is known (`0.0..=1.0`).
+    pub fn mastery(&self) -> f64 {
+        self.level as f64 / MAX_LEVEL as f64
+    }
+
+    /// Records playing the position in the given time, scheduling it further away.
+    fn hit(&mut self, response_time: Duration, round: u32) {
+        let total = self.response_time.saturating_mul(self.hits) + response_time;
+        self.hits += 1;
+        self.response_time = total / self.hits;
+        if response_time <= SLOW_RESPONSE {
+            self.level = (self.level + 1).min(MAX_LEVEL);
+        }
+        // The interval doubles with every level
+        self.due = round + (1 << self.level);
+    }
+
+    /// Records running out of time, asking for the position again soon.
+    fn miss(&mut self, round: u32) {
+        self.misses += 1;
+        self.level = 0;
+        self.due = round + 1;
+    }
+}
+
+/// The statistics of every position that was asked for.
+///
+/// They are written as text, one position per line, so they can be stored
+/// in a file or in flash.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FretboardStats {
+    /// The number of targets asked for so far.
+    round: u32,
+    /// The statistics of the positions.
+    positions: BTreeMap<FretPosition, PositionStats>,
+}
+
+impl FretboardStats {
+    /// Reads the statistics as they are displayed.
+    ///
+    /// Lines that can't be read or are out of range are skipped, losing only those positions.
+    pub fn parse(s: &str) -> Self {
+        let mut stats = Self::default();
+        for line in s.lines() {
+            let fields: Vec<u64> = line
+                .split_whitespace()
+                .map_while(|field| field.parse().ok())
+                .collect();
+            match fields[..] {
+                [round] => {
+                    if let Ok(round) = u32::try_from(round) {
+                        stats.round = round;
+                    }
+                }
+                [string, fret, hits, misses, response_ms, level, due] => {
+                    let position = || {
+                        let string = usize::try_from(string)
+                            .ok()
+                            .filter(|string| *string < STANDARD_TUNING.len())?;
+                        let position = FretPosition::new(string, u8::try_from(fret).ok()?);
+                        let position_stats = PositionStats {
+                            hits: u32::try_from(hits).ok()?,
+                            misses: u32::try_from(misses).ok()?,
+                            response_time: Duration::from_millis(response_ms),
+                            level: u8::try_from(level).ok()?.min(MAX_LEVEL),
+                            due: u32::try_from(due).ok()?,
+                        };
+                        Some((position, position_stats))
+                    };
+                    if let Some((position, position_stats)) = position() {
+                        stats.positions.insert(position, position_stats);
+                    }
+                }
+                _ => {}
+            }
+        }
+        stats
+    }
+
+    /// Returns the number of targets asked for so far.
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    /// Returns the statistics of the position, if it was asked for.
+    pub fn get(&self, position: FretPosition) -> Option<&PositionStats> {
+        self.positions.get(&position)
+    }
+
+    /// Returns the mastery of the positions within the frets that were asked for.
+    pub fn heatmap(&self, frets: &RangeInclusive<u8>) -> Vec<(FretPosition, f64)> {
+        self.positions
+            .iter()
+            .filter(|(position, _)| frets.contains(&position.fret))
+            .map(|(position, stats)| (*position, stats.mastery()))
+            .collect()
+    }
+
+    /// Returns the average mastery of every position within the frets (`0.0..=1.0`).
+    pub fn mastery(&self, frets: &RangeInclusive<u8>) -> f64 {
+        let count = STANDARD_TUNING.len() * frets.clone().count();
+        if count == 0 {
+            return 0.0;
+        }
+        let total: f64 = self.heatmap(frets).iter().map(|(_, mastery)| mastery).sum();
+        total / count as f64
+    }
+}
+
+impl fmt::Display for FretboardStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.round)?;
+        for (position, stats) in &self.positions {
+            writeln!(
+                f,
+                "{} {} {} {} {} {} {}",
+                position.string,
+                position.fret,
+                stats.hits,
+                stats.misses,
+                stats.response_time.as_millis(),
+                stats.level,
+                stats.due
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A fretboard memorization trainer that asks for weak positions more often.
+///
+/// Positions are scheduled like flash cards: every quick answer doubles the
+/// number of rounds until a position is asked for again, and a miss brings it
+/// back right away.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trainer {
+    /// The statistics of the positions.
+    stats: FretboardStats,
+    /// The position to play.
+    target: Target,
+}
+
+impl Trainer {
+    /// Creates a trainer that continues from the statistics.
+    pub fn new(stats: FretboardStats) -> Self {
+        Self {
+            stats,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the statistics of the positions.
+    pub fn stats(&self) -> &FretboardStats {
+        &self.stats
+    }
+
+    /// Returns the position to play, if one was picked.
+    pub fn target(&self) -> Option<FretPosition> {
+        self.target.position()
+    }
+
+    /// Returns the note to play, if one was picked.
+    pub fn target_note(&self) -> Option<Note> {
+        self.target.note()
+    }
+
+    /// Returns how much of the time limit is left (`0.0..=1.0`).
+    pub fn remaining(&self, time: Duration) -> f64 {
+        self.target.remaining(time, TIME_LIMIT)
+    }
+
+    /// Picks the next target within the frets at the given stream time.
+    ///
+    /// Positions that are due are picked at random, the less known ones more
+    /// likely. `pick` returns a random number below the given total weight.
+    /// If none is due, the one due first is picked.
+    pub fn next_target(
+        &mut self,
+        frets: &RangeInclusive<u8>,
+        time: Duration,
+        pick: impl FnOnce(usize) -> usize,
+    ) -> Option<FretPosition> {
+        let mut positions: Vec<FretPosition> = (0..STANDARD_TUNING.len())
+            .flat_map(|string| {
+                frets
+                    .clone()
+                    .map(move |fret| FretPosition::new(string, fret))
+            })
+            .collect();
+        self.target.retain_new(&mut positions);
+        let stats =
+            |position: &FretPosition| self.stats.get(*position).copied().unwrap_or_default();
+        let due: Vec<(FretPosition, usize)> = positions
+            .iter()
+            .filter(|position| stats(position).due <= self.stats.round)
+            .map(|position| (*position, (MAX_LEVEL + 1 - stats(position).level) as usize))
+            .collect();
+
+        let target = if due.is_empty() {
+            positions
+                .iter()
+                .min_by_key(|position| (stats(position).due, stats(position).level))
+                .copied()?
+        } else {
+            let total = due.iter().map(|(_, weight)| weight).sum::<usize>();
+            let mut index = pick(total).min(total - 1);
+            due.iter()
+                .find(|(_, weight)| {
+                    let found = index < *weight;
+                    index = index.saturating_sub(*weight);
+                    found
+                })
+                .map(|(position, _)| *position)?
+        };
+        self.stats.round += 1;
+        self.target.set(target, time);
+        Some(target)
+    }
+
+    /// Plays a note at the given stream time, recording a hit if it is the target.
+    ///
+    /// The note can't tell the positions of a unison apart, so any of them counts.
+    /// Returns the response time on a hit.
+    pub fn play(&mut self, note: Note, time: Duration) -> Option<Duration> {
+        if self.target_note() != Some(note) {
+            return None;
+        }
+        let response_time = self.target.elapsed(time);
+        let target = self.target.take()?;
+        let round = self.stats.round;
+        self.stats
+            .positions
+            .entry(target)
+            .or_default()
+            .hit(response_time, round);
+        Some(response_time)
+    }
+
+    /// Checks the time limit at the given stream time, recording a miss if it ran out.
+    ///
+    /// Returns `true` if the target was missed.
+    pub fn update(&mut self, time: Duration) -> bool {
+        let Some(target) = self.target.expire(time, TIME_LIMIT) else {
+            return false;
+        };
+        let round = self.stats.round;
+        self.stats.positions.entry(target).or_default().miss(round);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedules_positions() {
+        let mut stats = PositionStats::default();
+        stats.hit(Duration::from_secs(1), 10);
+        stats.hit(Duration::from_secs(3), 12);
+        assert_eq!(2, stats.level);
+        assert_eq!(16, stats.due);
+        assert_eq!(Duration::from_secs(2), stats.response_time);
+        // Slow answers don't move up
+        stats.hit(Duration::from_secs(5), 16);
+        assert_eq!(2, stats.level);
+        assert_eq!(0.4, stats.mastery());
+
+        stats.miss(20);
+        assert_eq!(0, stats.level);
+        assert_eq!(21, stats.due);
+        assert_eq!((3, 1), (stats.hits, stats.misses));
+    }
+
+    #[test]
+    fn prefers_weak_positions() {
+        let mut trainer = Trainer::default();
+        let target = trainer.next_target(&(0..=0), Duration::ZERO, |_| 0);
+        assert_eq!(Some(FretPosition::new(0, 0)), target);
+        assert_eq!(Some(Duration::from_secs(1)), {
+            trainer.play(Note::A(2), Duration::from_secs(1));
+            trainer.play(Note::E(2), Duration::from_secs(1))
+        });
+
+        // The known open E string is not due, the others are
+        let target = trainer.next_target(&(0..=0), Duration::from_secs(2), |_| 0);
+        assert_eq!(Some(FretPosition::new(1, 0)), target);
+        assert!(!trainer.update(Duration::from_secs(8)));
+        assert!(trainer.update(Duration::from_secs(9)));
+        let stats = trainer.stats().get(FretPosition::new(1, 0)).unwrap();
+        assert_eq!(1, stats.misses);
+
+        // The open E and A strings are not due yet, the other four weigh 6 each
+        let target = trainer.next_target(&(0..=0), Duration::ZERO, |total| {
+            assert_eq!(24, total);
+            23
+        });
+        assert_eq!(Some(FretPosition::new(5, 0)), target);
+    }
+
+    #[test]
+    fn picks_the_earliest_position_when_none_is_due() {
+        let mut stats = FretboardStats::default();
+        for string in 0..STANDARD_TUNING.len() {
+            stats.positions.insert(
+                FretPosition::new(string, 0),
+                PositionStats {
+                    due: 10 - string as u32,
+                    ..PositionStats::default()
+                },
+            );
+        }
+        let mut trainer = Trainer::new(stats);
+        assert_eq!(
+            Some(FretPosition::new(5, 0)),
+            trainer.next_target(&(0..=0), Duration::ZERO, |_| 0)
+        );
+        assert_eq!(Some(Note::E(4)), trainer.target_note());
+    }
+
+    #[test]
+    fn writes_and_reads_stats() {
+        let mut trainer = Trainer::default();
+        trainer.next_target(&(5..=5), Duration::ZERO, |_| 0);
+        trainer.play(Note::A(2), Duration::from_millis(1500));
+        let stats = trainer.stats();
+        assert_eq!("1\n0 5 1 0 1500 1 3\n", stats.to_string());
+        assert_eq!(*stats, FretboardStats::parse(&stats.to_string()));
+        assert_eq!(
+            vec![(FretPosition::new(0, 5), 0.2)],
+            stats.heatmap(&(0..=12))
+        );
+        assert!(stats.heatmap(&(6..=12)).is_empty());
+        assert_eq!(0.1, stats.mastery(&(5..=6)) * 6.0);
+
+        let damaged = FretboardStats::parse("7\n0 5 x\n1 2 3 0 900 9 4\n");
+        assert_eq!(7, damaged.round());
+        assert_eq!(None, damaged.get(FretPosition::new(0, 5)));
+        assert_eq!(
+            Some(MAX_LEVEL),
+            damaged
+                .get(FretPosition::new(1, 2))
+                .map(|stats| stats.level)
+        );
+    }
+
+    #[test]
+    fn skips_out_of_range_positions() {
+        let stats = FretboardStats::parse(concat!(
+            "4294967296\n",
+            "1 300 1 0 900 1 4\n",
+            "18446744073709551615 2 1 0 900 1 4\n",
+            "6 2 1 0 900 1 4\n",
+            "2 3 1 0 900 1 4\n",
+        ));
+        assert_eq!(0, stats.round());
+        assert_eq!(
+            vec![(FretPosition::new(2, 3), 0.2)],
+            stats.heatmap(&(0..=24))
+        );
+    }
+}
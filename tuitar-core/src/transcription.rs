@@ -0,0 +1,455 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use ratatui_fretboard::note::{Note, A4_FREQUENCY};
+use ratatui_fretboard::position::choose_positions;
+use ratatui_fretboard::tablature::{TabBeat, Tablature};
+
+use crate::segment::NoteEvent;
+
+/// The default tempo of a transcription in beats per minute.
+pub const DEFAULT_TEMPO: f64 = 120.0;
+
+/// The minimum pitch change that is recorded as a bend (cents).
+const MIN_BEND_CENTS: f64 = 5.0;
+
+/// The largest bend that can be recorded (cents).
+///
+/// Matches the default pitch bend range of MIDI synthesizers.
+const MAX_BEND_CENTS: f64 = 200.0;
+
+/// The shortest note in a tablature, in quarter notes (a sixteenth note).
+const TAB_RESOLUTION: f64 = 0.25;
+
+/// A note of a transcription.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscribedNote {
+    /// The played note.
+    pub note: Note,
+    /// The start of the note, relative to the start of the transcription.
+    pub start: Duration,
+    /// How long the note was held.
+    pub duration: Duration,
+    /// How hard the note was played (`1..=127`).
+    pub velocity: u8,
+    /// The pitch deviation in cents over time, relative to the start of the note.
+    pub bends: Vec<(Duration, f64)>,
+}
+
+impl TranscribedNote {
+    /// Returns the end of the note, relative to the start of the transcription.
+    pub fn end(&self) -> Duration {
+        self.start + self.duration
+    }
+}
+
+/// Collects the played notes into a transcription.
+#[derive(Debug, Clone)]
+pub struct Transcription {
+    /// The tempo in beats per minute.
+    pub tempo: f64,
+    /// The stream time the transcription started at.
+    start: Duration,
+    /// The finished notes.
+    notes: Vec<TranscribedNote>,
+    /// The note that is currently being played.
+    active: Option<TranscribedNote>,
+}
+
+impl Transcription {
+    /// Starts a new transcription at the given stream time.
+    pub fn new(start: Duration) -> Self {
+        Self {
+            tempo: DEFAULT_TEMPO,
+            start,
+            notes: Vec::new(),
+            active: None,
+        }
+    }
+
+    pub fn with_tempo(mut self, tempo: f64) -> Self {
+        self.tempo = tempo;
+        self
+    }
+
+    /// Adds a note event.
+    pub fn push_event(&mut self, event: &NoteEvent) {
+        match *event {
+            NoteEvent::NoteOn {
+                note,
+                time,
+                velocity,
+            } => {
+                self.end_note(time);
+                self.active = Some(TranscribedNote {
+                    note,
+                    start: self.relative(time),
+                    duration: Duration::ZERO,
+                    velocity,
+                    bends: Vec::new(),
+                });
+            }
+            NoteEvent::NoteOff { note, time } => {
+                if self
+                    .active
+                    .as_ref()
+                    .is_some_and(|active| active.note == note)
+                {
+                    self.end_note(time);
+                }
+            }
+        }
+    }
+
+    /// Records the detected frequency of the current note as a bend.
+    pub fn push_frequency(&mut self, time: Duration, frequency: f64) {
+        let time = self.relative(time);
+        let Some(active) = self.active.as_mut() else {
+            return;
+        };
+
+        let cents = (1200.0 * (frequency / active.note.frequency(A4_FREQUENCY)).log2())
+            .clamp(-MAX_BEND_CENTS, MAX_BEND_CENTS);
+        let last = active.bends.last().map_or(0.0, |(_, cents)| *cents);
+        if (cents - last).abs() >= MIN_BEND_CENTS {
+            active
+                .bends
+                .push((time.saturating_sub(active.start), cents));
+        }
+    }
+
+    /// Ends the note that is currently being played.
+    pub fn finish(&mut self, time: Duration) {
+        self.end_note(time);
+    }
+
+    /// Returns the finished notes.
+    pub fn notes(&self) -> &[TranscribedNote] {
+        &self.notes
+    }
+
+    /// Returns `true` if no notes were finished yet.
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// Returns the time since the start of the transcription.
+    pub fn elapsed(&self, time: Duration) -> Duration {
+        self.relative(time)
+    }
+
+    /// Returns the tablature of the finished notes.
+    ///
+    /// The length of each note is quantized to sixteenth notes at the tempo
+    /// of the transcription and includes the rest after it.
+    pub fn tablature(&self, tuning: &[Note], frets: &RangeInclusive<u8>) -> Tablature {
+        let notes: Vec<[Note; 1]> = self.notes.iter().map(|n| [n.note]).collect();
+        let positions = choose_positions(&notes, tuning, frets);
+
+        let mut beats = Vec::with_capacity(self.notes.len() + 1);
+        if let Some(first) = self.notes.first() {
+            // Keep the measures aligned to the start of the transcription
+            let rest = self.quarters(first.start);
+            if rest > 0.0 {
+                beats.push(TabBeat::default().with_duration(rest));
+            }
+        }
+        for (i, (note, positions)) in self.notes.iter().zip(positions).enumerate() {
+            let end = self.notes.get(i + 1).map_or(note.end(), |next| next.start);
+            let duration = self
+                .quarters(end.saturating_sub(note.start))
+                .max(TAB_RESOLUTION);
+            beats.push(TabBeat::new(positions).with_duration(duration));
+        }
+
+        Tablature::new(tuning.to_vec()).with_beats(beats)
+    }
+
+    /// Converts a duration to quarter notes, quantized to the tablature resolution.
+    fn quarters(&self, duration: Duration) -> f64 {
+        let quarters = duration.as_secs_f64() * self.tempo / 60.0;
+        (quarters / TAB_RESOLUTION).round() * TAB_RESOLUTION
+    }
+
+    /// Converts a stream time to a time relative to the start.
+    fn relative(&self, time: Duration) -> Duration {
+        time.saturating_sub(self.start)
+    }
+
+    fn end_note(&mut self, time: Duration) {
+        let time = self.relative(time);
+        if let Some(mut note) = self.active.take() {
+            note.duration = time.saturating_sub(note.start);
+            self.notes.push(note);
+        }
+    }
+}
+
+#[cfg(feature = "midi")]
+mod midi {
+    use std::io;
+
+    use midly::num::{u14, u15, u24, u28, u4, u7};
+    use midly::{
+        Format, Header, MetaMessage, MidiMessage, PitchBend, Smf, Timing, TrackEvent,
+        TrackEventKind,
+    };
+
+    use super::*;
+
+    /// The resolution of the MIDI file.
+    const TICKS_PER_BEAT: u16 = 480;
+
+    /// The MIDI channel the notes are written to.
+    const CHANNEL: u8 = 0;
+
+    /// The center value of a MIDI pitch bend, i.e. no bend.
+    const PITCH_BEND_CENTER: f64 = 8192.0;
+
+    impl Transcription {
+        /// Converts the transcription to a single-track Standard MIDI File.
+        pub fn to_midi(&self) -> Smf<'static> {
+            let mut events = vec![(
+                0,
+                TrackEventKind::Meta(MetaMessage::Tempo(u24::new(
+                    (60_000_000.0 / self.tempo).round() as u32,
+                ))),
+            )];
+            // Set the pitch bend range with the RPN 0 controllers
+            let semitones = (MAX_BEND_CENTS / 100.0) as u8;
+            for (controller, value) in [(101, 0), (100, 0), (6, semitones), (38, 0)] {
+                events.push((
+                    0,
+                    midi_event(MidiMessage::Controller {
+                        controller: u7::new(controller),
+                        value: u7::new(value),
+                    }),
+                ));
+            }
+
+            for note in &self.notes {
+                // Notes above G9 can't be written
+                let Some(key) = note.note.midi_number().map(u7::new) else {
+                    continue;
+                };
+                for (offset, cents) in &note.bends {
+                    events.push((self.ticks(note.start + *offset), pitch_bend(*cents)));
+                }
+                events.push((
+                    self.ticks(note.start),
+                    midi_event(MidiMessage::NoteOn {
+                        key,
+                        vel: u7::new(note.velocity.clamp(1, 127)),
+                    }),
+                ));
+                events.push((
+                    self.ticks(note.end()),
+                    midi_event(MidiMessage::NoteOff {
+                        key,
+                        vel: u7::new(0),
+                    }),
+                ));
+                if !note.bends.is_empty() {
+                    events.push((self.ticks(note.end()), pitch_bend(0.0)));
+                }
+            }
+
+            // Stable, so events at the same tick keep their order
+            events.sort_by_key(|(tick, _)| *tick);
+
+            let mut last = 0;
+            let mut track: Vec<TrackEvent<'static>> = events
+                .into_iter()
+                .map(|(tick, kind)| {
+                    let delta = tick - last;
+                    last = tick;
+                    TrackEvent {
+                        delta: u28::new(delta),
+                        kind,
+                    }
+                })
+                .collect();
+            track.push(TrackEvent {
+                delta: u28::new(0),
+                kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+            });
+
+            let mut smf = Smf::new(Header::new(
+                Format::SingleTrack,
+                Timing::Metrical(u15::new(TICKS_PER_BEAT)),
+            ));
+            smf.tracks.push(track);
+            smf
+        }
+
+        /// Writes the transcription as a Standard MIDI File.
+        pub fn write_midi<W: io::Write>(&self, writer: W) -> io::Result<()> {
+            self.to_midi().write_std(writer)
+        }
+
+        /// Converts a time to MIDI ticks.
+        fn ticks(&self, time: Duration) -> u32 {
+            (time.as_secs_f64() * self.tempo / 60.0 * TICKS_PER_BEAT as f64).round() as u32
+        }
+    }
+
+    fn midi_event(message: MidiMessage) -> TrackEventKind<'static> {
+        TrackEventKind::Midi {
+            channel: u4::new(CHANNEL),
+            message,
+        }
+    }
+
+    fn pitch_bend(cents: f64) -> TrackEventKind<'static> {
+        let value = PITCH_BEND_CENTER + cents / MAX_BEND_CENTS * PITCH_BEND_CENTER;
+        midi_event(MidiMessage::PitchBend {
+            bend: PitchBend(u14::new(value.round().clamp(0.0, 16383.0) as u16)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A4: Note = Note::A(4);
+    const B4: Note = Note::B(4);
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn note_on(note: Note, millis: u64) -> NoteEvent {
+        NoteEvent::NoteOn {
+            note,
+            time: ms(millis),
+            velocity: 100,
+        }
+    }
+
+    #[test]
+    fn collects_notes() {
+        let mut transcription = Transcription::new(ms(1000));
+        transcription.push_event(&note_on(A4, 1000));
+        transcription.push_event(&NoteEvent::NoteOff {
+            note: A4,
+            time: ms(1500),
+        });
+        transcription.push_event(&note_on(B4, 1500));
+        transcription.finish(ms(2000));
+
+        let notes: Vec<_> = transcription
+            .notes()
+            .iter()
+            .map(|n| (n.note, n.start, n.duration))
+            .collect();
+        assert_eq!(vec![(A4, ms(0), ms(500)), (B4, ms(500), ms(500))], notes);
+    }
+
+    #[test]
+    fn ignores_unmatched_note_off() {
+        let mut transcription = Transcription::new(ms(0));
+        transcription.push_event(&note_on(A4, 0));
+        transcription.push_event(&NoteEvent::NoteOff {
+            note: B4,
+            time: ms(100),
+        });
+        assert!(transcription.is_empty());
+        transcription.finish(ms(200));
+        assert_eq!(ms(200), transcription.notes()[0].duration);
+    }
+
+    #[test]
+    fn records_bends() {
+        let mut transcription = Transcription::new(ms(0));
+        transcription.push_event(&note_on(A4, 0));
+        transcription.push_frequency(ms(10), 440.5);
+        // A quarter tone sharp
+        transcription.push_frequency(ms(100), 440.0 * 2.0_f64.powf(50.0 / 1200.0));
+        transcription.push_frequency(ms(200), 440.0 * 2.0);
+        transcription.finish(ms(300));
+
+        let bends: Vec<_> = transcription.notes()[0]
+            .bends
+            .iter()
+            .map(|(offset, cents)| (*offset, cents.round()))
+            .collect();
+        assert_eq!(vec![(ms(100), 50.0), (ms(200), MAX_BEND_CENTS)], bends);
+    }
+
+    #[test]
+    fn converts_to_tablature() {
+        use ratatui_fretboard::note::STANDARD_TUNING;
+
+        let mut transcription = Transcription::new(ms(0)).with_tempo(60.0);
+        transcription.push_event(&note_on(Note::E(2), 1000));
+        transcription.push_event(&note_on(Note::A(2), 1480));
+        transcription.finish(ms(4000));
+
+        let tab = transcription.tablature(&STANDARD_TUNING, &(0..=12));
+        let durations: Vec<_> = tab.beats().iter().map(|b| b.duration).collect();
+        assert_eq!(vec![Some(1.0), Some(0.5), Some(2.5)], durations);
+        assert_eq!("E|----0-------|", tab.to_string().lines().last().unwrap());
+    }
+
+    #[cfg(feature = "midi")]
+    #[test]
+    fn converts_to_midi() {
+        use midly::num::{u14, u24, u7};
+        use midly::{MetaMessage, MidiMessage, TrackEventKind};
+
+        let mut transcription = Transcription::new(ms(0)).with_tempo(60.0);
+        transcription.push_event(&note_on(A4, 500));
+        transcription.push_frequency(ms(750), 440.0 * 2.0_f64.powf(100.0 / 1200.0));
+        transcription.finish(ms(1500));
+
+        let smf = transcription.to_midi();
+        let track = &smf.tracks[0];
+        assert_eq!(
+            TrackEventKind::Meta(MetaMessage::Tempo(u24::new(1_000_000))),
+            track[0].kind
+        );
+
+        let notes: Vec<_> = track
+            .iter()
+            .filter_map(|event| match event.kind {
+                TrackEventKind::Midi { message, .. } => match message {
+                    MidiMessage::Controller { .. } => None,
+                    message => Some((event.delta.as_int(), message)),
+                },
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    240,
+                    MidiMessage::NoteOn {
+                        key: u7::new(69),
+                        vel: u7::new(100)
+                    }
+                ),
+                (
+                    120,
+                    MidiMessage::PitchBend {
+                        bend: midly::PitchBend(u14::new(12288))
+                    }
+                ),
+                (
+                    360,
+                    MidiMessage::NoteOff {
+                        key: u7::new(69),
+                        vel: u7::new(0)
+                    }
+                ),
+                (
+                    0,
+                    MidiMessage::PitchBend {
+                        bend: midly::PitchBend(u14::new(8192))
+                    }
+                ),
+            ],
+            notes
+        );
+    }
+}
@@ -1,20 +1,58 @@
 use ratatui::layout::{Alignment, Margin, Offset, Rect};
 use ratatui::style::Color;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::LineGauge;
+use ratatui::widgets::{LineGauge, Paragraph, Wrap};
 use ratatui::Frame;
 use ratatui::{
     style::{Style, Stylize},
     symbols,
     widgets::{Axis, Chart, Dataset, GraphType},
 };
-use ratatui_fretboard::{Fretboard, FretboardState};
+use ratatui_fretboard::note::Note;
+use ratatui_fretboard::{Fretboard, FretboardMarks, FretboardState, Keyboard, KeyboardState};
 use tui_bar_graph::{BarGraph, BarStyle, ColorMode};
 use tui_big_text::BigText;
 
+use crate::harmonics::{self, HarmonicAnalysis};
+use crate::level::LevelMeter;
+use crate::scope::{Oscilloscope, Trigger};
 use crate::state::State;
 use crate::transform::Transformer;
 
+/// Frequency range of the dBFS spectrum in Hz.
+///
+/// Starts just below low E2 and goes high enough to see harmonics but ignore hiss.
+const SPECTRUM_RANGE: (f64, f64) = (80.0, 5000.0);
+
+/// The lowest level shown on the dBFS spectrum.
+const DB_FLOOR: f64 = -60.0;
+
+/// The keys shown in the keyboard view, from low E of a guitar upwards.
+const KEYBOARD_KEYS: std::ops::RangeInclusive<Note> = Note::E(2)..=Note::E(6);
+
+/// The height of the keyboard view.
+const KEYBOARD_HEIGHT: u16 = 5;
+
+/// The widget used to show the detected notes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NoteView {
+    /// A guitar fretboard.
+    #[default]
+    Fretboard,
+    /// A piano keyboard.
+    Keyboard,
+}
+
+impl NoteView {
+    /// Returns the other view.
+    pub fn toggle(self) -> Self {
+        match self {
+            NoteView::Fretboard => NoteView::Keyboard,
+            NoteView::Keyboard => NoteView::Fretboard,
+        }
+    }
+}
+
 pub fn draw_waveform<T: Transformer>(
     frame: &mut Frame<'_>,
     area: Rect,
@@ -72,6 +110,91 @@ pub fn draw_waveform<T: Transformer>(
     frame.render_widget(chart, area);
 }
 
+pub fn draw_oscilloscope<T: Transformer>(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    state: &State<T>,
+    scope: &mut Oscilloscope,
+    bounds: (f64, f64),
+    titles: (&'static str, &'static str),
+) {
+    let fundamental = state.get_current_note().map(|detected| detected.frequency);
+    scope.update(&state.samples, state.sample_rate, fundamental);
+    if scope.sample_rate() <= 0.0 {
+        return;
+    }
+
+    // Time since the trigger in milliseconds
+    let duration = scope.trace().len() as f64 / scope.sample_rate() * 1000.0;
+    let data_points: Vec<(f64, f64)> = scope
+        .trace()
+        .iter()
+        .enumerate()
+        .map(|(i, &sample)| (i as f64 / scope.sample_rate() * 1000.0, sample as f64))
+        .collect();
+
+    let label_count = 5;
+    let x_labels: Vec<String> = (0..label_count)
+        .map(|i| format!("{:.1}", i as f64 * duration / (label_count - 1) as f64))
+        .collect();
+
+    let x_axis = Axis::default()
+        .title(titles.1.red())
+        .style(Style::default().white())
+        .bounds([0.0, duration])
+        .labels(x_labels);
+
+    let bounds = scope.bounds(bounds);
+    let y_axis = Axis::default()
+        .title(titles.0.red())
+        .style(Style::default().white())
+        .bounds([bounds.0, bounds.1])
+        .labels(vec![
+            format!("{:.1}", bounds.0 / 1000.0),
+            format!("{:.1}", (bounds.0 + (bounds.1 - bounds.0) * 0.5) / 1000.0),
+            format!("{:.1}", bounds.1 / 1000.0),
+        ]);
+
+    let level_points = [(0.0, scope.level()), (duration, scope.level())];
+    let mut datasets = vec![Dataset::default()
+        .name("Audio Waveform")
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().white())
+        .data(&data_points)];
+    if scope.trigger != Trigger::Off {
+        datasets.insert(
+            0,
+            Dataset::default()
+                .name("Trigger Level")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().dark_gray())
+                .data(&level_points),
+        );
+    }
+
+    let chart = Chart::new(datasets).x_axis(x_axis).y_axis(y_axis);
+    frame.render_widget(chart, area);
+
+    let trigger_style = if scope.is_triggered() || scope.trigger == Trigger::Off {
+        Style::new().green()
+    } else {
+        Style::new().red()
+    };
+    let mut status = vec![
+        Span::styled(scope.trigger.to_string(), trigger_style),
+        format!(" {}P", scope.periods).white(),
+    ];
+    if scope.auto_scale {
+        status.push(" A".cyan());
+    }
+    if scope.is_held() {
+        status.push(" HOLD".yellow().bold());
+    }
+    frame.render_widget(Line::from(status).alignment(Alignment::Right), area);
+}
+
 pub fn draw_frequency<T: Transformer>(frame: &mut Frame<'_>, area: Rect, state: &State<T>) {
     let data_points = state.transform.fft_data();
 
@@ -113,26 +236,123 @@ pub fn draw_dbfs_spectrum<T: Transformer>(
 ) {
     // FFT mags (linear)
     let mags = state.transform.fft_data();
-    if mags.is_empty() || state.sample_rate <= 0.0 {
+    let Some((points, _)) = dbfs_points(&mags, state.sample_rate) else {
         return;
+    };
+
+    // Line plot (Braille points also fine; pick Line for dB curve)
+    let dataset = Dataset::default()
+        .name("Magnitude (dB)")
+        .graph_type(GraphType::Line)
+        .marker(symbols::Marker::Braille)
+        .style(Style::default().white())
+        .data(&points);
+
+    frame.render_widget(dbfs_chart(vec![dataset], title), area);
+}
+
+pub fn draw_harmonics<T: Transformer>(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    state: &State<T>,
+    count: usize,
+    title: (&'static str, &'static str),
+) {
+    let mags = state.transform.fft_data();
+    let Some((points, ref_mag)) = dbfs_points(&mags, state.sample_rate) else {
+        return;
+    };
+
+    let analysis = state.get_current_note().and_then(|detected| {
+        HarmonicAnalysis::new(&mags, state.sample_rate, detected.frequency, count)
+    });
+
+    // Mark the partials on the same dB scale as the spectrum
+    let markers: Vec<(f64, f64)> = analysis
+        .iter()
+        .flat_map(|analysis| &analysis.harmonics)
+        .filter(|h| (SPECTRUM_RANGE.0..=SPECTRUM_RANGE.1).contains(&h.frequency))
+        .map(|h| (h.frequency, to_db(h.magnitude, ref_mag)))
+        .collect();
+
+    let spectrum = Dataset::default()
+        .name("Magnitude (dB)")
+        .graph_type(GraphType::Line)
+        .marker(symbols::Marker::Braille)
+        .style(Style::default().dark_gray())
+        .data(&points);
+
+    let partials = Dataset::default()
+        .name("Harmonics")
+        .graph_type(GraphType::Scatter)
+        .marker(symbols::Marker::Dot)
+        .style(Style::default().yellow().bold())
+        .data(&markers);
+
+    frame.render_widget(dbfs_chart(vec![spectrum, partials], title), area);
+
+    let Some(analysis) = analysis else {
+        return;
+    };
+
+    let summary = Line::from(vec![
+        "B ".red(),
+        format!("{:.1e}", analysis.inharmonicity).white(),
+        " Ctr ".red(),
+        format_frequency(analysis.spectral_centroid).white(),
+    ]);
+
+    let levels = Line::from(
+        analysis
+            .harmonics
+            .iter()
+            .skip(1)
+            .flat_map(|h| {
+                [
+                    Span::styled(format!("{}:", h.number), Color::Yellow),
+                    Span::raw(format!("{:.0}dB{:+.0}c ", h.level_db, h.deviation_cents)),
+                ]
+            })
+            .collect::<Vec<_>>(),
+    );
+
+    frame.render_widget(
+        Paragraph::new(vec![summary.alignment(Alignment::Right), levels]).wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
+/// Converts a magnitude to dB relative to the given reference, clamped to [`DB_FLOOR`].
+fn to_db(magnitude: f64, reference: f64) -> f64 {
+    let db = 20.0 * (magnitude / reference).max(1e-12).log10();
+    db.clamp(DB_FLOOR, 0.0)
+}
+
+/// Formats a frequency in Hz, switching to kHz above 1000 Hz.
+fn format_frequency(frequency: f64) -> String {
+    if frequency >= 1000.0 {
+        format!("{:.1}k", frequency / 1000.0)
+    } else {
+        format!("{frequency:.0}")
     }
+}
 
-    let fft_size = mags.len();
-    let freq_per_bin = state.sample_rate / fft_size as f64;
+/// Returns the spectrum in [`SPECTRUM_RANGE`] as `(frequency, dB)` points,
+/// along with the peak magnitude used as the 0 dB reference.
+fn dbfs_points(mags: &[f64], sample_rate: f64) -> Option<(Vec<(f64, f64)>, f64)> {
+    let freq_per_bin = harmonics::bin_width(sample_rate, mags.len())?;
 
     // Show 20 Hz .. 20 kHz (clamped to Nyquist)
     // let f_lo = 20.0_f64;
     // let nyquist = state.sample_rate / 2.0;
     // let f_hi = nyquist.min(20_000.0);
-    let f_lo = 80.0; // just below low E2
-    let f_hi = 5000.0; // see harmonics but ignore hiss
-
-    if f_hi <= f_lo {
-        return;
-    }
+    let (f_lo, f_hi) = SPECTRUM_RANGE;
 
     let start_bin = (f_lo / freq_per_bin).ceil() as usize;
-    let end_bin = ((f_hi / freq_per_bin).floor() as usize).min(fft_size.saturating_sub(1));
+    let end_bin = ((f_hi / freq_per_bin).floor() as usize).min(mags.len().saturating_sub(1));
+    if start_bin > end_bin {
+        return None;
+    }
 
     let slice = &mags[start_bin..=end_bin];
 
@@ -140,29 +360,27 @@ pub fn draw_dbfs_spectrum<T: Transformer>(
     let ref_mag = slice.iter().copied().fold(0.0_f64, f64::max).max(1e-12); // avoid log of 0
 
     // Convert to dBFS and clamp floor
-    let db_floor = -60.0_f64;
-    let points: Vec<(f64, f64)> = slice
+    let points = slice
         .iter()
         .enumerate()
-        .map(|(i, m)| {
+        .map(|(i, &m)| {
             let freq = (start_bin + i) as f64 * freq_per_bin;
-            let db = 20.0 * (m / ref_mag).max(1e-12).log10();
-            (freq, db.clamp(db_floor, 0.0))
+            (freq, to_db(m, ref_mag))
         })
         .collect();
 
+    Some((points, ref_mag))
+}
+
+/// Builds a chart with frequency on the x-axis and dB on the y-axis.
+fn dbfs_chart<'a>(datasets: Vec<Dataset<'a>>, title: (&'static str, &'static str)) -> Chart<'a> {
+    let (f_lo, f_hi) = SPECTRUM_RANGE;
+
     // X labels in kHz
     let x_labels = {
         let ticks = 4; // start, 1/3, 2/3, end
         (0..ticks)
-            .map(|i| {
-                let f = f_lo + (f_hi - f_lo) * (i as f64 / (ticks - 1) as f64);
-                if f >= 1000.0 {
-                    format!("{:.1}k", f / 1000.0)
-                } else {
-                    format!("{f:.0}")
-                }
-            })
+            .map(|i| format_frequency(f_lo + (f_hi - f_lo) * (i as f64 / (ticks - 1) as f64)))
             .collect::<Vec<_>>()
     };
 
@@ -176,24 +394,15 @@ pub fn draw_dbfs_spectrum<T: Transformer>(
     let y_axis = Axis::default()
         .title(title.0.red())
         .style(Style::default().white())
-        .bounds([db_floor, 0.0])
+        .bounds([DB_FLOOR, 0.0])
         .labels(vec![
-            format!("{:.0}", db_floor),
+            format!("{:.0}", DB_FLOOR),
             "-40".into(),
             "-20".into(),
             "0".into(),
         ]);
 
-    // Line plot (Braille points also fine; pick Line for dB curve)
-    let dataset = Dataset::default()
-        .name("Magnitude (dB)")
-        .graph_type(GraphType::Line)
-        .marker(symbols::Marker::Braille)
-        .style(Style::default().white())
-        .data(&points);
-
-    let chart = Chart::new(vec![dataset]).x_axis(x_axis).y_axis(y_axis);
-    frame.render_widget(chart, area);
+    Chart::new(datasets).x_axis(x_axis).y_axis(y_axis)
 }
 
 pub fn draw_fretboard<T: Transformer>(
@@ -201,27 +410,47 @@ pub fn draw_fretboard<T: Transformer>(
     area: Rect,
     state: &State<T>,
     fretboard_state: &mut FretboardState,
+    fretboard_marks: FretboardMarks,
+    view: NoteView,
 ) {
-    let fretboard = Fretboard::default();
-
-    if let Some(note) = state
-        .get_current_note()
-        .and_then(|(note, _)| note.try_into().ok())
-    {
-        fretboard_state.set_active_note(note);
+    let mut fretboard = Fretboard::default()
+        .with_spelling(state.spelling)
+        .with_marks(fretboard_marks);
+    let mut keyboard = Keyboard::default();
+
+    if let Some(detected) = state.get_current_note() {
+        if detected.is_uncertain() {
+            fretboard = fretboard.with_active_note_style(Style::new().dark_gray());
+            keyboard = keyboard.with_active_note_style(Style::new().dark_gray());
+        }
+        if let Some(note) = detected.fretboard_note() {
+            fretboard_state.set_active_note(note);
+        }
     }
 
-    frame.render_stateful_widget(&fretboard, area, fretboard_state);
+    match view {
+        NoteView::Fretboard => frame.render_stateful_widget(&fretboard, area, fretboard_state),
+        NoteView::Keyboard => {
+            let mut keyboard_state = KeyboardState::from(&*fretboard_state);
+            keyboard_state.set_keys(KEYBOARD_KEYS);
+            let area = Rect {
+                height: area.height.min(KEYBOARD_HEIGHT),
+                ..area
+            };
+            frame.render_stateful_widget(&keyboard, area, &mut keyboard_state);
+        }
+    }
 
     fretboard_state.clear_active_notes();
 }
 
 pub fn draw_cents<T: Transformer>(frame: &mut Frame<'_>, area: Rect, state: &State<T>) {
-    let Some((note, cents)) = state.get_current_note() else {
+    let Some(detected) = state.get_current_note() else {
         return;
     };
 
-    let cents = cents.clamp(-50.0, 50.0);
+    let note = detected.note();
+    let cents = detected.cents.clamp(-50.0, 50.0);
     let ratio = ((cents + 50.0) / 100.0).clamp(0.0, 1.0);
 
     let label = if cents.abs() < 1.0 {
@@ -232,7 +461,12 @@ pub fn draw_cents<T: Transformer>(frame: &mut Frame<'_>, area: Rect, state: &Sta
         format!("{cents:.1}c")
     };
 
-    let (filled_style, unfilled_style) = if cents < 0.0 {
+    let (filled_style, unfilled_style) = if detected.is_uncertain() {
+        (
+            Style::new().gray().on_dark_gray(),
+            Style::new().dark_gray().on_black(),
+        )
+    } else if cents < 0.0 {
         (
             Style::new().white().on_red().bold(),
             Style::new().gray().on_black(),
@@ -263,34 +497,64 @@ pub fn draw_cents<T: Transformer>(frame: &mut Frame<'_>, area: Rect, state: &Sta
     }
 }
 
+pub fn draw_level_meter<T: Transformer>(frame: &mut Frame<'_>, area: Rect, state: &State<T>) {
+    let meter = LevelMeter::new(state.level).with_noise_gate(state.noise_gate);
+    frame.render_widget(&meter, area);
+}
+
 pub fn draw_note_name<T: Transformer>(frame: &mut Frame<'_>, area: Rect, state: &State<T>) {
-    let Some((note, cents)) = state.get_current_note() else {
+    if !state.has_signal() {
+        let area = area.offset(Offset {
+            x: 0,
+            y: (area.height / 2) as i32,
+        });
+        frame.render_widget(
+            Line::styled("No signal", Style::new().dark_gray().italic())
+                .alignment(Alignment::Center),
+            area,
+        );
         return;
-    };
+    }
 
-    let Some(note_name) = note.name() else {
+    let Some(detected) = state.get_current_note() else {
         return;
     };
 
+    let note = detected.note();
+    let cents = detected.cents;
+    let note_name = match detected.fretboard_note() {
+        Some(note) => note.spelled(state.spelling),
+        None => match note.name() {
+            Some(name) => name.to_string(),
+            None => return,
+        },
+    };
+
     // One character represents 10 cents, max 5 characters
     let padding = (cents.abs() / 10.0).round() as usize;
     let padding = padding.min(5);
     let mut spans = Vec::new();
     if cents.abs() < 1.0 {
         spans.push(Span::raw("["));
-        spans.push(note_name.to_string().green());
+        spans.push(note_name.green());
         spans.push(Span::raw("]"));
     } else if cents > 0.0 {
         spans.push(Span::raw(" ".repeat(padding)));
-        spans.push(note_name.to_string().blue());
+        spans.push(note_name.blue());
         spans.push(Span::styled(".".repeat(padding), Color::Green));
     } else {
         spans.push(Span::styled(".".repeat(padding), Color::Red));
-        spans.push(note_name.to_string().blue());
+        spans.push(note_name.blue());
         spans.push(Span::raw(" ".repeat(padding)));
     };
 
-    let text = vec![Line::from(spans)];
+    let mut line = Line::from(spans);
+    if detected.is_uncertain() {
+        // Dim the whole reading while the detection is unreliable
+        line = line.patch_style(Style::new().dark_gray());
+    }
+
+    let text = vec![line];
     let area = area.offset(Offset {
         x: 0,
         y: (area.height / 2).saturating_sub(state.bottom_padding) as i32,
@@ -304,8 +568,12 @@ pub fn draw_note_name<T: Transformer>(frame: &mut Frame<'_>, area: Rect, state:
     frame.render_widget(big_text, area);
 
     let freq_text = format!("{:.2} Hz", note.frequency());
-    let freq_line =
-        Line::styled(&freq_text, Style::new().bold().white()).alignment(Alignment::Center);
+    let freq_style = if detected.is_uncertain() {
+        Style::new().dark_gray()
+    } else {
+        Style::new().bold().white()
+    };
+    let freq_line = Line::styled(&freq_text, freq_style).alignment(Alignment::Center);
     let text_area = area
         .offset(Offset {
             x: 0,
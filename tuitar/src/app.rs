@@ -1,24 +1,59 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
 use std::sync::mpsc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use ratatui::crossterm::event::{Event, KeyCode};
-use ratatui::layout::{Margin, Offset};
-use ratatui::style::Modifier;
-use ratatui_fretboard::FretboardState;
+use ratatui::layout::{Margin, Offset, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui_fretboard::note::{Spelling, STANDARD_TUNING};
+use ratatui_fretboard::{FretboardMarks, FretboardState};
 use tui_big_text::PixelSize;
 use tuitar_core::fps::FpsWidget;
+use tuitar_core::harmonics::DEFAULT_HARMONIC_COUNT;
+use tuitar_core::level::DEFAULT_NOISE_GATE;
+use tuitar_core::scope::Oscilloscope;
+use tuitar_core::segment::NoteEvent;
 use tuitar_core::state::State;
+use tuitar_core::trainer::{FretboardStats, Trainer, TIME_LIMIT};
+use tuitar_core::transcription::Transcription;
 use tuitar_core::ui::*;
 
 use crate::input::Recorder;
 use crate::transform::Transform;
 
+/// The amount to move the trigger level with a single key press.
+const TRIGGER_LEVEL_STEP: f64 = 512.0;
+
+/// The amount to move the noise gate threshold with a single key press (dB).
+const NOISE_GATE_STEP: f64 = 3.0;
+
+/// The highest fret used for the exported tablature.
+const MAX_TAB_FRET: u8 = 24;
+
+/// The file in the output directory the trainer statistics are kept in.
+const TRAINER_STATS_FILE: &str = "tuitar-trainer.txt";
+
 pub struct Application {
     pub is_running: bool,
     pub state: State<Transform>,
     pub receiver: mpsc::Receiver<Vec<i16>>,
     pub recorder: Recorder,
     pub fps_widget: FpsWidget,
+    pub oscilloscope: Oscilloscope,
+    /// The transcription that is being recorded.
+    pub transcription: Option<Transcription>,
+    /// The fretboard memorization trainer, while it is running.
+    pub trainer: Option<Trainer>,
+    /// The directory to save the transcriptions and trainer statistics to.
+    pub output_dir: PathBuf,
+    /// The result of the last save.
+    status: Option<Line<'static>>,
     tab: usize,
+    /// Whether the notes are shown on a fretboard or a keyboard.
+    note_view: NoteView,
 }
 
 impl Default for Application {
@@ -41,7 +76,6 @@ impl Application {
             12,
             PixelSize::Full,
             5,
-            Some(5),
         );
 
         Self {
@@ -52,7 +86,13 @@ impl Application {
             fps_widget: FpsWidget::default()
                 .with_label(true)
                 .with_style(Modifier::ITALIC),
+            oscilloscope: Oscilloscope::default(),
+            transcription: None,
+            trainer: None,
+            output_dir: PathBuf::from("."),
+            status: None,
             tab: 0,
+            note_view: NoteView::default(),
         }
     }
 
@@ -60,15 +100,153 @@ impl Application {
         self.recorder.start();
     }
 
+    /// Processes the received samples and records the detected notes.
+    pub fn process_samples(&mut self, samples: &[i16]) {
+        self.state
+            .process_samples(samples, self.recorder.sample_rate() as f64);
+
+        let events = self.state.take_events();
+        if let Some(trainer) = self.trainer.as_mut() {
+            for event in &events {
+                if let NoteEvent::NoteOn { note, time, .. } = event {
+                    trainer.play(*note, *time);
+                }
+            }
+            let time = self.state.time();
+            trainer.update(time);
+            if trainer.target().is_none() {
+                trainer.next_target(&FretboardState::default().frets, time, |total| {
+                    fastrand::usize(..total)
+                });
+            }
+        }
+        if let Some(transcription) = self.transcription.as_mut() {
+            for event in &events {
+                transcription.push_event(event);
+            }
+            if let Some(detected) = self.state.get_current_note() {
+                transcription.push_frequency(self.state.time(), detected.frequency);
+            }
+        }
+    }
+
+    /// Starts transcribing the played notes or saves the current transcription.
+    pub fn toggle_transcription(&mut self) {
+        match self.transcription.take() {
+            Some(mut transcription) => {
+                transcription.finish(self.state.time());
+                self.status = Some(match self.save_transcription(&transcription) {
+                    Ok(path) => Line::styled(
+                        format!("Saved {} (.mid/.txt)", path.display()),
+                        Style::new().dark_gray().italic(),
+                    ),
+                    Err(e) => Line::styled(format!("Failed to save: {e}"), Style::new().red()),
+                });
+            }
+            None => {
+                self.transcription = Some(Transcription::new(self.state.time()));
+                self.status = None;
+            }
+        }
+    }
+
+    /// Writes the transcription to a new MIDI file and tablature text file in the output directory.
+    ///
+    /// Returns the path of the files without the extension.
+    fn save_transcription(&self, transcription: &Transcription) -> std::io::Result<PathBuf> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = self.output_dir.join(format!("tuitar-{timestamp}"));
+        transcription.write_midi(BufWriter::new(File::create(path.with_extension("mid"))?))?;
+
+        let tablature = transcription.tablature(&STANDARD_TUNING, &(0..=MAX_TAB_FRET));
+        fs::write(path.with_extension("txt"), tablature.to_string())?;
+        Ok(path)
+    }
+
+    /// Starts the trainer from the saved statistics, or stops and saves them.
+    pub fn toggle_trainer(&mut self) {
+        let path = self.output_dir.join(TRAINER_STATS_FILE);
+        match self.trainer.take() {
+            Some(trainer) => {
+                self.status = Some(match fs::write(&path, trainer.stats().to_string()) {
+                    Ok(()) => Line::styled(
+                        format!("Saved {}", path.display()),
+                        Style::new().dark_gray().italic(),
+                    ),
+                    Err(e) => Line::styled(format!("Failed to save: {e}"), Style::new().red()),
+                });
+            }
+            None => {
+                // Start from scratch if there are no statistics yet
+                let stats = fs::read_to_string(&path).unwrap_or_default();
+                self.trainer = Some(Trainer::new(FretboardStats::parse(&stats)));
+                self.status = None;
+            }
+        }
+    }
+
+    /// Stops the application, saving the transcription that is being recorded
+    /// and the trainer statistics.
+    pub fn quit(&mut self) {
+        if self.transcription.is_some() {
+            self.toggle_transcription();
+        }
+        if self.trainer.is_some() {
+            self.toggle_trainer();
+        }
+        self.is_running = false;
+    }
+
     pub fn switch_tab(&mut self) {
-        self.tab = (self.tab + 1) % 3;
+        self.tab = (self.tab + 1) % 4;
+    }
+
+    pub fn adjust_noise_gate(&mut self, delta: f64) {
+        self.state.noise_gate = self
+            .state
+            .noise_gate
+            .map(|gate| (gate + delta).clamp(-90.0, 0.0));
+    }
+
+    pub fn toggle_noise_gate(&mut self) {
+        self.state.noise_gate = match self.state.noise_gate {
+            Some(_) => None,
+            None => Some(DEFAULT_NOISE_GATE),
+        };
+    }
+
+    pub fn toggle_spelling(&mut self) {
+        self.state.spelling = match self.state.spelling {
+            Spelling::Sharps => Spelling::Flats,
+            _ => Spelling::Sharps,
+        };
     }
 
     pub fn handle_event(&mut self, event: Event) {
         if let Event::Key(key) = event {
             match key.code {
                 KeyCode::Tab => self.switch_tab(),
-                KeyCode::Char('q') | KeyCode::Esc => self.is_running = false,
+                KeyCode::Char('t') => self.oscilloscope.next_trigger(),
+                KeyCode::Char('+') => self.oscilloscope.increase_periods(),
+                KeyCode::Char('-') => self.oscilloscope.decrease_periods(),
+                KeyCode::Char('a') => {
+                    self.oscilloscope.auto_scale = !self.oscilloscope.auto_scale;
+                }
+                KeyCode::Char(' ') => self.oscilloscope.toggle_hold(),
+                KeyCode::Up => self.oscilloscope.adjust_trigger_level(TRIGGER_LEVEL_STEP),
+                KeyCode::Down => self.oscilloscope.adjust_trigger_level(-TRIGGER_LEVEL_STEP),
+                KeyCode::Char('0') => self.oscilloscope.reset_trigger_level(),
+                KeyCode::Char('[') => self.adjust_noise_gate(-NOISE_GATE_STEP),
+                KeyCode::Char(']') => self.adjust_noise_gate(NOISE_GATE_STEP),
+                KeyCode::Char('g') => self.toggle_noise_gate(),
+                KeyCode::Char('r') => self.toggle_transcription(),
+                KeyCode::Char('m') => self.toggle_trainer(),
+                KeyCode::Char('k') => self.note_view = self.note_view.toggle(),
+                KeyCode::Char('b') => self.toggle_spelling(),
+                KeyCode::Char('q') | KeyCode::Esc => self.quit(),
                 _ => {}
             }
         }
@@ -87,27 +265,92 @@ impl Application {
         });
 
         match self.tab {
-            0 => draw_waveform(
+            0 => draw_oscilloscope(
                 frame,
                 area,
                 &self.state,
+                &mut self.oscilloscope,
                 (i16::MIN as f64, i16::MAX as f64),
-                ("Amplitudes", "Time(s)"),
+                ("Amplitudes", "Time(ms)"),
             ),
             1 => {
                 draw_frequency(frame, area, &self.state);
                 draw_note_name(frame, area, &self.state);
             }
             2 => draw_dbfs_spectrum(frame, area, &self.state, ("Level (dBFS)", "Frequency (Hz)")),
+            3 => draw_harmonics(
+                frame,
+                area,
+                &self.state,
+                DEFAULT_HARMONIC_COUNT,
+                ("Level (dBFS)", "Frequency (Hz)"),
+            ),
             _ => {}
         }
 
-        let fretboard_width = 51;
+        // The keyboard takes as much width as it can get
+        let fretboard_width = match self.note_view {
+            NoteView::Fretboard => 51,
+            NoteView::Keyboard => frame.area().width as i32,
+        };
         let mut area = frame.area().offset(Offset {
             x: (frame.area().width as i32 - fretboard_width) / 2,
             y: (frame.area().height / 2) as i32 + 4,
         });
         area.width = fretboard_width.try_into().unwrap_or(0);
-        draw_fretboard(frame, area, &self.state, &mut FretboardState::default());
+        let mut fretboard_state = FretboardState::default();
+        let mut fretboard_marks = FretboardMarks::default();
+        if let Some(trainer) = &self.trainer {
+            if let (Some(note), Some(position)) = (trainer.target_note(), trainer.target()) {
+                fretboard_state.set_ghost_note(note);
+                fretboard_marks.set_ghost_positions(vec![position]);
+            }
+            fretboard_marks.set_heatmap(trainer.stats().heatmap(&fretboard_state.frets));
+        }
+        draw_fretboard(
+            frame,
+            area,
+            &self.state,
+            &mut fretboard_state,
+            fretboard_marks,
+            self.note_view,
+        );
+
+        let area = frame.area();
+        let meter_area = Rect::new(
+            area.x,
+            area.bottom().saturating_sub(1),
+            area.width.min(32),
+            1,
+        );
+        draw_level_meter(frame, meter_area, &self.state);
+
+        let status = match &self.transcription {
+            Some(transcription) => {
+                let elapsed = transcription.elapsed(self.state.time()).as_secs();
+                Some(Line::styled(
+                    format!("● REC {:02}:{:02}", elapsed / 60, elapsed % 60),
+                    Style::new().red().bold(),
+                ))
+            }
+            None => match &self.trainer {
+                Some(trainer) => Some(Line::styled(
+                    format!(
+                        "● TRAIN {:.0}% {:.0}s",
+                        trainer.stats().mastery(&FretboardState::default().frets) * 100.0,
+                        trainer.remaining(self.state.time()) * TIME_LIMIT.as_secs_f64()
+                    ),
+                    Style::new().cyan().bold(),
+                )),
+                None => self.status.clone(),
+            },
+        };
+        if let Some(status) = status {
+            let x = meter_area.right() + 1;
+            frame.render_widget(
+                status,
+                Rect::new(x, meter_area.y, area.right().saturating_sub(x), 1),
+            );
+        }
     }
 }
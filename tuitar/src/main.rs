@@ -1,23 +1,58 @@
+use std::{env, path::PathBuf, process};
+
 use ratatui::crossterm::event;
 use tuitar::app::Application;
 
+const USAGE: &str = "Usage: tuitar [-o | --output-dir <DIR>]
+
+Options:
+  -o, --output-dir <DIR>  Save recordings and trainer statistics to DIR [default: .]
+  -h, --help              Print this help";
+
+/// Returns the output directory given on the command line, exiting on invalid arguments.
+fn parse_output_dir() -> PathBuf {
+    let mut output_dir = PathBuf::from(".");
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output-dir" => match args.next() {
+                Some(dir) => output_dir = PathBuf::from(dir),
+                None => exit_with_usage(&format!("{arg} needs a directory")),
+            },
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ => exit_with_usage(&format!("unexpected argument '{arg}'")),
+        }
+    }
+    if !output_dir.is_dir() {
+        exit_with_usage(&format!("{} is not a directory", output_dir.display()));
+    }
+    output_dir
+}
+
+fn exit_with_usage(error: &str) -> ! {
+    eprintln!("error: {error}\n\n{USAGE}");
+    process::exit(2);
+}
+
 fn main() {
+    let output_dir = parse_output_dir();
     let mut app = Application::new();
+    app.output_dir = output_dir;
     app.start_recording();
 
     let mut terminal = ratatui::init();
-    let mut samples = Vec::new();
 
     while app.is_running {
-        app.state
-            .process_samples(&samples, app.recorder.sample_rate() as f64);
+        // Process every received buffer once, so that the stream time stays accurate
+        while let Ok(samples) = app.receiver.try_recv() {
+            app.process_samples(&samples);
+        }
 
         terminal.draw(|frame| app.render(frame)).unwrap();
 
-        if let Ok(v) = app.receiver.try_recv() {
-            samples = v;
-        }
-
         if event::poll(std::time::Duration::from_millis(16)).unwrap() {
             let event = event::read().unwrap();
             app.handle_event(event);